@@ -2,11 +2,16 @@
 
 use linera_sdk::views::ViewError;
 use linera_sdk::{
-    linera_base_types::{Amount, Timestamp, WithContractAbi},
-    views::View,
+    linera_base_types::{Amount, ChainId, Timestamp, WithContractAbi},
+    views::{RootView, View},
     Contract, ContractRuntime,
 };
+use predictive_manager::pricing::{
+    constant_product_buy_cost, constant_product_sell_proceeds, lmsr_buy_cost, lmsr_cost,
+    lmsr_liquidity_parameter, lmsr_sell_proceeds, mul_div, ONE,
+};
 use predictive_manager::state::*;
+use predictive_manager::{OperationError, OperationReceipt};
 use std::collections::BTreeMap;
 use thiserror::Error;
 
@@ -44,10 +49,113 @@ pub enum ContractError {
     NotResolved,
     #[error("insufficient level")]
     InsufficientLevel,
+    #[error("invalid price")]
+    InvalidPrice,
+    #[error("order not found")]
+    OrderNotFound,
+    #[error("no matching orders")]
+    NoMatchingOrders,
+    #[error("order would cross your own resting order")]
+    SelfCrossingOrder,
+    #[error("invalid deadline")]
+    InvalidDeadline,
+    #[error("resolution time not reached")]
+    ResolutionTooEarly,
+    #[error("slippage limit exceeded")]
+    SlippageExceeded,
+    #[error("trade deadline passed")]
+    DeadlineExpired,
+    #[error("market is resolved by its price oracle")]
+    OracleMarket,
+    #[error("market has no resolution open to challenge")]
+    NotDisputable,
+    #[error("market is not disputed")]
+    NotDisputed,
+    #[error("already voted")]
+    AlreadyVoted,
+    #[error("invalid game config")]
+    InvalidConfig,
+    #[error("remote buys need a max payment to escrow")]
+    MaxPaymentRequired,
+    #[error("invalid transfer")]
+    InvalidTransfer,
+    #[error("invalid airdrop")]
+    InvalidAirdrop,
+    #[error("player has unpaid debt")]
+    InDebt,
+    #[error("stake outside the period's limits")]
+    InvalidStake,
+    #[error("asset not listed")]
+    UnknownAsset,
     #[error(transparent)]
     View(#[from] ViewError),
 }
 
+impl From<ContractError> for OperationError {
+    fn from(error: ContractError) -> Self {
+        match error {
+            ContractError::Unauthorized => OperationError::Unauthorized,
+            ContractError::PlayerAlreadyExists => OperationError::PlayerAlreadyExists,
+            ContractError::DailyRewardAlreadyClaimed => OperationError::DailyRewardAlreadyClaimed,
+            ContractError::InsufficientBalance => OperationError::InsufficientBalance,
+            ContractError::MarketNotActive => OperationError::MarketNotActive,
+            ContractError::InvalidOutcome => OperationError::InvalidOutcome,
+            ContractError::PlayerNotFound => OperationError::PlayerNotFound,
+            ContractError::MarketNotFound => OperationError::MarketNotFound,
+            ContractError::GuildNotFound => OperationError::GuildNotFound,
+            ContractError::AlreadyInGuild => OperationError::AlreadyInGuild,
+            ContractError::NotGuildMember => OperationError::NotGuildMember,
+            ContractError::NotAdmin => OperationError::NotAdmin,
+            ContractError::NotResolved => OperationError::NotResolved,
+            ContractError::InsufficientLevel => OperationError::InsufficientLevel,
+            ContractError::InvalidPrice => OperationError::InvalidPrice,
+            ContractError::OrderNotFound => OperationError::OrderNotFound,
+            ContractError::NoMatchingOrders => OperationError::NoMatchingOrders,
+            ContractError::SelfCrossingOrder => OperationError::SelfCrossingOrder,
+            ContractError::InvalidDeadline => OperationError::InvalidDeadline,
+            ContractError::ResolutionTooEarly => OperationError::ResolutionTooEarly,
+            ContractError::SlippageExceeded => OperationError::SlippageExceeded,
+            ContractError::DeadlineExpired => OperationError::DeadlineExpired,
+            ContractError::OracleMarket => OperationError::OracleMarket,
+            ContractError::NotDisputable => OperationError::NotDisputable,
+            ContractError::NotDisputed => OperationError::NotDisputed,
+            ContractError::AlreadyVoted => OperationError::AlreadyVoted,
+            ContractError::InvalidConfig => OperationError::InvalidConfig,
+            ContractError::MaxPaymentRequired => OperationError::MaxPaymentRequired,
+            ContractError::InvalidTransfer => OperationError::InvalidTransfer,
+            ContractError::InvalidAirdrop => OperationError::InvalidAirdrop,
+            ContractError::InDebt => OperationError::InDebt,
+            ContractError::InvalidStake => OperationError::InvalidStake,
+            ContractError::UnknownAsset => OperationError::UnknownAsset,
+            ContractError::View(error) => OperationError::View(error.to_string()),
+        }
+    }
+}
+
+/// Limits a player sets on a trade, so it fails instead of filling at an unexpected price
+#[derive(Debug, Clone, Copy, Default)]
+struct TradeLimits {
+    max_payment: Option<Amount>, // Most points (or shares) the player gives up, fee included
+    min_received: Option<Amount>, // Fewest points (or shares) the player receives, net of the fee
+    deadline: Option<Timestamp>, // Latest time the trade may execute
+}
+
+impl TradeLimits {
+    /// Check a trade's terms against the limits, before any state is changed
+    fn check(&self, paid: Amount, received: Amount) -> Result<(), ContractError> {
+        if self
+            .max_payment
+            .is_some_and(|max_payment| paid > max_payment)
+            || self
+                .min_received
+                .is_some_and(|min_received| received < min_received)
+        {
+            return Err(ContractError::SlippageExceeded);
+        }
+        Ok(())
+    }
+}
+
 // ============================================================================
 // Contract Implementation
 // ============================================================================
@@ -55,6 +163,14 @@ pub enum ContractError {
 pub struct PredictionMarketContract {
     state: PredictionMarketState,
     runtime: ContractRuntime<Self>,
+    outbox: Vec<OutgoingMessage>, // Prepared since the last checkpoint, discarded by a rollback
+}
+
+/// A message held back until the state changes that prepared it are kept
+struct OutgoingMessage {
+    message: Message,
+    destination: ChainId,
+    tracked: bool, // Authenticated and tracked, so a rejection bounces it back
 }
 
 linera_sdk::contract!(PredictionMarketContract);
@@ -73,7 +189,11 @@ impl Contract for PredictionMarketContract {
         let state = PredictionMarketState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        PredictionMarketContract { state, runtime }
+        PredictionMarketContract {
+            state,
+            runtime,
+            outbox: Vec::new(),
+        }
     }
 
     async fn instantiate(&mut self, config: GameConfig) {
@@ -85,9 +205,14 @@ impl Contract for PredictionMarketContract {
                 final_config.admin = Some(deployer);
             }
         }
+        // Rejecting the instantiation is the only way to refuse a config here
+        assert!(
+            Self::config_valid(&final_config),
+            "Invalid game config: {final_config:?}"
+        );
 
         self.state.config.set(final_config);
-        self.state.total_supply.set(Amount::ZERO);
+        self.state.supply.set(SupplyComponents::default());
         self.state.next_market_id.set(0);
         let _ = self.initialize_achievements().await;
         self.state.leaderboard.set(Leaderboard {
@@ -113,74 +238,348 @@ impl Contract for PredictionMarketContract {
         // Register this chain if not already registered (for cross-chain coordination)
         self.ensure_chain_registered().await;
 
-        match operation {
-            predictive_manager::Operation::RegisterPlayer { display_name } => {
-                let _ = self
-                    .register_player(player_id, display_name, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::UpdateProfile { display_name } => {
-                let _ = self.update_player_profile(player_id, display_name).await;
-            }
-            predictive_manager::Operation::ClaimDailyReward => {
-                let _ = self.claim_daily_reward(player_id, current_time).await;
-            }
+        // Close markets past their close time and pay out resolutions whose dispute period
+        // has ended before anything else runs, so the operation can't roll them back
+        self.process_market_closes(current_time).await;
+        self.process_resolution_deadlines(current_time).await;
+
+        // A failed operation rolls back to here, keeping the bookkeeping above
+        self.checkpoint().await;
+
+        let result = match operation {
+            predictive_manager::Operation::RegisterPlayer { display_name } => self
+                .register_player(player_id, display_name, current_time)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::UpdateProfile { display_name } => self
+                .update_player_profile(player_id, display_name)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::ClaimDailyReward => self
+                .claim_daily_reward(player_id, current_time)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::TransferPoints { to, amount, memo } => self
+                .transfer_points(player_id, to, amount, memo)
+                .await
+                .map(|()| OperationReceipt::default()),
             predictive_manager::Operation::CreateMarket {
                 title,
                 amount,
                 fee_percent,
-            } => {
-                let _ = self
-                    .create_market(player_id, title, amount, fee_percent, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::BuyShares { market_id, amount } => {
-                let _ = self
-                    .buy_shares(player_id, market_id, amount, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::SellShares { market_id, amount } => {
-                let _ = self
-                    .sell_shares(player_id, market_id, amount, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::MintPoints { amount } => {
-                let _ = self.mint_points(player_id, amount).await;
-            }
-            predictive_manager::Operation::CreateGuild { name } => {
-                let _ = self.create_guild(player_id, name, current_time).await;
-            }
-            predictive_manager::Operation::JoinGuild { guild_id } => {
-                let _ = self.join_guild(player_id, guild_id).await;
-            }
+                outcomes,
+                market_type,
+                close_time,
+                resolution_time,
+                oracle,
+            } => self
+                .create_market(
+                    player_id,
+                    title,
+                    amount,
+                    fee_percent,
+                    outcomes,
+                    market_type,
+                    close_time,
+                    resolution_time,
+                    oracle,
+                    current_time,
+                )
+                .await
+                .map(|(market_id, creation_cost)| OperationReceipt {
+                    market_id: Some(market_id),
+                    fees_charged: creation_cost,
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::BuyShares {
+                market_id,
+                chain_id,
+                outcome_id,
+                amount,
+                max_payment,
+                min_received,
+                deadline,
+            } => self
+                .buy_shares(
+                    player_id,
+                    market_id,
+                    chain_id,
+                    outcome_id,
+                    amount,
+                    TradeLimits {
+                        max_payment,
+                        min_received,
+                        deadline,
+                    },
+                    current_time,
+                )
+                .await
+                .map(|fee| OperationReceipt {
+                    market_id: Some(market_id),
+                    fees_charged: fee,
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::SellShares {
+                market_id,
+                chain_id,
+                outcome_id,
+                amount,
+                max_payment,
+                min_received,
+                deadline,
+            } => self
+                .sell_shares(
+                    player_id,
+                    market_id,
+                    chain_id,
+                    outcome_id,
+                    amount,
+                    TradeLimits {
+                        max_payment,
+                        min_received,
+                        deadline,
+                    },
+                    current_time,
+                )
+                .await
+                .map(|fee| OperationReceipt {
+                    market_id: Some(market_id),
+                    fees_charged: fee,
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::CloseMarket { market_id } => self
+                .close_market(player_id, market_id)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::CancelMarket { market_id } => self
+                .cancel_market(player_id, market_id)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::ResolveMarket {
+                market_id,
+                winning_outcome,
+            } => self
+                .resolve_market(player_id, market_id, winning_outcome)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::ChallengeResolution {
+                market_id,
+                outcome_id,
+            } => self
+                .challenge_resolution(player_id, market_id, outcome_id, current_time)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::VoteOnDispute { market_id, uphold } => self
+                .vote_on_dispute(player_id, market_id, uphold, current_time)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::SettleDispute { market_id, uphold } => self
+                .settle_dispute(player_id, market_id, uphold)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::AddLiquidity { market_id, amount } => self
+                .add_liquidity(player_id, market_id, amount)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::WithdrawLiquidity { market_id, shares } => self
+                .withdraw_liquidity(player_id, market_id, shares)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::PlaceLimitOrder {
+                market_id,
+                outcome_id,
+                side,
+                price,
+                quantity,
+            } => self
+                .place_limit_order(
+                    player_id,
+                    market_id,
+                    outcome_id,
+                    side,
+                    price,
+                    quantity,
+                    current_time,
+                )
+                .await
+                .map(|(order_id, filled)| OperationReceipt {
+                    market_id: Some(market_id),
+                    order_id: Some(order_id),
+                    filled_quantity: Some(filled),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::PlaceMarketOrder {
+                market_id,
+                outcome_id,
+                side,
+                quantity,
+            } => self
+                .place_market_order(player_id, market_id, outcome_id, side, quantity)
+                .await
+                .map(|filled| OperationReceipt {
+                    market_id: Some(market_id),
+                    filled_quantity: Some(filled),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::CancelOrder {
+                market_id,
+                order_id,
+            } => self
+                .cancel_order(player_id, market_id, order_id)
+                .await
+                .map(|()| OperationReceipt {
+                    market_id: Some(market_id),
+                    order_id: Some(order_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::MintPoints { recipient, amount } => self
+                .mint_points(player_id, recipient, amount)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::Airdrop { credits } => self
+                .airdrop(player_id, credits)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::SpendTreasury {
+                recipient,
+                amount,
+                memo,
+            } => self
+                .spend_treasury(player_id, recipient, amount, memo)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::CreateGuild { name } => self
+                .create_guild(player_id, name, current_time)
+                .await
+                .map(|guild_id| OperationReceipt {
+                    guild_id: Some(guild_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::JoinGuild { guild_id } => self
+                .join_guild(player_id, guild_id)
+                .await
+                .map(|()| OperationReceipt {
+                    guild_id: Some(guild_id),
+                    ..OperationReceipt::default()
+                }),
             predictive_manager::Operation::LeaveGuild => {
-                let _ = self.leave_guild(player_id).await;
-            }
-            predictive_manager::Operation::ContributeToGuild { amount } => {
-                let _ = self.contribute_to_guild(player_id, amount).await;
-            }
-            predictive_manager::Operation::UpdateGameConfig { config } => {
-                let _ = self.update_game_config(player_id, config).await;
-            }
-            predictive_manager::Operation::PredictDailyOutcome { outcome } => {
-                let _ = self
-                    .predict_daily_outcome(player_id, outcome, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::PredictWeeklyOutcome { outcome } => {
-                let _ = self
-                    .predict_weekly_outcome(player_id, outcome, current_time)
-                    .await;
-            }
-            predictive_manager::Operation::PredictMonthlyOutcome { outcome } => {
-                let _ = self
-                    .predict_monthly_outcome(player_id, outcome, current_time)
-                    .await;
+                self.leave_guild(player_id)
+                    .await
+                    .map(|guild_id| OperationReceipt {
+                        guild_id: Some(guild_id),
+                        ..OperationReceipt::default()
+                    })
             }
-            predictive_manager::Operation::UpdateMarketPrice { price } => {
-                let _ = self
-                    .update_market_price(player_id, price, current_time)
-                    .await;
+            predictive_manager::Operation::ContributeToGuild { amount } => self
+                .contribute_to_guild(player_id, amount)
+                .await
+                .map(|guild_id| OperationReceipt {
+                    guild_id: Some(guild_id),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::UpdateGameConfig { config } => self
+                .update_game_config(player_id, config)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::ReconcileSupply => self
+                .reconcile_supply(player_id)
+                .await
+                .map(|()| OperationReceipt::default()),
+            predictive_manager::Operation::PredictDailyOutcome {
+                asset,
+                outcome,
+                stake,
+            } => self
+                .predict_outcome(
+                    player_id,
+                    asset,
+                    PredictionPeriod::Daily,
+                    outcome,
+                    stake,
+                    current_time,
+                )
+                .await
+                .map(|period_start| OperationReceipt {
+                    period_start: Some(period_start),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::PredictWeeklyOutcome {
+                asset,
+                outcome,
+                stake,
+            } => self
+                .predict_outcome(
+                    player_id,
+                    asset,
+                    PredictionPeriod::Weekly,
+                    outcome,
+                    stake,
+                    current_time,
+                )
+                .await
+                .map(|period_start| OperationReceipt {
+                    period_start: Some(period_start),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::PredictMonthlyOutcome {
+                asset,
+                outcome,
+                stake,
+            } => self
+                .predict_outcome(
+                    player_id,
+                    asset,
+                    PredictionPeriod::Monthly,
+                    outcome,
+                    stake,
+                    current_time,
+                )
+                .await
+                .map(|period_start| OperationReceipt {
+                    period_start: Some(period_start),
+                    ..OperationReceipt::default()
+                }),
+            predictive_manager::Operation::UpdateMarketPrice { asset, price } => self
+                .update_market_price(player_id, asset, price, current_time)
+                .await
+                .map(|()| OperationReceipt::default()),
+        };
+
+        match result {
+            Ok(receipt) => Ok(self.complete_receipt(player_id, receipt).await),
+            Err(error) => {
+                // In strict mode a failed operation rejects the whole block,
+                // so none of its partial state changes are committed
+                if self.state.config.get().strict_operations {
+                    panic!("Operation failed: {error}");
+                }
+                // Otherwise only the operation's own changes and messages are discarded
+                self.rollback();
+                Err(error.into())
             }
         }
     }
@@ -190,6 +589,16 @@ impl Contract for PredictionMarketContract {
             // Local events (same chain)
             Message::MarketCreated { .. } => {}
             Message::TradeExecuted { .. } => {}
+            Message::MarketResolved { .. } => {}
+            Message::PointsMinted { .. } => {}
+            Message::TreasurySpent { .. } => {}
+            Message::SupplyReconciled { .. } => {}
+            Message::PointsTransferred { .. } => {}
+            Message::ResolutionProposed { .. } => {}
+            Message::ResolutionChallenged { .. } => {}
+            Message::DisputeSettled { .. } => {}
+            Message::LiquidityAdded { .. } => {}
+            Message::LiquidityWithdrawn { .. } => {}
             Message::PlayerLeveledUp { .. } => {}
             Message::AchievementUnlocked { .. } => {}
             Message::GuildCreated { .. } => {}
@@ -209,10 +618,11 @@ impl Contract for PredictionMarketContract {
                 }
 
                 // Idempotency check: Only create if market doesn't already exist
+                // Market IDs are per chain, so the registry is keyed by the hosting chain as well
                 if self
                     .state
                     .global_markets
-                    .get(&market_id)
+                    .get(&(chain_id, market_id))
                     .await
                     .ok()
                     .flatten()
@@ -227,7 +637,45 @@ impl Contract for PredictionMarketContract {
                         status: MarketStatus::Active,
                         created_at: self.runtime.system_time(),
                     };
-                    let _ = self.state.global_markets.insert(&market_id, global_market);
+                    let _ = self
+                        .state
+                        .global_markets
+                        .insert(&(chain_id, market_id), global_market);
+                }
+
+                // Mark message as processed
+                let _ = self.mark_message_processed(&message_id).await;
+            }
+            Message::GlobalMarketStatusUpdated {
+                market_id,
+                status,
+                chain_id,
+                message_id,
+            } => {
+                // Idempotency check: Skip if message already processed
+                if self.is_message_processed(&message_id).await {
+                    return; // Message already processed, skip
+                }
+
+                // Only move forward in the lifecycle: a late Closed must not overwrite Resolved or Cancelled
+                if let Some(mut global_market) = self
+                    .state
+                    .global_markets
+                    .get(&(chain_id, market_id))
+                    .await
+                    .ok()
+                    .flatten()
+                {
+                    if matches!(
+                        global_market.status,
+                        MarketStatus::Active | MarketStatus::Closed
+                    ) {
+                        global_market.status = status;
+                        let _ = self
+                            .state
+                            .global_markets
+                            .insert(&(chain_id, market_id), global_market);
+                    }
                 }
 
                 // Mark message as processed
@@ -383,6 +831,7 @@ impl Contract for PredictionMarketContract {
                 let _ = self.mark_message_processed(&message_id).await;
             }
             Message::GlobalPriceUpdate {
+                asset,
                 price,
                 timestamp,
                 chain_id: _,
@@ -394,17 +843,160 @@ impl Contract for PredictionMarketContract {
                 }
 
                 // Price timestamp validation: Only update if incoming price is newer
-                let current_price = self.state.current_market_price.get();
+                let current_price = self.asset_price(&asset).await.unwrap_or_default();
                 if timestamp > current_price.timestamp {
                     // Update global price (propagate across chains)
                     let market_price = MarketPrice { price, timestamp };
-                    self.state.current_market_price.set(market_price);
+                    let _ = self.state.asset_prices.insert(&asset, market_price);
+                    let _ = self
+                        .update_candles(
+                            CandleSource::PriceFeed(asset.clone()),
+                            0,
+                            price,
+                            Amount::ZERO,
+                            timestamp,
+                        )
+                        .await;
+                    // Predictions made on this chain resolve from the relayed price
+                    // A failure rejects the message rather than keep a partial resolution
+                    self.resolve_expired_predictions(&asset, timestamp)
+                        .await
+                        .expect("Failed to resolve expired predictions");
                 }
                 // If timestamp is older or equal, ignore the update (already have newer price)
 
                 // Mark message as processed
                 let _ = self.mark_message_processed(&message_id).await;
             }
+            // Cross-chain trading
+            Message::RemoteTradeRequest {
+                trade_id,
+                trader,
+                level,
+                home_chain,
+                market_id,
+                outcome_id,
+                side,
+                amount,
+                escrow,
+                max_payment,
+                min_received,
+                deadline,
+            } => {
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The hosting chain never executed the trade, so the whole escrow comes back
+                    self.close_remote_trade(
+                        trade_id,
+                        escrow,
+                        Amount::ZERO,
+                        RemoteTradeStatus::Bounced,
+                        None,
+                    )
+                    .await
+                    .expect("Failed to refund bounced trade");
+                    return;
+                }
+
+                let limits = TradeLimits {
+                    max_payment,
+                    min_received,
+                    deadline,
+                };
+                let (refund, fee, error) = self
+                    .execute_remote_trade(
+                        trader, level, home_chain, market_id, outcome_id, side, amount, escrow,
+                        limits,
+                    )
+                    .await
+                    .expect("Failed to execute remote trade");
+                self.send_tracked(
+                    Message::RemoteTradeSettled {
+                        trade_id,
+                        trader,
+                        refund,
+                        fee,
+                        error,
+                    },
+                    home_chain,
+                );
+            }
+            Message::RemoteTradeSettled {
+                trade_id,
+                trader,
+                refund,
+                fee,
+                error,
+            } => {
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // Keep the points in the hosted account rather than lose them
+                    let origin = self.message_origin_account();
+                    self.credit_player(trader, refund, origin, LedgerReason::RemoteTrade)
+                        .await
+                        .expect("Failed to restore bounced settlement");
+                    return;
+                }
+
+                let status = if error.is_some() {
+                    RemoteTradeStatus::Rejected
+                } else {
+                    RemoteTradeStatus::Settled
+                };
+                self.close_remote_trade(
+                    trade_id,
+                    refund,
+                    fee,
+                    status,
+                    error.map(|error| error.to_string()),
+                )
+                .await
+                .expect("Failed to settle remote trade");
+            }
+            Message::RemotePayout { player_id, amount } => {
+                // A bounced payout goes back to the hosted account it came from
+                let origin = self.message_origin_account();
+                self.credit_player(player_id, amount, origin, LedgerReason::RemoteTrade)
+                    .await
+                    .expect("Failed to credit remote payout");
+            }
+            // Cross-chain transfers
+            Message::TransferCredit {
+                from,
+                to,
+                amount,
+                memo,
+            } => {
+                if self.runtime.message_is_bouncing() == Some(true) {
+                    // The recipient's chain refused the credit, so the sender gets the points back
+                    let origin = self.message_origin_account();
+                    self.credit_player(from, amount, origin, LedgerReason::Transfer)
+                        .await
+                        .expect("Failed to refund bounced transfer");
+                    return;
+                }
+
+                // Panicking rejects the message, which bounces it back to refund the sender
+                let mut recipient = self
+                    .state
+                    .players
+                    .get(&to)
+                    .await
+                    .expect("Failed to load transfer recipient")
+                    .expect("Transfer recipient is not registered on this chain");
+                let origin = self.message_origin_account();
+                self.ledger_credit(&mut recipient, amount, origin, LedgerReason::Transfer)
+                    .await
+                    .expect("Failed to journal transfer");
+                self.state
+                    .players
+                    .insert(&to, recipient)
+                    .expect("Failed to credit transfer");
+                self.emit(Message::PointsTransferred {
+                    from,
+                    to,
+                    amount,
+                    memo,
+                });
+            }
             Message::ChainRegistered {
                 chain_id,
                 timestamp,
@@ -415,8 +1007,9 @@ impl Contract for PredictionMarketContract {
         }
     }
 
-    async fn store(self) {
-        // State is automatically saved by Linera SDK
+    async fn store(mut self) {
+        self.send_outbox();
+        self.state.save().await.expect("Failed to save state");
     }
 }
 
@@ -428,6 +1021,8 @@ impl PredictionMarketContract {
     // Leaderboard broadcast thresholds
     const LEADERBOARD_BROADCAST_TIME_THRESHOLD_MICROS: u64 = 30_000_000; // 30 seconds
     const LEADERBOARD_BROADCAST_UPDATE_THRESHOLD: u64 = 10; // Broadcast after 10 updates
+    /// Longest memo a point transfer may carry, in characters
+    const MAX_MEMO_LENGTH: usize = 280;
     /// Initialize the achievement system with predefined achievements
     /// This sets up the reward system for player progression
     async fn initialize_achievements(&mut self) -> Result<(), ContractError> {
@@ -522,15 +1117,13 @@ impl PredictionMarketContract {
         let config = self.state.config.get();
         let initial_tokens = config.initial_player_tokens;
 
-        // Give initial points to the player (no external transfer needed)
-
         let display_name_clone = display_name.clone();
-        let player = Player {
+        let mut player = Player {
             id: player_id,
             display_name,
             registration_time: current_time,
             last_login: current_time,
-            token_balance: initial_tokens,
+            token_balance: Amount::ZERO,
             total_earned: initial_tokens,
             total_spent: Amount::ZERO,
             level: 1,
@@ -544,13 +1137,19 @@ impl PredictionMarketContract {
             guild_id: None,
             achievements_earned: Vec::new(),
             active_markets: Vec::new(),
+            debt: Amount::ZERO,
         };
 
+        // Give initial points to the player (no external transfer needed)
+        self.ledger_credit(
+            &mut player,
+            initial_tokens,
+            LedgerAccount::ISSUANCE,
+            LedgerReason::Registration,
+        )
+        .await?;
         self.state.players.insert(&player_id, player)?;
 
-        let total_supply = self.state.total_supply.get().saturating_add(initial_tokens);
-        self.state.total_supply.set(total_supply);
-
         // Broadcast player registration to all chains for horizontal scaling
         self.broadcast_global_player_registered(player_id, display_name_clone)
             .await;
@@ -606,15 +1205,97 @@ impl PredictionMarketContract {
         let reward = config.daily_login_reward;
 
         // Add reward points to the player (no external transfer needed)
-
-        player.token_balance = player.token_balance.saturating_add(reward);
+        self.credit_reward(&mut player, reward, LedgerReason::DailyReward)
+            .await?;
         player.total_earned = player.total_earned.saturating_add(reward);
         player.last_login = current_time;
         self.state.players.insert(&player_id, player)?;
+        Ok(())
+    }
+
+    /// Send points to another player
+    /// A recipient on this chain is credited at once; a recipient registered on another chain
+    /// is credited by a tracked message, and the sender is refunded if that message bounces
+    ///
+    /// # Arguments
+    /// * `sender` - The player sending points
+    /// * `to` - The player receiving them
+    /// * `amount` - How many points to send
+    /// * `memo` - Optional note for the recipient
+    ///
+    /// # Returns
+    /// * `Ok(())` - Points sent successfully
+    /// * `Err(InvalidTransfer)` - Amount is zero, the sender is the recipient, or the memo is too long
+    /// * `Err(PlayerNotFound)` - Sender isn't registered, or the recipient is unknown on every chain
+    /// * `Err(InsufficientBalance)` - Sender doesn't hold the amount
+    /// * `Err(InDebt)` - Sender owes unpaid penalties
+    async fn transfer_points(
+        &mut self,
+        sender: PlayerId,
+        to: PlayerId,
+        amount: Amount,
+        memo: Option<String>,
+    ) -> Result<(), ContractError> {
+        if amount == Amount::ZERO
+            || to == sender
+            || memo
+                .as_ref()
+                .is_some_and(|memo| memo.chars().count() > Self::MAX_MEMO_LENGTH)
+        {
+            return Err(ContractError::InvalidTransfer);
+        }
+
+        let mut player = self.get_player(&sender).await?;
+        Self::ensure_no_debt(&player)?;
+        if player.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let current_chain = self.runtime.chain_id();
+        let recipient_chain = if self.state.players.contains_key(&to).await? {
+            None
+        } else {
+            match self.state.global_players.get(&to).await? {
+                Some(info) if info.chain_id != current_chain => Some(info.chain_id),
+                _ => return Err(ContractError::PlayerNotFound),
+            }
+        };
 
-        let total_supply = self.state.total_supply.get();
-        let new_total = total_supply.saturating_add(reward);
-        self.state.total_supply.set(new_total);
+        match recipient_chain {
+            None => {
+                let mut recipient = self.get_player(&to).await?;
+                self.ledger_transfer(&mut player, &mut recipient, amount, LedgerReason::Transfer)
+                    .await?;
+                self.state.players.insert(&sender, player)?;
+                self.state.players.insert(&to, recipient)?;
+                self.emit(Message::PointsTransferred {
+                    from: sender,
+                    to,
+                    amount,
+                    memo,
+                });
+            }
+            Some(chain_id) => {
+                // Debited here first; tracking bounces the credit back if the recipient's chain refuses it
+                self.ledger_debit(
+                    &mut player,
+                    amount,
+                    LedgerAccount::chain(chain_id),
+                    LedgerReason::Transfer,
+                )
+                .await?;
+                self.state.players.insert(&sender, player)?;
+                self.send_tracked(
+                    Message::TransferCredit {
+                        from: sender,
+                        to,
+                        amount,
+                        memo,
+                    },
+                    chain_id,
+                );
+            }
+        }
         Ok(())
     }
 
@@ -626,40 +1307,60 @@ impl PredictionMarketContract {
     /// # Arguments
     /// * `creator` - The player creating the market (must be level 5+ and have 10,000+ points)
     /// * `title` - Market title/name
-    /// * `amount` - Amount of points available in this market
+    /// * `amount` - Amount of points available in this market (the LMSR subsidy of a prediction market)
     /// * `fee_percent` - Fee percentage market creator wants to charge on trades (0-100)
+    /// * `outcomes` - Names of the outcomes of a prediction market (empty for a point market)
+    /// * `market_type` - How the market prices trades (fixed rate, constant product or prediction)
+    /// * `close_time` - Optional time at which trading stops
+    /// * `resolution_time` - Optional earliest time a prediction market can be resolved
+    /// * `oracle` - Optional price condition that resolves a Yes/No prediction market at the end of the current period
     /// * `current_time` - Current timestamp for market timing
     ///
     /// # Returns
-    /// * `Ok(())` - Market created successfully
+    /// * `Ok((market_id, creation_cost))` - Market created successfully
     /// * `Err(InsufficientLevel)` - Player must be at least level 5 to create markets
-    /// * `Err(InsufficientBalance)` - Player must have at least 10,000 points to create markets
+    /// * `Err(InsufficientBalance)` - Player must have at least 10,000 points and cover the cost plus liquidity,
+    ///   and a market maker (prediction or constant product) needs an amount large enough to price trades
+    /// * `Err(InvalidOutcome)` - A prediction market needs at least two outcomes, a point market none,
+    ///   and an oracle market is a prediction market whose outcomes are set for it
+    /// * `Err(InvalidDeadline)` - Close time has passed, resolution time precedes it or is set on a point market,
+    ///   or an oracle market would trade past its period or set a resolution time
+    /// * `Err(UnknownAsset)` - An oracle market's asset is not listed in the config
+    /// * `Err(InDebt)` - Player owes unpaid penalties
+    #[allow(clippy::too_many_arguments)]
     async fn create_market(
         &mut self,
         creator: PlayerId,
         title: String,
         amount: Amount,
         fee_percent: u8,
+        mut outcomes: Vec<String>,
+        market_type: MarketType,
+        mut close_time: Option<Timestamp>,
+        resolution_time: Option<Timestamp>,
+        oracle: Option<PriceCondition>,
         current_time: Timestamp,
-    ) -> Result<(), ContractError> {
+    ) -> Result<(MarketId, Amount), ContractError> {
         // Get player - must exist and be registered
         let mut player = self.get_player(&creator).await?;
+        Self::ensure_no_debt(&player)?;
+
+        let config = self.state.config.get();
 
-        // Market creators must be at least level 5
-        if player.level < 5 {
+        // Market creators must reach the configured level
+        if player.level < config.min_creator_level {
             return Err(ContractError::InsufficientLevel);
         }
 
-        // Market creators must have at least 10,000 points
-        let min_points_to_create = Amount::from_tokens(10000);
-        if player.token_balance < min_points_to_create {
+        // Market creators must hold the configured minimum balance
+        if player.token_balance < config.min_creator_balance {
             return Err(ContractError::InsufficientBalance);
         }
 
         // Get market creation cost from config
-        let config = self.state.config.get();
+        // The creator also escrows the market's initial liquidity
         let creation_cost = config.market_creation_cost;
-        if player.token_balance < creation_cost {
+        if player.token_balance < creation_cost.saturating_add(amount) {
             return Err(ContractError::InsufficientBalance);
         }
 
@@ -668,18 +1369,103 @@ impl PredictionMarketContract {
             return Err(ContractError::InvalidOutcome); // Reuse error type for now
         }
 
+        // An oracle market is a Yes/No prediction market on an asset's price over the current period;
+        // trading stops when the period ends and the period's prices resolve it
+        let oracle = match oracle {
+            Some(condition) => {
+                if market_type != MarketType::Prediction || !outcomes.is_empty() {
+                    return Err(ContractError::InvalidOutcome);
+                }
+                self.ensure_asset_listed(&condition.asset)?;
+                let period_start = self.get_period_start(condition.period, current_time);
+                let period_end = Timestamp::from(
+                    period_start
+                        .micros()
+                        .saturating_add(Self::period_length_micros(condition.period)),
+                );
+                if resolution_time.is_some()
+                    || close_time.is_some_and(|close_time| close_time > period_end)
+                {
+                    return Err(ContractError::InvalidDeadline);
+                }
+                outcomes = vec!["Yes".to_string(), "No".to_string()];
+                close_time = Some(close_time.unwrap_or(period_end));
+                Some(OracleCondition {
+                    asset: condition.asset,
+                    period: condition.period,
+                    period_start,
+                    outcome: condition.outcome,
+                })
+            }
+            None => None,
+        };
+
+        // A prediction market needs at least two outcomes to predict between, a point market has none
+        let outcomes_valid = match market_type {
+            MarketType::Prediction => {
+                outcomes.len() >= 2 && outcomes.len() <= OutcomeId::MAX as usize
+            }
+            MarketType::FixedRate | MarketType::ConstantProduct => outcomes.is_empty(),
+        };
+        if !outcomes_valid {
+            return Err(ContractError::InvalidOutcome);
+        }
+
+        // Deadlines must lie ahead, and only prediction markets wait to be resolved
+        if close_time.is_some_and(|close_time| close_time <= current_time) {
+            return Err(ContractError::InvalidDeadline);
+        }
+        if let Some(resolution_time) = resolution_time {
+            if market_type != MarketType::Prediction
+                || resolution_time <= current_time
+                || close_time.is_some_and(|close_time| resolution_time < close_time)
+            {
+                return Err(ContractError::InvalidDeadline);
+            }
+        }
+
+        // The initial amount subsidizes the LMSR market maker, so it must cover a nonzero `b`
+        let liquidity_parameter = if market_type == MarketType::Prediction {
+            lmsr_liquidity_parameter(amount, outcomes.len())
+        } else {
+            Amount::ZERO
+        };
+        let priced = match market_type {
+            MarketType::FixedRate => true,
+            MarketType::ConstantProduct => Self::initial_payment_reserve(amount) > Amount::ZERO,
+            MarketType::Prediction => liquidity_parameter > Amount::ZERO,
+        };
+        if !priced {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let market_id = self.generate_market_id().await?;
+
         // Deduct creation cost from player
-        player.token_balance = player.token_balance.saturating_sub(creation_cost);
+        self.ledger_debit(
+            &mut player,
+            creation_cost,
+            LedgerAccount::TREASURY,
+            LedgerReason::MarketCreationFee,
+        )
+        .await?;
         player.total_spent = player.total_spent.saturating_add(creation_cost);
+        // Move the initial liquidity into the market; it is returned when the market ends
+        self.ledger_debit(
+            &mut player,
+            amount,
+            LedgerAccount::market(market_id),
+            LedgerReason::LiquidityDeposit,
+        )
+        .await?;
         // Update reputation for creating a market
         player.reputation = player.reputation.saturating_add(5);
         self.state.players.insert(&creator, player)?;
 
-        // Distribute market creation fee to platform (goes to total supply)
+        // Distribute market creation fee to platform (goes to the treasury)
         self.distribute_market_creator_fee(creator, creation_cost)
             .await?;
 
-        let market_id = self.generate_market_id().await?;
         let title_clone = title.clone();
 
         let market = Market {
@@ -693,9 +1479,47 @@ impl PredictionMarketContract {
             total_liquidity: amount,
             positions: BTreeMap::new(),
             total_participants: 0,
+            outcomes: outcomes
+                .into_iter()
+                .enumerate()
+                .map(|(id, name)| MarketOutcome {
+                    id: id as OutcomeId,
+                    name,
+                    total_shares: Amount::ZERO,
+                })
+                .collect(),
+            winning_outcome: None,
+            liquidity_parameter,
+            market_type,
+            payment_reserve: Amount::ZERO,
+            close_time,
+            resolution_time,
+            // The creator's initial liquidity makes them the first provider
+            liquidity_positions: BTreeMap::from([(
+                creator,
+                LiquidityPosition {
+                    market_id,
+                    provider: creator,
+                    shares: amount,
+                    deposited: amount,
+                    earned: Amount::ZERO,
+                },
+            )]),
+            total_lp_shares: amount,
+            trade_count: 0,
+            oracle: oracle.clone(),
+            resolution: None,
         };
 
         self.state.markets.insert(&market_id, market)?;
+        if let Some(close_time) = close_time {
+            self.state
+                .market_close_times
+                .insert(&market_id, close_time)?;
+        }
+        if let Some(condition) = oracle {
+            self.track_oracle_market(market_id, condition).await?;
+        }
 
         // Update creator's active markets
         let mut creator_player = self.get_player(&creator).await?;
@@ -709,51 +1533,120 @@ impl PredictionMarketContract {
         // Check for achievements after creating market
         self.check_achievements(&mut creator_player).await?;
 
-        self.runtime
-            .prepare_message(Message::MarketCreated { market_id, creator })
-            .send_to(self.runtime.chain_id());
+        self.emit(Message::MarketCreated { market_id, creator });
 
         // Broadcast market creation to all chains for horizontal scaling
         self.broadcast_global_market_created(market_id, creator, title_clone)
             .await;
 
-        Ok(())
+        Ok((market_id, creation_cost))
     }
 
-    /// Buy points from a market
-    /// Allows players to buy points from active markets with level-based progressive exchange rate
+    /// Buy points from a market, or shares of an outcome in a prediction market
+    /// Allows players to buy points from active markets with level-based progressive exchange rate;
+    /// constant-product markets price the points along their pool's curve instead
     /// Market creator receives fee based on their chosen fee percentage
-    /// Exchange rate scales with player level:
-    /// - Level 1: pay 10 points to get 100 points (10:1 ratio)
-    /// - Level 2: pay 100 points to get 1000 points (10:1 ratio)
+    /// Exchange rate is set by `fixed_rate_exchange` in the config, 10:1 by default:
+    /// - Level 1: pay 10 points to get 100 points
+    /// - Level 2: pay 100 points to get 1000 points
+    ///
+    /// A market hosted on another chain is traded through a remote trade request instead
     ///
     /// # Arguments
     /// * `player_id` - The player buying points
     /// * `market_id` - The market to buy points from
-    /// * `amount` - How many points the player wants to receive
+    /// * `chain_id` - The chain hosting the market, none for a market on this chain
+    /// * `outcome_id` - The outcome to buy shares of (prediction markets only)
+    /// * `amount` - How many points (or shares) the player wants to receive
+    /// * `limits` - Optional maximum payment, minimum received and deadline
     /// * `current_time` - Current timestamp
     ///
     /// # Returns
-    /// * `Ok(())` - Points purchased successfully
+    /// * `Ok(fee)` - Points purchased successfully, returns the fee charged
     /// * `Err(InsufficientBalance)` - Player doesn't have enough tokens to pay
     /// * `Err(MarketNotFound)` - Market doesn't exist
     /// * `Err(MarketNotActive)` - Market is not active
+    /// * `Err(InvalidOutcome)` - Outcome is missing or unknown for a prediction market, or given for a point market
+    /// * `Err(SlippageExceeded)` - The trade would cost more than `max_payment` or receive less than `min_received`
+    /// * `Err(DeadlineExpired)` - The trade's deadline has passed
+    /// * `Err(MaxPaymentRequired)` - The market is remote and no `max_payment` was given to escrow
+    /// * `Err(InDebt)` - Player owes unpaid penalties
+    #[allow(clippy::too_many_arguments)]
     async fn buy_shares(
         &mut self,
         player_id: PlayerId,
         market_id: MarketId,
+        chain_id: Option<ChainId>,
+        outcome_id: Option<OutcomeId>,
         amount: Amount,
+        limits: TradeLimits,
         current_time: Timestamp,
-    ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
+    ) -> Result<Amount, ContractError> {
+        if limits
+            .deadline
+            .is_some_and(|deadline| current_time > deadline)
+        {
+            return Err(ContractError::DeadlineExpired);
+        }
+
+        if let Some(remote) = self.remote_market(chain_id, market_id).await? {
+            return self
+                .request_remote_trade(
+                    player_id,
+                    remote,
+                    outcome_id,
+                    OrderSide::Buy,
+                    amount,
+                    limits,
+                    current_time,
+                )
+                .await;
+        }
+
+        let player = self.get_player(&player_id).await?;
+        let fee = self
+            .buy_with_account(player, market_id, outcome_id, amount, limits, current_time)
+            .await?;
+        self.reward_trade(&player_id).await?;
+        Ok(fee)
+    }
+
+    /// Buy from a market hosted on this chain with an account already loaded,
+    /// a registered player's or the hosted account of a remote trader
+    /// Returns the fee charged; the errors are those of `buy_shares`
+    async fn buy_with_account(
+        &mut self,
+        mut player: Player,
+        market_id: MarketId,
+        outcome_id: Option<OutcomeId>,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        let player_id = player.id;
+        Self::ensure_no_debt(&player)?;
 
         // Get the specific market to buy from
-        let mut market = self.get_market(&market_id).await?;
+        let mut market = self.touch_market(market_id).await?;
 
         if market.status != MarketStatus::Active {
             return Err(ContractError::MarketNotActive);
         }
 
+        if market.market_type == MarketType::Prediction {
+            return self
+                .buy_outcome_shares(player, market, outcome_id, amount, limits, current_time)
+                .await;
+        }
+        if outcome_id.is_some() {
+            return Err(ContractError::InvalidOutcome);
+        }
+        if market.market_type == MarketType::ConstantProduct {
+            return self
+                .buy_from_pool(player, market, amount, limits, current_time)
+                .await;
+        }
+
         // Check if market has enough liquidity to sell
         let points_to_receive = market.total_liquidity.min(amount);
         if points_to_receive == Amount::ZERO {
@@ -761,49 +1654,47 @@ impl PredictionMarketContract {
         }
 
         // Calculate payment based on progressive exchange (works for all levels 1 to infinity)
-        // Exchange rate is always 10:1 (pay 10% to receive 100%)
-        // Examples:
+        // Exchange rate comes from the config (10:1 by default, pay 10% to receive 100%)
+        // Examples at 10:1:
         //   Level 1: want 100 points → pay 10 points
         //   Level 2: want 1000 points → pay 100 points
         //   Level 3: want 10000 points → pay 1000 points
         //   Level N: want X points → pay X/10 points
-        let points_tokens: u128 = points_to_receive.into();
-        let base_payment_tokens = points_tokens.saturating_div(10);
-        let base_payment = Amount::from_tokens(base_payment_tokens);
+        let exchange_rate = self.state.config.get().fixed_rate_exchange;
+        let points_attos: u128 = points_to_receive.into();
+        let base_payment = Amount::from_attos(points_attos / exchange_rate as u128);
 
         // Calculate fee based on market's fee percentage
-        let config = self.state.config.get();
-        let fee_divisor = config.fee_divisor.into();
-        let fee_tokens = base_payment_tokens
-            .saturating_mul(market.fee_percent as u128)
-            .saturating_div(fee_divisor);
-        let buyer_fee = Amount::from_tokens(fee_tokens);
+        let buyer_fee = self.calculate_market_fee(&market, base_payment);
         let actual_payment = base_payment.saturating_add(buyer_fee);
 
         // Ensure player has enough to pay (base payment + fee)
         if player.token_balance < actual_payment {
             return Err(ContractError::InsufficientBalance);
         }
+        limits.check(actual_payment, points_to_receive)?;
 
         // Player pays base payment + fee
-        player.token_balance = player.token_balance.saturating_sub(actual_payment);
+        let market_account = LedgerAccount::market(market_id);
+        self.ledger_debit(
+            &mut player,
+            actual_payment,
+            market_account,
+            LedgerReason::Trade,
+        )
+        .await?;
         player.total_spent = player.total_spent.saturating_add(actual_payment);
 
         // Player receives points from the market (transfer from market liquidity to player)
-        player.token_balance = player.token_balance.saturating_add(points_to_receive);
+        self.ledger_credit(
+            &mut player,
+            points_to_receive,
+            market_account,
+            LedgerReason::Trade,
+        )
+        .await?;
         player.total_earned = player.total_earned.saturating_add(points_to_receive);
 
-        // Distribute trading fees: creator gets (base payment + fee), platform gets 2% of creator's fee
-        // First, give market creator the base payment
-        let mut market_creator = self.get_player(&market.creator).await?;
-        market_creator.token_balance = market_creator.token_balance.saturating_add(base_payment);
-        market_creator.total_earned = market_creator.total_earned.saturating_add(base_payment);
-        market_creator.total_profit = market_creator.total_profit.saturating_add(base_payment);
-        self.state.players.insert(&market.creator, market_creator)?;
-
-        // Distribute the fee portion: creator keeps 98%, platform gets 2%
-        self.distribute_trading_fees(market_id, buyer_fee).await?;
-
         // Update market liquidity (points available decrease as they're sold)
         market.total_liquidity = market.total_liquidity.saturating_sub(points_to_receive);
 
@@ -815,98 +1706,279 @@ impl PredictionMarketContract {
         });
         position.total_invested = position.total_invested.saturating_add(actual_payment);
 
-        if !player.active_markets.contains(&market_id) {
-            player.active_markets.push(market_id);
-            market.total_participants += 1;
-        }
-
-        player.markets_participated += 1;
-        // Update reputation for buying shares
-        player.reputation = player.reputation.saturating_add(1);
-        self.add_experience(&mut player, 10).await?;
-
-        self.state.markets.insert(&market_id, market)?;
-        self.state.players.insert(&player_id, player.clone())?;
-
-        // Check for achievements after buying (first buy achievement)
-        self.check_achievements(&mut player).await?;
+        self.record_trade(
+            player,
+            market,
+            0,
+            OrderSide::Buy,
+            points_to_receive,
+            base_payment,
+            buyer_fee,
+        )
+        .await?;
 
-        self.runtime
-            .prepare_message(Message::TradeExecuted {
-                player_id,
-                market_id,
-                outcome_id: 0,
-                shares: points_to_receive,
-                price: base_payment,
-            })
-            .send_to(self.runtime.chain_id());
-        Ok(())
+        // Providers are paid once the buyer is stored, so a provider buying in their own market keeps it:
+        // they share the base payment for the points sold, and the fee net of the platform's cut
+        self.pay_liquidity_providers(market_id, base_payment)
+            .await?;
+        self.distribute_trading_fees(market_id, buyer_fee).await?;
+        Ok(buyer_fee)
     }
 
-    /// Sell points to a market
-    /// Allows players at level 5+ to sell their points to a specific market
-    /// Market creator receives fee based on their chosen fee percentage
-    ///
-    /// # Arguments
-    /// * `player_id` - The player selling points
-    /// * `market_id` - The market to sell points to
-    /// * `amount` - How many points to sell
-    /// * `current_time` - Current timestamp
-    ///
-    /// # Returns
-    /// * `Ok(())` - Points sold successfully
-    /// * `Err(InsufficientLevel)` - Player must be at least level 5 to sell
-    /// * `Err(InsufficientBalance)` - Player doesn't have enough points to sell
-    /// * `Err(MarketNotFound)` - Market doesn't exist
-    /// * `Err(MarketNotActive)` - Market is not active
-    async fn sell_shares(
+    /// Buy shares of one outcome in a prediction market
+    /// Shares are priced by the market's LMSR cost function, paid into the market pool, plus the market fee
+    async fn buy_outcome_shares(
         &mut self,
-        player_id: PlayerId,
-        market_id: MarketId,
-        amount: Amount,
+        mut player: Player,
+        mut market: Market,
+        outcome_id: Option<OutcomeId>,
+        shares: Amount,
+        limits: TradeLimits,
         current_time: Timestamp,
-    ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-
-        // Sellers must be at least level 5
-        if player.level < 5 {
-            return Err(ContractError::InsufficientLevel);
+    ) -> Result<Amount, ContractError> {
+        let outcome_id = Self::validate_outcome(&market, outcome_id)?;
+        if shares == Amount::ZERO {
+            return Err(ContractError::InsufficientBalance);
         }
 
-        if player.token_balance < amount {
+        let cost = lmsr_buy_cost(
+            &Self::outcome_quantities(&market),
+            market.liquidity_parameter,
+            outcome_id as usize,
+            shares,
+        );
+        let fee = self.calculate_market_fee(&market, cost);
+        let payment = cost.saturating_add(fee);
+        if player.token_balance < payment {
             return Err(ContractError::InsufficientBalance);
         }
+        limits.check(payment, shares)?;
 
-        // Get the specific market to sell to
-        let mut market = self.get_market(&market_id).await?;
+        self.ledger_debit(
+            &mut player,
+            payment,
+            LedgerAccount::market(market.id),
+            LedgerReason::Trade,
+        )
+        .await?;
+        player.total_spent = player.total_spent.saturating_add(payment);
+
+        // The cost goes into the pool that pays out the winning outcome
+        market.total_liquidity = market.total_liquidity.saturating_add(cost);
+        let outcome = &mut market.outcomes[outcome_id as usize];
+        outcome.total_shares = outcome.total_shares.saturating_add(shares);
+
+        // Positions only track what went into the pool, so cancellation can refund it
+        let position = market.positions.entry(player.id).or_insert(PlayerPosition {
+            shares_by_outcome: BTreeMap::new(),
+            total_invested: Amount::ZERO,
+            entry_time: current_time,
+        });
+        let held = position.shares_by_outcome.entry(outcome_id).or_default();
+        *held = held.saturating_add(shares);
+        position.total_invested = position.total_invested.saturating_add(cost);
+
+        let market_id = market.id;
+        self.record_trade(
+            player,
+            market,
+            outcome_id,
+            OrderSide::Buy,
+            shares,
+            cost,
+            fee,
+        )
+        .await?;
+
+        // Fees are paid once the buyer is stored, so a provider buying in their own market keeps them
+        self.distribute_trading_fees(market_id, fee).await?;
+        Ok(fee)
+    }
+
+    /// Buy points from a constant-product market
+    /// The price follows x * y = k between the market's liquidity and its payment reserve,
+    /// so every point bought makes the next one more expensive
+    async fn buy_from_pool(
+        &mut self,
+        mut player: Player,
+        mut market: Market,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        // The pool can never be fully drained
+        if amount == Amount::ZERO || amount >= market.total_liquidity {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let cost = constant_product_buy_cost(
+            market.total_liquidity,
+            Self::pool_payment_reserve(&market),
+            amount,
+        );
+        let fee = self.calculate_market_fee(&market, cost);
+        let payment = cost.saturating_add(fee);
+        if player.token_balance < payment {
+            return Err(ContractError::InsufficientBalance);
+        }
+        limits.check(payment, amount)?;
+
+        let market_account = LedgerAccount::market(market.id);
+        self.ledger_debit(&mut player, payment, market_account, LedgerReason::Trade)
+            .await?;
+        player.total_spent = player.total_spent.saturating_add(payment);
+        self.ledger_credit(&mut player, amount, market_account, LedgerReason::Trade)
+            .await?;
+        player.total_earned = player.total_earned.saturating_add(amount);
+
+        // The payment stays in the pool; the creator receives it when the market ends
+        market.total_liquidity = market.total_liquidity.saturating_sub(amount);
+        market.payment_reserve = market.payment_reserve.saturating_add(cost);
+
+        let position = market.positions.entry(player.id).or_insert(PlayerPosition {
+            shares_by_outcome: BTreeMap::new(),
+            total_invested: Amount::ZERO,
+            entry_time: current_time,
+        });
+        position.total_invested = position.total_invested.saturating_add(payment);
+
+        let market_id = market.id;
+        self.record_trade(player, market, 0, OrderSide::Buy, amount, cost, fee)
+            .await?;
+
+        // Fees are paid once the buyer is stored, so a provider buying in their own market keeps them
+        self.distribute_trading_fees(market_id, fee).await?;
+        Ok(fee)
+    }
+
+    /// Sell points to a market, or shares of an outcome back to a prediction market
+    /// Allows players at level 5+ to sell their points to a specific market
+    /// Market creator receives fee based on their chosen fee percentage
+    /// A market hosted on another chain is traded through a remote trade request instead
+    ///
+    /// # Arguments
+    /// * `player_id` - The player selling points
+    /// * `market_id` - The market to sell points to
+    /// * `chain_id` - The chain hosting the market, none for a market on this chain
+    /// * `outcome_id` - The outcome to sell shares of (prediction markets only)
+    /// * `amount` - How many points (or shares) to sell
+    /// * `limits` - Optional maximum payment, minimum received and deadline
+    /// * `current_time` - Current timestamp
+    ///
+    /// # Returns
+    /// * `Ok(fee)` - Points sold successfully, returns the fee charged
+    /// * `Err(InsufficientLevel)` - Player must be at least level 5 to sell points
+    /// * `Err(InsufficientBalance)` - Player doesn't have enough points (or shares) to sell
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(MarketNotActive)` - Market is not active
+    /// * `Err(InvalidOutcome)` - Outcome is missing or unknown for a prediction market, or given for a point market
+    /// * `Err(SlippageExceeded)` - The trade would give up more than `max_payment` or receive less than `min_received`
+    /// * `Err(DeadlineExpired)` - The trade's deadline has passed
+    /// * `Err(InDebt)` - Player owes unpaid penalties and is selling points
+    #[allow(clippy::too_many_arguments)]
+    async fn sell_shares(
+        &mut self,
+        player_id: PlayerId,
+        market_id: MarketId,
+        chain_id: Option<ChainId>,
+        outcome_id: Option<OutcomeId>,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        if limits
+            .deadline
+            .is_some_and(|deadline| current_time > deadline)
+        {
+            return Err(ContractError::DeadlineExpired);
+        }
+
+        if let Some(remote) = self.remote_market(chain_id, market_id).await? {
+            return self
+                .request_remote_trade(
+                    player_id,
+                    remote,
+                    outcome_id,
+                    OrderSide::Sell,
+                    amount,
+                    limits,
+                    current_time,
+                )
+                .await;
+        }
+
+        let player = self.get_player(&player_id).await?;
+        let fee = self
+            .sell_with_account(player, market_id, outcome_id, amount, limits, current_time)
+            .await?;
+        self.reward_trade(&player_id).await?;
+        Ok(fee)
+    }
+
+    /// Sell to a market hosted on this chain with an account already loaded,
+    /// a registered player's or the hosted account of a remote trader
+    /// Returns the fee charged; the errors are those of `sell_shares`
+    async fn sell_with_account(
+        &mut self,
+        mut player: Player,
+        market_id: MarketId,
+        outcome_id: Option<OutcomeId>,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        let player_id = player.id;
+
+        // Get the specific market to sell to
+        let mut market = self.touch_market(market_id).await?;
 
         if market.status != MarketStatus::Active {
             return Err(ContractError::MarketNotActive);
         }
 
+        if market.market_type == MarketType::Prediction {
+            return self
+                .sell_outcome_shares(player, market, outcome_id, amount, limits)
+                .await;
+        }
+        if outcome_id.is_some() {
+            return Err(ContractError::InvalidOutcome);
+        }
+
+        // Selling points moves them into the market, so debtors may only sell outcome shares
+        Self::ensure_no_debt(&player)?;
+
+        // Sellers must reach the configured level
+        if player.level < self.state.config.get().min_seller_level {
+            return Err(ContractError::InsufficientLevel);
+        }
+
+        if player.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        if market.market_type == MarketType::ConstantProduct {
+            return self
+                .sell_to_pool(player, market, amount, limits, current_time)
+                .await;
+        }
+
         // Calculate seller fee using the market's fee percentage (set by market creator)
-        let config = self.state.config.get();
-        let fee_divisor = config.fee_divisor.into();
-        let amount_tokens: u128 = amount.into();
-        let fee_tokens = amount_tokens
-            .saturating_mul(market.fee_percent as u128)
-            .saturating_div(fee_divisor);
-        let seller_fee = Amount::from_tokens(fee_tokens);
+        let seller_fee = self.calculate_market_fee(&market, amount);
         let points_for_market = amount.saturating_sub(seller_fee);
+        // The points join the market's liquidity, nothing is paid back
+        limits.check(amount, Amount::ZERO)?;
 
-        // Burn points from player (decrease their balance)
-        player.token_balance = player.token_balance.saturating_sub(amount);
+        // The points move from the player into the market
+        self.ledger_debit(
+            &mut player,
+            amount,
+            LedgerAccount::market(market_id),
+            LedgerReason::Trade,
+        )
+        .await?;
         player.total_spent = player.total_spent.saturating_add(amount);
 
-        // Distribute trading fees: creator gets 98% of fee, platform gets 2% of fee
-        self.distribute_trading_fees(market_id, seller_fee).await?;
-
-        // Burn points from total supply (net after fee)
-        let current_supply = self.state.total_supply.get();
-        self.state
-            .total_supply
-            .set(current_supply.saturating_sub(points_for_market.min(*current_supply)));
-
         // Add liquidity to market (points available increase, minus fee)
         market.total_liquidity = market.total_liquidity.saturating_add(points_for_market);
 
@@ -918,443 +1990,3091 @@ impl PredictionMarketContract {
         });
         position.total_invested = position.total_invested.saturating_add(amount);
 
-        if !player.active_markets.contains(&market_id) {
-            player.active_markets.push(market_id);
-            market.total_participants += 1;
-        }
+        self.record_trade(
+            player,
+            market,
+            0,
+            OrderSide::Sell,
+            amount,
+            amount,
+            seller_fee,
+        )
+        .await?;
 
-        player.markets_participated += 1;
-        // Update reputation for selling shares
-        player.reputation = player.reputation.saturating_add(1);
-        self.add_experience(&mut player, 10).await?;
+        // Distribute trading fees: providers get the fee net of the platform's cut
+        self.distribute_trading_fees(market_id, seller_fee).await?;
+        Ok(seller_fee)
+    }
 
-        self.state.markets.insert(&market_id, market)?;
-        self.state.players.insert(&player_id, player.clone())?;
+    /// Sell points to a constant-product market
+    /// The points (minus the market fee) join the pool's liquidity and the seller is paid along the curve,
+    /// never more than buyers have paid into the pool
+    async fn sell_to_pool(
+        &mut self,
+        mut player: Player,
+        mut market: Market,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        let fee = self.calculate_market_fee(&market, amount);
+        let points_for_market = amount.saturating_sub(fee);
+        let proceeds = constant_product_sell_proceeds(
+            market.total_liquidity,
+            Self::pool_payment_reserve(&market),
+            points_for_market,
+        )
+        .min(market.payment_reserve);
+        limits.check(amount, proceeds)?;
 
-        // Check for achievements after selling (first sell achievement)
-        self.check_achievements(&mut player).await?;
+        let market_account = LedgerAccount::market(market.id);
+        self.ledger_debit(&mut player, amount, market_account, LedgerReason::Trade)
+            .await?;
+        player.total_spent = player.total_spent.saturating_add(amount);
+        self.ledger_credit(&mut player, proceeds, market_account, LedgerReason::Trade)
+            .await?;
+        player.total_earned = player.total_earned.saturating_add(proceeds);
 
-        self.runtime
-            .prepare_message(Message::TradeExecuted {
-                player_id,
-                market_id,
-                outcome_id: 0,
-                shares: amount,
-                price: amount,
-            })
-            .send_to(self.runtime.chain_id());
-        Ok(())
-    }
+        market.total_liquidity = market.total_liquidity.saturating_add(points_for_market);
+        market.payment_reserve = market.payment_reserve.saturating_sub(proceeds);
 
-    /// Mint points (Admin only)
-    /// Allows admin to mint more points to the total supply
-    ///
-    /// # Arguments
-    /// * `caller` - The player attempting to mint points
-    /// * `amount` - How many points to mint
-    ///
-    /// # Returns
-    /// * `Ok(())` - Points minted successfully
-    /// * `Err(NotAdmin)` - Caller is not the admin
-    async fn mint_points(&mut self, caller: PlayerId, amount: Amount) -> Result<(), ContractError> {
-        let config = self.state.config.get();
+        let position = market.positions.entry(player.id).or_insert(PlayerPosition {
+            shares_by_outcome: BTreeMap::new(),
+            total_invested: Amount::ZERO,
+            entry_time: current_time,
+        });
+        position.total_invested = position.total_invested.saturating_add(amount);
 
-        // Only admin can mint points
-        if let Some(admin) = config.admin {
-            if caller != admin {
-                return Err(ContractError::NotAdmin);
-            }
-        } else {
-            return Err(ContractError::NotAdmin);
+        let market_id = market.id;
+        self.record_trade(player, market, 0, OrderSide::Sell, amount, proceeds, fee)
+            .await?;
+
+        // Fees are paid once the seller is stored, so a provider selling in their own market keeps them
+        self.distribute_trading_fees(market_id, fee).await?;
+        Ok(fee)
+    }
+
+    /// Sell shares of one outcome back to a prediction market
+    /// The pool buys the shares back at the market's LMSR price, minus the market fee
+    async fn sell_outcome_shares(
+        &mut self,
+        mut player: Player,
+        mut market: Market,
+        outcome_id: Option<OutcomeId>,
+        shares: Amount,
+        limits: TradeLimits,
+    ) -> Result<Amount, ContractError> {
+        let outcome_id = Self::validate_outcome(&market, outcome_id)?;
+        if shares == Amount::ZERO {
+            return Err(ContractError::InsufficientBalance);
         }
 
-        // Increase total supply
-        let current_supply = self.state.total_supply.get();
-        self.state
-            .total_supply
-            .set(current_supply.saturating_add(amount));
+        let proceeds = lmsr_sell_proceeds(
+            &Self::outcome_quantities(&market),
+            market.liquidity_parameter,
+            outcome_id as usize,
+            shares,
+        );
+        let fee = self.calculate_market_fee(&market, proceeds);
+        let received = proceeds.saturating_sub(fee);
+
+        let position = market
+            .positions
+            .get_mut(&player.id)
+            .ok_or(ContractError::InsufficientBalance)?;
+        let held = position
+            .shares_by_outcome
+            .get(&outcome_id)
+            .copied()
+            .unwrap_or_default();
+        if held < shares {
+            return Err(ContractError::InsufficientBalance);
+        }
+        limits.check(shares, received)?;
+
+        // Reduce the invested amount in proportion to the shares sold
+        let total_held = position
+            .shares_by_outcome
+            .values()
+            .fold(Amount::ZERO, |total, shares| total.saturating_add(*shares));
+        let invested_sold = Amount::from_attos(mul_div(
+            position.total_invested.into(),
+            shares.into(),
+            total_held.into(),
+        ));
+        position.total_invested = position.total_invested.saturating_sub(invested_sold);
+        position
+            .shares_by_outcome
+            .insert(outcome_id, held.saturating_sub(shares));
+
+        market.total_liquidity = market.total_liquidity.saturating_sub(proceeds);
+        let outcome = &mut market.outcomes[outcome_id as usize];
+        outcome.total_shares = outcome.total_shares.saturating_sub(shares);
+
+        self.ledger_credit(
+            &mut player,
+            received,
+            LedgerAccount::market(market.id),
+            LedgerReason::Trade,
+        )
+        .await?;
+        player.total_earned = player.total_earned.saturating_add(received);
+        // A debtor's proceeds pay off the debt first
+        self.repay_debt(&mut player, received).await?;
+
+        let market_id = market.id;
+        self.record_trade(
+            player,
+            market,
+            outcome_id,
+            OrderSide::Sell,
+            shares,
+            proceeds,
+            fee,
+        )
+        .await?;
 
-        Ok(())
+        // Fees are paid once the seller is stored, so a provider selling in their own market keeps them
+        self.distribute_trading_fees(market_id, fee).await?;
+        Ok(fee)
     }
 
-    /// Create a new guild
-    /// Allows players to form social groups for collaborative gameplay
+    /// Close a market to further trading
+    /// Positions stay open until the market is resolved or cancelled
     ///
     /// # Arguments
-    /// * `founder` - The player creating the guild
-    /// * `name` - The name of the guild
-    /// * `current_time` - Current timestamp for guild creation
+    /// * `caller` - The player closing the market (creator or admin)
+    /// * `market_id` - The market to close
     ///
     /// # Returns
-    /// * `Ok(())` - Guild created successfully
-    /// * `Err(AlreadyInGuild)` - Founder is already in a guild
-    async fn create_guild(
+    /// * `Ok(())` - Market closed successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(Unauthorized)` - Caller is neither the market creator nor the admin
+    /// * `Err(MarketNotActive)` - Market is not active
+    async fn close_market(
         &mut self,
-        founder: PlayerId,
-        name: String,
-        current_time: Timestamp,
+        caller: PlayerId,
+        market_id: MarketId,
     ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&founder).await?;
-        if player.guild_id.is_some() {
-            return Err(ContractError::AlreadyInGuild);
-        }
-        let new_id = self.next_guild_id().await?;
-        let guild = Guild {
-            id: new_id,
-            name: name.clone(),
-            founder,
-            members: vec![founder],
-            creation_time: current_time,
-            total_guild_profit: Amount::ZERO,
-            guild_level: 1,
-            shared_pool: Amount::ZERO,
-        };
-        self.state.guilds.insert(&new_id, guild)?;
-        player.guild_id = Some(new_id);
-        self.state.players.insert(&founder, player)?;
+        let mut market = self.touch_market(market_id).await?;
+        self.ensure_market_authority(caller, &market)?;
 
-        self.runtime
-            .prepare_message(Message::GuildCreated {
-                guild_id: new_id,
-                name: name.clone(),
-            })
-            .send_to(self.runtime.chain_id());
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
 
-        // Broadcast guild creation to all chains for horizontal scaling
-        self.broadcast_global_guild_created(new_id, name, founder)
-            .await;
-        Ok(())
+        self.release_orders(&mut market).await?;
+        self.set_market_status(market, MarketStatus::Closed).await
     }
 
-    /// Join an existing guild
-    /// Allows players to join guilds created by other players
+    /// Cancel a market
+    /// Refunds what players invested in open positions and returns the remaining liquidity to the creator
     ///
     /// # Arguments
-    /// * `player_id` - The player joining the guild
-    /// * `guild_id` - The guild to join
+    /// * `caller` - The player cancelling the market (creator or admin)
+    /// * `market_id` - The market to cancel
     ///
     /// # Returns
-    /// * `Ok(())` - Successfully joined guild
-    /// * `Err(AlreadyInGuild)` - Player is already in a guild
-    /// * `Err(GuildNotFound)` - Guild doesn't exist
-    async fn join_guild(
+    /// * `Ok(())` - Market cancelled successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(Unauthorized)` - Caller is neither the market creator nor the admin
+    /// * `Err(MarketNotActive)` - Market is already resolved or cancelled
+    async fn cancel_market(
         &mut self,
-        player_id: PlayerId,
-        guild_id: GuildId,
+        caller: PlayerId,
+        market_id: MarketId,
     ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-        if player.guild_id.is_some() {
-            return Err(ContractError::AlreadyInGuild);
+        let mut market = self.touch_market(market_id).await?;
+        self.ensure_market_authority(caller, &market)?;
+
+        if !matches!(market.status, MarketStatus::Active | MarketStatus::Closed)
+            || market.resolution.is_some()
+        {
+            return Err(ContractError::MarketNotActive);
         }
-        let mut guild = self
-            .state
-            .guilds
-            .get(&guild_id)
-            .await?
-            .ok_or(ContractError::GuildNotFound)?;
-        guild.members.push(player_id);
-        self.state.guilds.insert(&guild_id, guild)?;
-        player.guild_id = Some(guild_id);
-        self.state.players.insert(&player_id, player.clone())?;
 
-        // Check for achievements after joining guild
-        self.check_achievements(&mut player).await?;
+        // Resting orders hand their escrow back first, so the refunds below see every share
+        self.release_orders(&mut market).await?;
+
+        // Refund open positions (positions that still hold shares)
+        for (holder, position) in market.positions.iter_mut() {
+            let holds_shares = position
+                .shares_by_outcome
+                .values()
+                .any(|shares| *shares > Amount::ZERO);
+            if !holds_shares {
+                continue;
+            }
 
-        Ok(())
-    }
+            let mut account = self.get_account(holder).await?;
+            self.ledger_credit(
+                &mut account,
+                position.total_invested,
+                LedgerAccount::market(market.id),
+                LedgerReason::MarketRefund,
+            )
+            .await?;
+            self.store_account(account).await?;
+            market.total_liquidity = market
+                .total_liquidity
+                .saturating_sub(position.total_invested);
 
-    /// Leave the current guild
-    /// Allows players to leave their current guild
-    ///
-    /// # Arguments
-    /// * `player_id` - The player leaving the guild
-    ///
-    /// # Returns
-    /// * `Ok(())` - Successfully left guild
-    /// * `Err(NotGuildMember)` - Player is not in a guild
-    async fn leave_guild(&mut self, player_id: PlayerId) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-        let guild_id = player.guild_id.ok_or(ContractError::NotGuildMember)?;
-        let mut guild = self
-            .state
-            .guilds
-            .get(&guild_id)
-            .await?
-            .ok_or(ContractError::GuildNotFound)?;
-        guild.members.retain(|m| m != &player_id);
-        self.state.guilds.insert(&guild_id, guild)?;
-        player.guild_id = None;
-        self.state.players.insert(&player_id, player)?;
-        Ok(())
+            position.shares_by_outcome.clear();
+            position.total_invested = Amount::ZERO;
+        }
+
+        for outcome in market.outcomes.iter_mut() {
+            outcome.total_shares = Amount::ZERO;
+        }
+
+        self.return_market_liquidity(&mut market).await?;
+        self.set_market_status(market, MarketStatus::Cancelled)
+            .await
     }
 
-    /// Contribute tokens to the guild's shared pool
-    /// Allows guild members to contribute tokens to the guild's collective fund
+    /// Resolve a market (Admin only)
+    /// Pays holders of the winning outcome one point per share out of the pool of a prediction market,
+    /// then returns the remaining liquidity to the creator.
+    /// With a dispute period configured, a prediction market's outcome is only proposed:
+    /// the creator may propose too by posting the challenge bond, and payouts wait for the period to end
     ///
     /// # Arguments
-    /// * `player_id` - The player contributing tokens
-    /// * `amount` - How many tokens to contribute
+    /// * `caller` - The player resolving the market
+    /// * `market_id` - The market to resolve
+    /// * `winning_outcome` - The outcome that happened (prediction markets only)
     ///
     /// # Returns
-    /// * `Ok(())` - Contribution successful
-    /// * `Err(NotGuildMember)` - Player is not in a guild
-    /// * `Err(InsufficientBalance)` - Player doesn't have enough tokens
-    async fn contribute_to_guild(
+    /// * `Ok(())` - Market resolved, or its resolution proposed, successfully
+    /// * `Err(NotAdmin)` - Caller is neither the admin nor a creator allowed to propose
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(MarketNotActive)` - Market is already resolved, cancelled or has a pending resolution
+    /// * `Err(OracleMarket)` - The market resolves from the price feed instead
+    /// * `Err(ResolutionTooEarly)` - The market's resolution time hasn't come yet
+    /// * `Err(InvalidOutcome)` - Outcome is missing or unknown for a prediction market, or given for a point market
+    /// * `Err(InsufficientBalance)` - The creator can't post the challenge bond
+    async fn resolve_market(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        winning_outcome: Option<OutcomeId>,
+    ) -> Result<(), ContractError> {
+        // Only admin can resolve markets outright; creators have a stake in the result,
+        // so they may only propose an outcome that can be disputed
+        let config = self.state.config.get().clone();
+        let is_admin = config.admin == Some(caller);
+        let is_proposer = config.dispute_period > 0
+            && self
+                .state
+                .markets
+                .get(&market_id)
+                .await?
+                .is_some_and(|market| {
+                    market.creator == caller && market.market_type == MarketType::Prediction
+                });
+        if !is_admin && !is_proposer {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let mut market = self.touch_market(market_id).await?;
+        if !matches!(market.status, MarketStatus::Active | MarketStatus::Closed)
+            || market.resolution.is_some()
+        {
+            return Err(ContractError::MarketNotActive);
+        }
+        if market.oracle.is_some() {
+            return Err(ContractError::OracleMarket);
+        }
+
+        let current_time = self.runtime.system_time();
+        if market
+            .resolution_time
+            .is_some_and(|resolution_time| current_time < resolution_time)
+        {
+            return Err(ContractError::ResolutionTooEarly);
+        }
+
+        if market.market_type == MarketType::Prediction {
+            let outcome_id = Self::validate_outcome(&market, winning_outcome)?;
+            if config.dispute_period > 0 {
+                let bond = if is_admin {
+                    Amount::ZERO
+                } else {
+                    config.challenge_bond
+                };
+                return self
+                    .propose_resolution(market, caller, outcome_id, bond, current_time)
+                    .await;
+            }
+            self.settle_outcome(&mut market, outcome_id).await?;
+        } else if winning_outcome.is_some() {
+            return Err(ContractError::InvalidOutcome);
+        }
+
+        self.return_market_liquidity(&mut market).await?;
+        self.set_market_status(market, MarketStatus::Resolved).await
+    }
+
+    /// Record a proposed outcome and start its dispute period
+    /// Trading stops and resting orders are released; payouts wait until the deadline passes
+    async fn propose_resolution(
+        &mut self,
+        mut market: Market,
+        proposer: PlayerId,
+        outcome_id: OutcomeId,
+        bond: Amount,
+        current_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        if bond > Amount::ZERO {
+            let mut player = self.get_player(&proposer).await?;
+            self.ledger_debit(
+                &mut player,
+                bond,
+                LedgerAccount::market(market.id),
+                LedgerReason::DisputeBond,
+            )
+            .await?;
+            self.state.players.insert(&proposer, player)?;
+        }
+
+        self.release_orders(&mut market).await?;
+        let dispute_period = self.state.config.get().dispute_period;
+        let deadline = Timestamp::from(current_time.micros().saturating_add(dispute_period));
+        market.resolution = Some(PendingResolution {
+            proposer,
+            outcome_id,
+            proposer_bond: bond,
+            deadline,
+            challenger: None,
+            challenge_outcome: None,
+            challenge_bond: Amount::ZERO,
+            uphold_weight: 0,
+            overturn_weight: 0,
+            voters: Vec::new(),
+        });
+        self.state
+            .pending_resolutions
+            .insert(&market.id, deadline)?;
+
+        self.emit(Message::ResolutionProposed {
+            market_id: market.id,
+            outcome_id,
+            deadline,
+        });
+        self.set_market_status(market, MarketStatus::Closed).await
+    }
+
+    /// Challenge a proposed resolution by posting the challenge bond
+    /// The market becomes disputed and position holders vote until a new deadline
+    ///
+    /// # Arguments
+    /// * `caller` - The player challenging the resolution
+    /// * `market_id` - The market whose resolution is challenged
+    /// * `outcome_id` - The outcome the challenger says should win
+    /// * `current_time` - Current timestamp
+    ///
+    /// # Returns
+    /// * `Ok(())` - Resolution challenged successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(NotDisputable)` - No unchallenged resolution is pending, or its dispute period has ended
+    /// * `Err(Unauthorized)` - Caller holds no position in the market, or proposed the resolution
+    /// * `Err(InvalidOutcome)` - Outcome is unknown or the one already proposed
+    /// * `Err(InsufficientBalance)` - Caller can't post the challenge bond
+    async fn challenge_resolution(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        current_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let mut market = self.get_market(&market_id).await?;
+        let Some(resolution) = market.resolution.as_ref() else {
+            return Err(ContractError::NotDisputable);
+        };
+        if market.status != MarketStatus::Closed
+            || resolution.challenger.is_some()
+            || current_time >= resolution.deadline
+        {
+            return Err(ContractError::NotDisputable);
+        }
+        if !market.positions.contains_key(&caller) || caller == resolution.proposer {
+            return Err(ContractError::Unauthorized);
+        }
+        if Self::validate_outcome(&market, Some(outcome_id))? == resolution.outcome_id {
+            return Err(ContractError::InvalidOutcome);
+        }
+
+        let config = self.state.config.get().clone();
+        let mut challenger = self.get_player(&caller).await?;
+        self.ledger_debit(
+            &mut challenger,
+            config.challenge_bond,
+            LedgerAccount::market(market.id),
+            LedgerReason::DisputeBond,
+        )
+        .await?;
+        self.state.players.insert(&caller, challenger)?;
+
+        // Voting gets a full dispute period of its own
+        let deadline = Timestamp::from(current_time.micros().saturating_add(config.dispute_period));
+        if let Some(resolution) = market.resolution.as_mut() {
+            resolution.challenger = Some(caller);
+            resolution.challenge_outcome = Some(outcome_id);
+            resolution.challenge_bond = config.challenge_bond;
+            resolution.deadline = deadline;
+        }
+        self.state
+            .pending_resolutions
+            .insert(&market_id, deadline)?;
+
+        self.emit(Message::ResolutionChallenged {
+            market_id,
+            challenger: caller,
+            outcome_id,
+        });
+        self.set_market_status(market, MarketStatus::Disputed).await
+    }
+
+    /// Vote on a disputed resolution, weighted by the voter's reputation
+    /// When voting ends the side with more weight wins; a tie upholds the proposal
+    ///
+    /// # Arguments
+    /// * `caller` - The player voting
+    /// * `market_id` - The disputed market
+    /// * `uphold` - Back the proposed outcome (true) or the challenger's (false)
+    /// * `current_time` - Current timestamp
+    ///
+    /// # Returns
+    /// * `Ok(())` - Vote recorded successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(NotDisputed)` - Market is not disputed, or voting has ended
+    /// * `Err(Unauthorized)` - Caller holds no position in the market, or is a party to the dispute
+    /// * `Err(AlreadyVoted)` - Caller already voted on this dispute
+    async fn vote_on_dispute(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        uphold: bool,
+        current_time: Timestamp,
+    ) -> Result<(), ContractError> {
+        let mut market = self.get_market(&market_id).await?;
+        let voter = self.get_player(&caller).await?;
+        let holds_position = market.positions.contains_key(&caller);
+        let Some(resolution) = market.resolution.as_mut() else {
+            return Err(ContractError::NotDisputed);
+        };
+        if market.status != MarketStatus::Disputed || current_time >= resolution.deadline {
+            return Err(ContractError::NotDisputed);
+        }
+        if !holds_position || caller == resolution.proposer || resolution.challenger == Some(caller)
+        {
+            return Err(ContractError::Unauthorized);
+        }
+        if resolution.voters.contains(&caller) {
+            return Err(ContractError::AlreadyVoted);
+        }
+
+        // Every participant counts, more so with reputation behind them
+        let weight = voter.reputation.max(1);
+        if uphold {
+            resolution.uphold_weight = resolution.uphold_weight.saturating_add(weight);
+        } else {
+            resolution.overturn_weight = resolution.overturn_weight.saturating_add(weight);
+        }
+        resolution.voters.push(caller);
+
+        self.state.markets.insert(&market_id, market)?;
+        Ok(())
+    }
+
+    /// Decide a disputed resolution without waiting for the vote (Admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The player settling the dispute
+    /// * `market_id` - The disputed market
+    /// * `uphold` - Keep the proposed outcome (true) or pay out the challenger's (false)
+    ///
+    /// # Returns
+    /// * `Ok(())` - Dispute settled and the market resolved
+    /// * `Err(NotAdmin)` - Caller is not the admin
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(NotDisputed)` - Market is not disputed
+    async fn settle_dispute(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        uphold: bool,
+    ) -> Result<(), ContractError> {
+        if self.state.config.get().admin != Some(caller) {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let market = self.get_market(&market_id).await?;
+        if market.status != MarketStatus::Disputed {
+            return Err(ContractError::NotDisputed);
+        }
+        self.finalize_resolution(market, uphold).await
+    }
+
+    /// Close every active market whose close time has passed
+    /// Runs before each operation, like `process_resolution_deadlines`, so an operation that
+    /// fails on the closed market can't roll the close back
+    async fn process_market_closes(&mut self, current_time: Timestamp) {
+        let mut due = Vec::new();
+        // Close times that can't be read now are picked up by a later operation
+        let _ = self
+            .state
+            .market_close_times
+            .for_each_index_value(|market_id, close_time| {
+                if *close_time <= current_time {
+                    due.push(market_id);
+                }
+                Ok(())
+            })
+            .await;
+
+        self.checkpoint().await;
+        for market_id in due {
+            match self.touch_market(market_id).await {
+                Ok(_) => self.checkpoint().await,
+                Err(_) => self.rollback(),
+            }
+        }
+    }
+
+    /// Resolve every market whose dispute period or vote has ended
+    /// Runs before each operation, so deadlines are enforced without a separate trigger
+    async fn process_resolution_deadlines(&mut self, current_time: Timestamp) {
+        let mut due = Vec::new();
+        // Deadlines that can't be read now are picked up by a later operation
+        let _ = self
+            .state
+            .pending_resolutions
+            .for_each_index_value(|market_id, deadline| {
+                if *deadline <= current_time {
+                    due.push(market_id);
+                }
+                Ok(())
+            })
+            .await;
+
+        // Each market pays out on its own: one that fails is rolled back and
+        // stays pending, so it can't block every operation on the chain
+        self.checkpoint().await;
+        for market_id in due {
+            match self.finalize_due_resolution(market_id).await {
+                Ok(()) => self.checkpoint().await,
+                Err(_) => self.rollback(),
+            }
+        }
+    }
+
+    /// Finalize a resolution whose dispute period has ended, by the weight of its votes
+    async fn finalize_due_resolution(&mut self, market_id: MarketId) -> Result<(), ContractError> {
+        let market = self.get_market(&market_id).await?;
+        // An unchallenged proposal has no votes against it and stands
+        let upheld = market
+            .resolution
+            .as_ref()
+            .is_none_or(|resolution| resolution.uphold_weight >= resolution.overturn_weight);
+        self.finalize_resolution(market, upheld).await
+    }
+
+    /// Pay out a proposed resolution, or the challenger's outcome when it is overturned
+    /// The winning side takes back its bond along with the losing side's
+    async fn finalize_resolution(
+        &mut self,
+        mut market: Market,
+        upheld: bool,
+    ) -> Result<(), ContractError> {
+        self.state.pending_resolutions.remove(&market.id)?;
+        let Some(resolution) = market.resolution.take() else {
+            return Ok(());
+        };
+
+        let (outcome_id, winner) = match (upheld, resolution.challenger) {
+            (false, Some(challenger)) => (
+                resolution
+                    .challenge_outcome
+                    .unwrap_or(resolution.outcome_id),
+                challenger,
+            ),
+            _ => (resolution.outcome_id, resolution.proposer),
+        };
+        let bonds = resolution
+            .proposer_bond
+            .saturating_add(resolution.challenge_bond);
+        if bonds > Amount::ZERO {
+            let mut player = self.get_player(&winner).await?;
+            self.ledger_credit(
+                &mut player,
+                bonds,
+                LedgerAccount::market(market.id),
+                LedgerReason::DisputeBond,
+            )
+            .await?;
+            self.state.players.insert(&winner, player)?;
+        }
+
+        let market_id = market.id;
+        self.settle_outcome(&mut market, outcome_id).await?;
+        self.return_market_liquidity(&mut market).await?;
+        self.set_market_status(market, MarketStatus::Resolved)
+            .await?;
+
+        if resolution.challenger.is_some() {
+            self.emit(Message::DisputeSettled { market_id, upheld });
+        }
+        Ok(())
+    }
+
+    /// Deposit points into a market's liquidity pool in exchange for LP shares
+    /// Shares are minted in proportion to the pool's current value, so existing providers aren't diluted.
+    /// Points only move between the provider and the market, so total supply is unchanged;
+    /// in a constant-product market the extra depth lowers the price
+    ///
+    /// # Arguments
+    /// * `caller` - The player providing liquidity
+    /// * `market_id` - The market to add liquidity to
+    /// * `amount` - How many points to deposit
+    ///
+    /// # Returns
+    /// * `Ok(())` - Liquidity added successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(MarketNotActive)` - Market is not active
+    /// * `Err(InsufficientBalance)` - Amount is more than the provider holds, or too small to mint a share
+    /// * `Err(InDebt)` - Provider owes unpaid penalties
+    async fn add_liquidity(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let mut market = self.touch_market(market_id).await?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+
+        let mut provider = self.get_player(&caller).await?;
+        Self::ensure_no_debt(&provider)?;
+        let pool_value = market
+            .total_liquidity
+            .saturating_add(market.payment_reserve);
+        let shares = if market.total_lp_shares == Amount::ZERO || pool_value == Amount::ZERO {
+            amount
+        } else {
+            Amount::from_attos(mul_div(
+                amount.into(),
+                market.total_lp_shares.into(),
+                pool_value.into(),
+            ))
+        };
+        if shares == Amount::ZERO || provider.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        self.ledger_debit(
+            &mut provider,
+            amount,
+            LedgerAccount::market(market_id),
+            LedgerReason::LiquidityDeposit,
+        )
+        .await?;
+        self.state.players.insert(&caller, provider)?;
+
+        market.total_liquidity = market.total_liquidity.saturating_add(amount);
+        market.total_lp_shares = market.total_lp_shares.saturating_add(shares);
+        let position = market
+            .liquidity_positions
+            .entry(caller)
+            .or_insert(LiquidityPosition {
+                market_id,
+                provider: caller,
+                shares: Amount::ZERO,
+                deposited: Amount::ZERO,
+                earned: Amount::ZERO,
+            });
+        position.shares = position.shares.saturating_add(shares);
+        position.deposited = position.deposited.saturating_add(amount);
+        self.state.markets.insert(&market_id, market)?;
+
+        self.emit(Message::LiquidityAdded {
+            market_id,
+            provider: caller,
+            amount,
+            shares,
+        });
+        Ok(())
+    }
+
+    /// Redeem LP shares for their part of a market's liquidity and payment reserve
+    /// Only liquidity the market doesn't need to honour its traders can be withdrawn
+    ///
+    /// # Arguments
+    /// * `caller` - The liquidity provider
+    /// * `market_id` - The market to withdraw liquidity from
+    /// * `shares` - How many LP shares to redeem
+    ///
+    /// # Returns
+    /// * `Ok(())` - Liquidity withdrawn successfully
+    /// * `Err(MarketNotFound)` - Market doesn't exist
+    /// * `Err(MarketNotActive)` - Market is already resolved or cancelled
+    /// * `Err(InsufficientBalance)` - Caller holds fewer shares, or the withdrawal would dip into the reserved liquidity
+    async fn withdraw_liquidity(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        shares: Amount,
+    ) -> Result<(), ContractError> {
+        let mut market = self.touch_market(market_id).await?;
+        if !matches!(market.status, MarketStatus::Active | MarketStatus::Closed) {
+            return Err(ContractError::MarketNotActive);
+        }
+
+        let held = market
+            .liquidity_positions
+            .get(&caller)
+            .map(|position| position.shares)
+            .unwrap_or_default();
+        if shares == Amount::ZERO || held < shares {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let total_shares = market.total_lp_shares.into();
+        let liquidity = Amount::from_attos(mul_div(
+            market.total_liquidity.into(),
+            shares.into(),
+            total_shares,
+        ));
+        let reserve = Amount::from_attos(mul_div(
+            market.payment_reserve.into(),
+            shares.into(),
+            total_shares,
+        ));
+        let reserved = Self::reserved_liquidity(&market);
+        if market.total_liquidity < reserved.saturating_add(liquidity) {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let amount = liquidity.saturating_add(reserve);
+        let mut provider = self.get_player(&caller).await?;
+        self.ledger_credit(
+            &mut provider,
+            amount,
+            LedgerAccount::market(market_id),
+            LedgerReason::LiquidityWithdrawal,
+        )
+        .await?;
+        // A debtor's withdrawal pays off the debt first
+        self.repay_debt(&mut provider, amount).await?;
+        self.state.players.insert(&caller, provider)?;
+
+        market.total_liquidity = market.total_liquidity.saturating_sub(liquidity);
+        market.payment_reserve = market.payment_reserve.saturating_sub(reserve);
+        market.total_lp_shares = market.total_lp_shares.saturating_sub(shares);
+        if held == shares {
+            market.liquidity_positions.remove(&caller);
+        } else if let Some(position) = market.liquidity_positions.get_mut(&caller) {
+            position.shares = position.shares.saturating_sub(shares);
+            position.deposited = position.deposited.saturating_sub(amount);
+        }
+        self.state.markets.insert(&market_id, market)?;
+
+        self.emit(Message::LiquidityWithdrawn {
+            market_id,
+            provider: caller,
+            amount,
+            shares,
+        });
+        Ok(())
+    }
+
+    /// Liquidity a market must keep to honour its traders
+    /// Prediction markets keep the LMSR cost C(q), which covers any payout, and at least what cancelling
+    /// would refund; constant-product pools keep one atto so their curve stays defined
+    fn reserved_liquidity(market: &Market) -> Amount {
+        match market.market_type {
+            MarketType::FixedRate => Amount::ZERO,
+            MarketType::ConstantProduct => Amount::from_attos(1),
+            MarketType::Prediction => {
+                let cost = lmsr_cost(
+                    &Self::outcome_quantities(market),
+                    market.liquidity_parameter,
+                );
+                let refunds = market
+                    .positions
+                    .values()
+                    .fold(Amount::ZERO, |total, position| {
+                        total.saturating_add(position.total_invested)
+                    });
+                cost.max(refunds)
+            }
+        }
+    }
+
+    /// Settle a prediction market on its winning outcome
+    /// Releases the order book, then pays the winners out of the pool before it goes back to its providers
+    async fn settle_outcome(
+        &mut self,
+        market: &mut Market,
+        outcome_id: OutcomeId,
+    ) -> Result<(), ContractError> {
+        self.release_orders(market).await?;
+        self.pay_out_winners(market, outcome_id).await?;
+        market.winning_outcome = Some(outcome_id);
+
+        self.emit(Message::MarketResolved {
+            market_id: market.id,
+            outcome_id,
+        });
+        Ok(())
+    }
+
+    /// Pay holders of the winning outcome one point per share out of the market pool
+    /// The LMSR cost function keeps the pool at least as large as any outcome's shares
+    async fn pay_out_winners(
+        &mut self,
+        market: &mut Market,
+        outcome_id: OutcomeId,
+    ) -> Result<(), ContractError> {
+        let mut paid_out = Amount::ZERO;
+        for (holder, position) in &market.positions {
+            let shares = position
+                .shares_by_outcome
+                .get(&outcome_id)
+                .copied()
+                .unwrap_or_default();
+            if shares == Amount::ZERO {
+                continue;
+            }
+
+            let payout = shares.min(market.total_liquidity.saturating_sub(paid_out));
+            let mut account = self.get_account(holder).await?;
+            self.ledger_credit(
+                &mut account,
+                payout,
+                LedgerAccount::market(market.id),
+                LedgerReason::MarketPayout,
+            )
+            .await?;
+            account.total_earned = account.total_earned.saturating_add(payout);
+            account.total_profit = account
+                .total_profit
+                .saturating_add(payout.saturating_sub(position.total_invested));
+            account.markets_won += 1;
+            self.store_account(account).await?;
+            paid_out = paid_out.saturating_add(payout);
+        }
+
+        market.total_liquidity = market.total_liquidity.saturating_sub(paid_out);
+        Ok(())
+    }
+
+    /// Mint points to a player (Admin only)
+    /// The recipient is credited and the total supply grows by the same amount
+    ///
+    /// # Arguments
+    /// * `caller` - The player attempting to mint points
+    /// * `recipient` - The player receiving the minted points
+    /// * `amount` - How many points to mint
+    ///
+    /// # Returns
+    /// * `Ok(())` - Points minted successfully
+    /// * `Err(NotAdmin)` - Caller is not the admin
+    /// * `Err(PlayerNotFound)` - Recipient is not registered on this chain
+    async fn mint_points(
+        &mut self,
+        caller: PlayerId,
+        recipient: PlayerId,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let config = self.state.config.get();
+
+        // Only admin can mint points
+        if let Some(admin) = config.admin {
+            if caller != admin {
+                return Err(ContractError::NotAdmin);
+            }
+        } else {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let player = self.get_player(&recipient).await?;
+        self.credit_minted_points(player, amount).await
+    }
+
+    /// Mint points to many players in one operation (Admin only)
+    /// Every recipient is checked before anyone is credited, and each credit emits its own event
+    ///
+    /// # Arguments
+    /// * `caller` - The player attempting the airdrop
+    /// * `credits` - The players to credit and how much each receives
+    ///
+    /// # Returns
+    /// * `Ok(())` - Every credit minted successfully
+    /// * `Err(NotAdmin)` - Caller is not the admin
+    /// * `Err(InvalidAirdrop)` - No credits, more than `max_airdrop_recipients`, or a zero amount
+    /// * `Err(PlayerNotFound)` - A recipient is not registered on this chain
+    async fn airdrop(
+        &mut self,
+        caller: PlayerId,
+        credits: Vec<AirdropCredit>,
+    ) -> Result<(), ContractError> {
+        let config = self.state.config.get();
+        if config.admin != Some(caller) {
+            return Err(ContractError::NotAdmin);
+        }
+        if credits.is_empty()
+            || credits.len() > config.max_airdrop_recipients as usize
+            || credits.iter().any(|credit| credit.amount == Amount::ZERO)
+        {
+            return Err(ContractError::InvalidAirdrop);
+        }
+
+        for credit in &credits {
+            if !self.state.players.contains_key(&credit.player_id).await? {
+                return Err(ContractError::PlayerNotFound);
+            }
+        }
+
+        // Loaded one at a time, so a player listed twice is credited twice
+        for credit in credits {
+            let player = self.get_player(&credit.player_id).await?;
+            self.credit_minted_points(player, credit.amount).await?;
+        }
+        Ok(())
+    }
+
+    /// Credit newly minted points to a player, grow the total supply and emit the mint event
+    async fn credit_minted_points(
+        &mut self,
+        mut player: Player,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let player_id = player.id;
+        self.ledger_credit(
+            &mut player,
+            amount,
+            LedgerAccount::ISSUANCE,
+            LedgerReason::Mint,
+        )
+        .await?;
+        player.total_earned = player.total_earned.saturating_add(amount);
+        self.state.players.insert(&player_id, player)?;
+
+        self.emit(Message::PointsMinted { player_id, amount });
+        Ok(())
+    }
+
+    /// Create a new guild
+    /// Allows players to form social groups for collaborative gameplay
+    ///
+    /// # Arguments
+    /// * `founder` - The player creating the guild
+    /// * `name` - The name of the guild
+    /// * `current_time` - Current timestamp for guild creation
+    ///
+    /// # Returns
+    /// * `Ok(guild_id)` - Guild created successfully
+    /// * `Err(AlreadyInGuild)` - Founder is already in a guild
+    async fn create_guild(
+        &mut self,
+        founder: PlayerId,
+        name: String,
+        current_time: Timestamp,
+    ) -> Result<GuildId, ContractError> {
+        let mut player = self.get_player(&founder).await?;
+        if player.guild_id.is_some() {
+            return Err(ContractError::AlreadyInGuild);
+        }
+        let new_id = self.next_guild_id().await?;
+        let guild = Guild {
+            id: new_id,
+            name: name.clone(),
+            founder,
+            members: vec![founder],
+            creation_time: current_time,
+            total_guild_profit: Amount::ZERO,
+            guild_level: 1,
+            shared_pool: Amount::ZERO,
+        };
+        self.state.guilds.insert(&new_id, guild)?;
+        player.guild_id = Some(new_id);
+        self.state.players.insert(&founder, player)?;
+
+        self.emit(Message::GuildCreated {
+            guild_id: new_id,
+            name: name.clone(),
+        });
+
+        // Broadcast guild creation to all chains for horizontal scaling
+        self.broadcast_global_guild_created(new_id, name, founder)
+            .await;
+        Ok(new_id)
+    }
+
+    /// Join an existing guild
+    /// Allows players to join guilds created by other players
+    ///
+    /// # Arguments
+    /// * `player_id` - The player joining the guild
+    /// * `guild_id` - The guild to join
+    ///
+    /// # Returns
+    /// * `Ok(())` - Successfully joined guild
+    /// * `Err(AlreadyInGuild)` - Player is already in a guild
+    /// * `Err(GuildNotFound)` - Guild doesn't exist
+    async fn join_guild(
+        &mut self,
+        player_id: PlayerId,
+        guild_id: GuildId,
+    ) -> Result<(), ContractError> {
+        let mut player = self.get_player(&player_id).await?;
+        if player.guild_id.is_some() {
+            return Err(ContractError::AlreadyInGuild);
+        }
+        let mut guild = self
+            .state
+            .guilds
+            .get(&guild_id)
+            .await?
+            .ok_or(ContractError::GuildNotFound)?;
+        guild.members.push(player_id);
+        self.state.guilds.insert(&guild_id, guild)?;
+        player.guild_id = Some(guild_id);
+        self.state.players.insert(&player_id, player.clone())?;
+
+        // Check for achievements after joining guild
+        self.check_achievements(&mut player).await?;
+
+        Ok(())
+    }
+
+    /// Leave the current guild
+    /// Allows players to leave their current guild
+    ///
+    /// # Arguments
+    /// * `player_id` - The player leaving the guild
+    ///
+    /// # Returns
+    /// * `Ok(guild_id)` - Successfully left guild
+    /// * `Err(NotGuildMember)` - Player is not in a guild
+    async fn leave_guild(&mut self, player_id: PlayerId) -> Result<GuildId, ContractError> {
+        let mut player = self.get_player(&player_id).await?;
+        let guild_id = player.guild_id.ok_or(ContractError::NotGuildMember)?;
+        let mut guild = self
+            .state
+            .guilds
+            .get(&guild_id)
+            .await?
+            .ok_or(ContractError::GuildNotFound)?;
+        guild.members.retain(|m| m != &player_id);
+        self.state.guilds.insert(&guild_id, guild)?;
+        player.guild_id = None;
+        self.state.players.insert(&player_id, player)?;
+        Ok(guild_id)
+    }
+
+    /// Contribute tokens to the guild's shared pool
+    /// Allows guild members to contribute tokens to the guild's collective fund
+    ///
+    /// # Arguments
+    /// * `player_id` - The player contributing tokens
+    /// * `amount` - How many tokens to contribute
+    ///
+    /// # Returns
+    /// * `Ok(guild_id)` - Contribution successful
+    /// * `Err(NotGuildMember)` - Player is not in a guild
+    /// * `Err(InsufficientBalance)` - Player doesn't have enough tokens
+    /// * `Err(InDebt)` - Player owes unpaid penalties
+    async fn contribute_to_guild(
+        &mut self,
+        player_id: PlayerId,
+        amount: Amount,
+    ) -> Result<GuildId, ContractError> {
+        let mut player = self.get_player(&player_id).await?;
+        let guild_id = player.guild_id.ok_or(ContractError::NotGuildMember)?;
+        Self::ensure_no_debt(&player)?;
+        if player.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        // Deduct contribution from player's points (no external transfer needed)
+
+        let mut guild = self
+            .state
+            .guilds
+            .get(&guild_id)
+            .await?
+            .ok_or(ContractError::GuildNotFound)?;
+        self.ledger_debit(
+            &mut player,
+            amount,
+            LedgerAccount::guild(guild_id),
+            LedgerReason::GuildContribution,
+        )
+        .await?;
+        guild.shared_pool = guild.shared_pool.saturating_add(amount);
+        self.state.players.insert(&player_id, player)?;
+        self.state.guilds.insert(&guild_id, guild)?;
+        Ok(guild_id)
+    }
+
+    /// Update the game configuration (Admin only)
+    /// Allows the admin to modify game parameters like token amounts and market settings
+    ///
+    /// # Arguments
+    /// * `caller` - The player attempting to update config
+    /// * `config` - The new game configuration
+    ///
+    /// # Returns
+    /// * `Ok(())` - Configuration updated successfully
+    /// * `Err(NotAdmin)` - Caller is not the admin
+    /// * `Err(InvalidConfig)` - The config drops the admin, has a zero fee divisor or exchange rate,
+    ///   a platform or prediction rake above 100%, stake limits with a zero or inverted range,
+    ///   or an asset list that is empty, repeats a symbol or has one that isn't alphanumeric
+    async fn update_game_config(
+        &mut self,
+        caller: PlayerId,
+        config: GameConfig,
+    ) -> Result<(), ContractError> {
+        let current = self.state.config.get();
+        if let Some(admin) = current.admin {
+            if caller != admin {
+                return Err(ContractError::NotAdmin);
+            }
+        } else {
+            return Err(ContractError::NotAdmin);
+        }
+
+        if config.admin.is_none() || !Self::config_valid(&config) {
+            return Err(ContractError::InvalidConfig);
+        }
+        self.state.config.set(config);
+        Ok(())
+    }
+
+    /// Check the settings the contract divides by or takes percentages of
+    /// Shared by instantiation and config updates, so no chain runs with a config it would panic on
+    fn config_valid(config: &GameConfig) -> bool {
+        config.fee_divisor != Amount::ZERO
+            && config.fixed_rate_exchange != 0
+            && config.platform_fee_percent <= 100
+            && config.prediction_rake_percent <= 100
+            && [
+                config.daily_stake,
+                config.weekly_stake,
+                config.monthly_stake,
+            ]
+            .iter()
+            .all(|limits| limits.min_stake != Amount::ZERO && limits.min_stake <= limits.max_stake)
+            && Self::assets_valid(&config.assets)
+    }
+
+    /// Asset symbols must be distinct and alphanumeric, as they are part of prediction keys
+    fn assets_valid(assets: &[AssetSymbol]) -> bool {
+        !assets.is_empty()
+            && assets.iter().enumerate().all(|(index, asset)| {
+                !asset.is_empty()
+                    && asset.chars().all(|c| c.is_ascii_alphanumeric())
+                    && !assets[..index].contains(asset)
+            })
+    }
+
+    // ============================================================================
+    // Single-File Prediction Market Game
+    // ============================================================================
+
+    /// This contract implements a complete prediction market game with:
+    /// - Player progression system (levels, reputation, achievements)
+    /// - Market operations (create, trade, resolve)
+    /// - Guild system (social features, shared pools)
+    /// - Points-based economy (no external tokens needed)
+    /// - Admin controls (game configuration)
+
+    // ============================================================================
+    // Cross-Chain Trading
+    // ============================================================================
+
+    /// Find a market that is hosted on another chain in the global registry
+    /// Market ids are only unique per chain, so the hosting chain is part of the lookup;
+    /// none (or this chain) means the market is local
+    ///
+    /// # Returns
+    /// * `Ok(Some(market))` - The market is remote
+    /// * `Ok(None)` - The market is on this chain
+    /// * `Err(MarketNotFound)` - The registry has no such market on that chain
+    async fn remote_market(
+        &mut self,
+        chain_id: Option<ChainId>,
+        market_id: MarketId,
+    ) -> Result<Option<GlobalMarketInfo>, ContractError> {
+        let current_chain = self.runtime.chain_id();
+        let Some(chain_id) = chain_id.filter(|chain_id| *chain_id != current_chain) else {
+            return Ok(None);
+        };
+        self.state
+            .global_markets
+            .get(&(chain_id, market_id))
+            .await?
+            .map(Some)
+            .ok_or(ContractError::MarketNotFound)
+    }
+
+    /// Escrow a trade on a remote market and send it to the chain hosting the market
+    /// Buys escrow their `max_payment` and point sales the points sold; outcome shares
+    /// are held on the hosting chain, so selling them escrows nothing.
+    /// The hosting chain answers with a settlement that returns the unused escrow and any proceeds
+    ///
+    /// # Arguments
+    /// * `player_id` - The player trading
+    /// * `market` - Registry entry of the remote market
+    /// * `outcome_id` - The outcome traded (prediction markets only)
+    /// * `side` - Buy or sell
+    /// * `amount` - How many points (or shares) to trade
+    /// * `limits` - Slippage limits and deadline, enforced by the hosting chain
+    /// * `current_time` - Current timestamp
+    ///
+    /// # Returns
+    /// * `Ok(fee)` - Trade request sent; the fee is only known at settlement, so it is zero
+    /// * `Err(MarketNotActive)` - The registry shows the market is no longer active
+    /// * `Err(MaxPaymentRequired)` - A buy without a `max_payment` to escrow
+    /// * `Err(PlayerNotFound)` - Player is not registered on this chain
+    /// * `Err(InsufficientBalance)` - Player can't cover the escrow
+    #[allow(clippy::too_many_arguments)]
+    async fn request_remote_trade(
+        &mut self,
+        player_id: PlayerId,
+        market: GlobalMarketInfo,
+        outcome_id: Option<OutcomeId>,
+        side: OrderSide,
+        amount: Amount,
+        limits: TradeLimits,
+        current_time: Timestamp,
+    ) -> Result<Amount, ContractError> {
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+
+        let escrow = match (side, outcome_id) {
+            (OrderSide::Buy, _) => limits
+                .max_payment
+                .ok_or(ContractError::MaxPaymentRequired)?,
+            (OrderSide::Sell, None) => amount,
+            (OrderSide::Sell, Some(_)) => Amount::ZERO,
+        };
+        let mut player = self.get_player(&player_id).await?;
+        // As on local markets, debtors may only sell outcome shares
+        if side == OrderSide::Buy || outcome_id.is_none() {
+            Self::ensure_no_debt(&player)?;
+        }
+        self.ledger_debit(
+            &mut player,
+            escrow,
+            LedgerAccount::chain(market.chain_id),
+            LedgerReason::RemoteTrade,
+        )
+        .await?;
+        let level = player.level;
+        self.state.players.insert(&player_id, player)?;
+
+        let trade_id = *self.state.next_remote_trade_id.get();
+        self.state.next_remote_trade_id.set(trade_id + 1);
+        self.state.remote_trades.insert(
+            &trade_id,
+            RemoteTrade {
+                id: trade_id,
+                player_id,
+                market_id: market.market_id,
+                chain_id: market.chain_id,
+                outcome_id,
+                side,
+                amount,
+                escrow,
+                refund: Amount::ZERO,
+                fee: Amount::ZERO,
+                status: RemoteTradeStatus::Pending,
+                error: None,
+                requested_at: current_time,
+            },
+        )?;
+
+        // Tracked, so a request the hosting chain can't take bounces back with its escrow
+        let home_chain = self.runtime.chain_id();
+        self.send_tracked(
+            Message::RemoteTradeRequest {
+                trade_id,
+                trader: player_id,
+                level,
+                home_chain,
+                market_id: market.market_id,
+                outcome_id,
+                side,
+                amount,
+                escrow,
+                max_payment: limits.max_payment,
+                min_received: limits.min_received,
+                deadline: limits.deadline,
+            },
+            market.chain_id,
+        );
+        Ok(Amount::ZERO)
+    }
+
+    /// Execute a trade requested from another chain against a market hosted here
+    /// The escrow is credited to the trader's account on this chain for the trade, and whatever it
+    /// leaves (unused escrow plus any proceeds) is taken back out to return to the home chain.
+    /// Traders without an account here get a hosted one that holds their positions; the trade runs
+    /// against it directly, so it never joins the registered players. Remote trades earn no
+    /// reputation, experience or achievements, as those would go home with the refund
+    ///
+    /// # Returns
+    /// * `Ok((refund, fee, error))` - Points to return, fee charged, and why the trade failed if it did
+    #[allow(clippy::too_many_arguments)]
+    async fn execute_remote_trade(
+        &mut self,
+        trader: PlayerId,
+        level: u32,
+        home_chain: ChainId,
+        market_id: MarketId,
+        outcome_id: Option<OutcomeId>,
+        side: OrderSide,
+        amount: Amount,
+        escrow: Amount,
+        limits: TradeLimits,
+    ) -> Result<(Amount, Amount, Option<OperationError>), ContractError> {
+        // Only markets stored here are traded, requests are never forwarded
+        if !self.state.markets.contains_key(&market_id).await? {
+            return Ok((escrow, Amount::ZERO, Some(OperationError::MarketNotFound)));
+        }
+
+        let current_time = self.runtime.system_time();
+        let mut account = match self.find_account(&trader).await? {
+            Some(account) => account,
+            None => {
+                self.state.remote_traders.insert(&trader, home_chain)?;
+                Player {
+                    id: trader,
+                    display_name: None,
+                    registration_time: current_time,
+                    last_login: current_time,
+                    token_balance: Amount::ZERO,
+                    total_earned: Amount::ZERO,
+                    total_spent: Amount::ZERO,
+                    level,
+                    experience_points: 0,
+                    reputation: 100,
+                    markets_participated: 0,
+                    markets_won: 0,
+                    total_profit: Amount::ZERO,
+                    win_streak: 0,
+                    best_win_streak: 0,
+                    guild_id: None,
+                    achievements_earned: Vec::new(),
+                    active_markets: Vec::new(),
+                    debt: Amount::ZERO,
+                }
+            }
+        };
+        account.level = account.level.max(level);
+        let balance_before = account.token_balance;
+        let home = LedgerAccount::chain(home_chain);
+        self.ledger_credit(&mut account, escrow, home, LedgerReason::RemoteTrade)
+            .await?;
+
+        let result = match side {
+            OrderSide::Buy => {
+                self.buy_with_account(
+                    account.clone(),
+                    market_id,
+                    outcome_id,
+                    amount,
+                    limits,
+                    current_time,
+                )
+                .await
+            }
+            OrderSide::Sell => {
+                self.sell_with_account(
+                    account.clone(),
+                    market_id,
+                    outcome_id,
+                    amount,
+                    limits,
+                    current_time,
+                )
+                .await
+            }
+        };
+
+        // A trade stores the account only once it succeeds, a failed one leaves it as credited
+        if result.is_ok() {
+            account = self.get_account(&trader).await?;
+        }
+        let refund = account.token_balance.saturating_sub(balance_before);
+        self.ledger_debit(&mut account, refund, home, LedgerReason::RemoteTrade)
+            .await?;
+        self.store_account(account).await?;
+
+        match result {
+            Ok(fee) => Ok((refund, fee, None)),
+            Err(ContractError::View(error)) => Err(ContractError::View(error)),
+            Err(error) => Ok((refund, Amount::ZERO, Some(error.into()))),
+        }
+    }
+
+    /// Finish a remote trade sent from this chain, returning its refund to the player
+    /// Each trade is closed once; later settlements of the same trade are ignored
+    async fn close_remote_trade(
+        &mut self,
+        trade_id: RemoteTradeId,
+        refund: Amount,
+        fee: Amount,
+        status: RemoteTradeStatus,
+        error: Option<String>,
+    ) -> Result<(), ContractError> {
+        let Some(mut trade) = self.state.remote_trades.get(&trade_id).await? else {
+            return Ok(());
+        };
+        if trade.status != RemoteTradeStatus::Pending {
+            return Ok(());
+        }
+
+        self.credit_player(
+            trade.player_id,
+            refund,
+            LedgerAccount::chain(trade.chain_id),
+            LedgerReason::RemoteTrade,
+        )
+        .await?;
+        trade.refund = refund;
+        trade.fee = fee;
+        trade.status = status;
+        trade.error = error;
+        self.state.remote_trades.insert(&trade_id, trade)?;
+        Ok(())
+    }
+
+    /// Add points to a player's balance on this chain, registered or hosted
+    /// Fails for a player without an account here, so that a message carrying the points is
+    /// rejected and bounces back to its sender instead of the points being lost
+    ///
+    /// # Returns
+    /// * `Ok(())` - Points credited
+    /// * `Err(PlayerNotFound)` - The player has no account on this chain
+    async fn credit_player(
+        &mut self,
+        player_id: PlayerId,
+        amount: Amount,
+        from: LedgerAccount,
+        reason: LedgerReason,
+    ) -> Result<(), ContractError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        let mut account = self
+            .find_account(&player_id)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)?;
+        self.ledger_credit(&mut account, amount, from, reason)
+            .await?;
+        self.store_account(account).await
+    }
+
+    /// Look up an account: a registered player's, or the hosted account of a trader from another chain
+    async fn find_account(&self, player_id: &PlayerId) -> Result<Option<Player>, ContractError> {
+        if let Some(player) = self.state.players.get(player_id).await? {
+            return Ok(Some(player));
+        }
+        Ok(self.state.hosted_accounts.get(player_id).await?)
+    }
+
+    /// Store an account with the registered players if it is one of them, with the hosted accounts otherwise
+    async fn store_account(&mut self, account: Player) -> Result<(), ContractError> {
+        if self.state.players.contains_key(&account.id).await? {
+            self.state.players.insert(&account.id, account)?;
+        } else {
+            self.state.hosted_accounts.insert(&account.id, account)?;
+        }
+        Ok(())
+    }
+
+    /// Ledger account of the chain that sent the message being executed
+    fn message_origin_account(&mut self) -> LedgerAccount {
+        let origin = self
+            .runtime
+            .message_origin_chain_id()
+            .expect("Incoming message has no origin chain");
+        LedgerAccount::chain(origin)
+    }
+
+    /// Send the balances of hosted accounts home once a market they hold positions in ends
+    /// Resolution payouts and cancellation refunds land in the hosted account first
+    async fn send_remote_payouts(
+        &mut self,
+        participants: &[PlayerId],
+    ) -> Result<(), ContractError> {
+        for participant in participants {
+            let Some(home_chain) = self.state.remote_traders.get(participant).await? else {
+                continue;
+            };
+            let Some(mut account) = self.state.hosted_accounts.get(participant).await? else {
+                continue;
+            };
+            let amount = account.token_balance;
+            if amount == Amount::ZERO {
+                continue;
+            }
+            self.ledger_debit(
+                &mut account,
+                amount,
+                LedgerAccount::chain(home_chain),
+                LedgerReason::RemoteTrade,
+            )
+            .await?;
+            self.state.hosted_accounts.insert(participant, account)?;
+
+            self.send_tracked(
+                Message::RemotePayout {
+                    player_id: *participant,
+                    amount,
+                },
+                home_chain,
+            );
+        }
+        Ok(())
+    }
+
+    // ============================================================================
+    // Order Book
+    // ============================================================================
+
+    /// Place a limit order for outcome shares on a prediction market's order book
+    /// The order first fills against resting orders on the other side in price-time priority,
+    /// then whatever is left rests on the book with its points (buy) or shares (sell) escrowed
+    ///
+    /// # Arguments
+    /// * `player_id` - The player placing the order
+    /// * `market_id` - The prediction market to trade on
+    /// * `outcome_id` - The outcome whose shares are traded
+    /// * `side` - Buy or sell
+    /// * `price` - Limit price in points per share (at most 1)
+    /// * `quantity` - Shares to trade
+    /// * `current_time` - Current timestamp, used for time priority
+    ///
+    /// # Returns
+    /// * `Ok((order_id, filled))` - Order placed, with the shares filled immediately
+    /// * `Err(InvalidPrice)` - Price is zero or above one point
+    /// * `Err(MarketNotActive)` - Market is not active
+    /// * `Err(InvalidOutcome)` - Outcome is unknown or the market is not a prediction market
+    /// * `Err(InsufficientBalance)` - Player can't cover the order's points or shares
+    /// * `Err(SelfCrossingOrder)` - Price crosses one of the player's own resting orders
+    /// * `Err(InDebt)` - Player owes unpaid penalties
+    #[allow(clippy::too_many_arguments)]
+    async fn place_limit_order(
+        &mut self,
+        player_id: PlayerId,
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        price: Amount,
+        quantity: Amount,
+        current_time: Timestamp,
+    ) -> Result<(OrderId, Amount), ContractError> {
+        // A share pays at most one point, so no sensible order is priced above that
+        if price == Amount::ZERO || price > Amount::ONE {
+            return Err(ContractError::InvalidPrice);
+        }
+
+        let mut player = self.get_player(&player_id).await?;
+        Self::ensure_no_debt(&player)?;
+        let mut market = self.get_order_market(market_id, outcome_id).await?;
+        Self::check_order_funds(&player, &market, outcome_id, side, Some(price), quantity)?;
+
+        let mut book = self
+            .state
+            .order_books
+            .get(&market_id)
+            .await?
+            .unwrap_or_default();
+        // Matching skips the player's own orders, so what is left would rest in a crossed book
+        if Self::crosses_own_order(&book, player_id, outcome_id, side, price) {
+            return Err(ContractError::SelfCrossingOrder);
+        }
+        let remaining = self
+            .match_order(
+                &mut player,
+                &mut market,
+                &mut book,
+                outcome_id,
+                side,
+                Some(price),
+                quantity,
+            )
+            .await?;
+
+        let order_id = *self.state.next_order_id.get();
+        self.state.next_order_id.set(order_id + 1);
+
+        if remaining > Amount::ZERO {
+            // Escrow what the resting order needs to settle its fills
+            let escrow = match side {
+                OrderSide::Buy => {
+                    let escrow = Self::order_value(price, remaining);
+                    self.ledger_debit(
+                        &mut player,
+                        escrow,
+                        LedgerAccount::market(market_id),
+                        LedgerReason::OrderEscrow,
+                    )
+                    .await?;
+                    escrow
+                }
+                OrderSide::Sell => Self::take_shares(&mut market, player_id, outcome_id, remaining),
+            };
+
+            let order = Order {
+                id: order_id,
+                market_id,
+                outcome_id,
+                owner: player_id,
+                side,
+                price,
+                quantity,
+                remaining,
+                escrow,
+                created_at: current_time,
+            };
+            match side {
+                OrderSide::Buy => {
+                    let index = book
+                        .bids
+                        .iter()
+                        .position(|resting| resting.price < price)
+                        .unwrap_or(book.bids.len());
+                    book.bids.insert(index, order);
+                }
+                OrderSide::Sell => {
+                    let index = book
+                        .asks
+                        .iter()
+                        .position(|resting| resting.price > price)
+                        .unwrap_or(book.asks.len());
+                    book.asks.insert(index, order);
+                }
+            }
+        }
+
+        self.store_order_trade(player, market, book).await?;
+        Ok((order_id, quantity.saturating_sub(remaining)))
+    }
+
+    /// Place a market order for outcome shares on a prediction market's order book
+    /// Fills against resting orders at the best prices available; whatever can't be filled is dropped
+    ///
+    /// # Arguments
+    /// * `player_id` - The player placing the order
+    /// * `market_id` - The prediction market to trade on
+    /// * `outcome_id` - The outcome whose shares are traded
+    /// * `side` - Buy or sell
+    /// * `quantity` - Shares to trade
+    ///
+    /// # Returns
+    /// * `Ok(filled)` - Order filled, fully or partially
+    /// * `Err(MarketNotActive)` - Market is not active
+    /// * `Err(InvalidOutcome)` - Outcome is unknown or the market is not a prediction market
+    /// * `Err(InsufficientBalance)` - Player doesn't hold the shares to sell
+    /// * `Err(NoMatchingOrders)` - Nothing on the book could be filled
+    /// * `Err(InDebt)` - Player owes unpaid penalties
+    async fn place_market_order(
+        &mut self,
+        player_id: PlayerId,
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        quantity: Amount,
+    ) -> Result<Amount, ContractError> {
+        let mut player = self.get_player(&player_id).await?;
+        Self::ensure_no_debt(&player)?;
+        let mut market = self.get_order_market(market_id, outcome_id).await?;
+        Self::check_order_funds(&player, &market, outcome_id, side, None, quantity)?;
+
+        let mut book = self
+            .state
+            .order_books
+            .get(&market_id)
+            .await?
+            .unwrap_or_default();
+        let remaining = self
+            .match_order(
+                &mut player,
+                &mut market,
+                &mut book,
+                outcome_id,
+                side,
+                None,
+                quantity,
+            )
+            .await?;
+        if remaining == quantity {
+            return Err(ContractError::NoMatchingOrders);
+        }
+
+        self.store_order_trade(player, market, book).await?;
+        Ok(quantity.saturating_sub(remaining))
+    }
+
+    /// Cancel a resting limit order and release its escrow to its owner
+    ///
+    /// # Arguments
+    /// * `caller` - The player cancelling the order (must own it)
+    /// * `market_id` - The market the order rests on
+    /// * `order_id` - The order to cancel
+    ///
+    /// # Returns
+    /// * `Ok(())` - Order cancelled successfully
+    /// * `Err(OrderNotFound)` - No such open order on the market
+    /// * `Err(Unauthorized)` - Caller doesn't own the order
+    async fn cancel_order(
+        &mut self,
+        caller: PlayerId,
+        market_id: MarketId,
+        order_id: OrderId,
+    ) -> Result<(), ContractError> {
+        let mut book = self
+            .state
+            .order_books
+            .get(&market_id)
+            .await?
+            .ok_or(ContractError::OrderNotFound)?;
+
+        let order = if let Some(index) = book.bids.iter().position(|order| order.id == order_id) {
+            if book.bids[index].owner != caller {
+                return Err(ContractError::Unauthorized);
+            }
+            book.bids.remove(index)
+        } else if let Some(index) = book.asks.iter().position(|order| order.id == order_id) {
+            if book.asks[index].owner != caller {
+                return Err(ContractError::Unauthorized);
+            }
+            book.asks.remove(index)
+        } else {
+            return Err(ContractError::OrderNotFound);
+        };
+
+        let mut market = self.touch_market(market_id).await?;
+        self.release_order(&mut market, order).await?;
+        self.state.markets.insert(&market_id, market)?;
+
+        if book.bids.is_empty() && book.asks.is_empty() {
+            self.state.order_books.remove(&market_id)?;
+        } else {
+            self.state.order_books.insert(&market_id, book)?;
+        }
+        Ok(())
+    }
+
+    /// Match an incoming order against the other side of the book in price-time priority
+    /// Every fill trades at the resting order's price, moves shares and points between the two players
+    /// and emits a TradeExecuted event; orders of the taker itself are skipped
+    /// Returns the quantity left unfilled
+    #[allow(clippy::too_many_arguments)]
+    async fn match_order(
+        &mut self,
+        taker: &mut Player,
+        market: &mut Market,
+        book: &mut OrderBook,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        limit_price: Option<Amount>,
+        quantity: Amount,
+    ) -> Result<Amount, ContractError> {
+        let resting = match side {
+            OrderSide::Buy => &mut book.asks,
+            OrderSide::Sell => &mut book.bids,
+        };
+
+        let mut remaining = quantity;
+        let mut index = 0;
+        while remaining > Amount::ZERO && index < resting.len() {
+            if resting[index].outcome_id != outcome_id || resting[index].owner == taker.id {
+                index += 1;
+                continue;
+            }
+
+            // The book is sorted by price, so the first order that doesn't cross ends matching
+            let price = resting[index].price;
+            let crosses = match (side, limit_price) {
+                (_, None) => true,
+                (OrderSide::Buy, Some(limit)) => price <= limit,
+                (OrderSide::Sell, Some(limit)) => price >= limit,
+            };
+            if !crosses {
+                break;
+            }
+
+            let mut fill = remaining.min(resting[index].remaining);
+            if side == OrderSide::Buy {
+                // A market buy only fills what the taker can pay for
+                let affordable = mul_div(taker.token_balance.into(), ONE, price.into());
+                fill = fill.min(Amount::from_attos(affordable));
+            }
+            if fill == Amount::ZERO {
+                break;
+            }
+
+            let value = Self::order_value(price, fill);
+            let maker_id = resting[index].owner;
+            let mut maker = self.get_player(&maker_id).await?;
+            match side {
+                OrderSide::Buy => {
+                    // The taker pays the maker and receives the escrowed shares with their invested amount
+                    let order = &mut resting[index];
+                    let invested = Amount::from_attos(mul_div(
+                        order.escrow.into(),
+                        fill.into(),
+                        order.remaining.into(),
+                    ));
+                    order.escrow = order.escrow.saturating_sub(invested);
+
+                    self.ledger_transfer(taker, &mut maker, value, LedgerReason::OrderFill)
+                        .await?;
+                    taker.total_spent = taker.total_spent.saturating_add(value);
+                    maker.total_earned = maker.total_earned.saturating_add(value);
+                    Self::give_shares(
+                        market,
+                        taker.id,
+                        outcome_id,
+                        fill,
+                        invested,
+                        self.runtime.system_time(),
+                    );
+                }
+                OrderSide::Sell => {
+                    // The maker's escrow pays the taker, who hands over shares with their invested amount
+                    let order = &mut resting[index];
+                    order.escrow = order.escrow.saturating_sub(value);
+
+                    let invested = Self::take_shares(market, taker.id, outcome_id, fill);
+                    self.ledger_credit(
+                        taker,
+                        value,
+                        LedgerAccount::market(market.id),
+                        LedgerReason::OrderFill,
+                    )
+                    .await?;
+                    taker.total_earned = taker.total_earned.saturating_add(value);
+                    Self::give_shares(
+                        market,
+                        maker_id,
+                        outcome_id,
+                        fill,
+                        invested,
+                        self.runtime.system_time(),
+                    );
+                }
+            }
+
+            if !maker.active_markets.contains(&market.id) {
+                maker.active_markets.push(market.id);
+                market.total_participants += 1;
+            }
+
+            let order = &mut resting[index];
+            order.remaining = order.remaining.saturating_sub(fill);
+            if order.remaining == Amount::ZERO {
+                // A filled buy order may keep rounding dust in escrow; it goes back to the maker
+                let order = resting.remove(index);
+                if order.side == OrderSide::Buy {
+                    self.ledger_credit(
+                        &mut maker,
+                        order.escrow,
+                        LedgerAccount::market(market.id),
+                        LedgerReason::OrderRelease,
+                    )
+                    .await?;
+                }
+            } else {
+                index += 1;
+            }
+            self.state.players.insert(&maker_id, maker)?;
+            remaining = remaining.saturating_sub(fill);
+
+            let trade = Trade {
+                id: market.trade_count,
+                market_id: market.id,
+                player_id: taker.id,
+                counterparty: Some(maker_id),
+                outcome_id,
+                side,
+                amount: fill,
+                price,
+                total: value,
+                fee: Amount::ZERO,
+                timestamp: self.runtime.system_time(),
+            };
+            self.log_trade(market, trade).await?;
+
+            self.emit(Message::TradeExecuted {
+                player_id: taker.id,
+                market_id: market.id,
+                outcome_id,
+                shares: fill,
+                price: value,
+            });
+        }
+
+        Ok(remaining)
+    }
+
+    /// Whether a limit order's price crosses one of its owner's resting orders on the other side
+    fn crosses_own_order(
+        book: &OrderBook,
+        player_id: PlayerId,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        price: Amount,
+    ) -> bool {
+        let resting = match side {
+            OrderSide::Buy => &book.asks,
+            OrderSide::Sell => &book.bids,
+        };
+        resting.iter().any(|order| {
+            order.owner == player_id
+                && order.outcome_id == outcome_id
+                && match side {
+                    OrderSide::Buy => order.price <= price,
+                    OrderSide::Sell => order.price >= price,
+                }
+        })
+    }
+
+    /// Load a market that can take orders for an outcome
+    /// Only active prediction markets have order books
+    async fn get_order_market(
         &mut self,
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+    ) -> Result<Market, ContractError> {
+        let market = self.touch_market(market_id).await?;
+        if market.status != MarketStatus::Active {
+            return Err(ContractError::MarketNotActive);
+        }
+        Self::validate_outcome(&market, Some(outcome_id))?;
+        Ok(market)
+    }
+
+    /// Check that a player can cover an order up front
+    /// Limit buys must afford the full order at their limit price, sellers must hold the shares
+    fn check_order_funds(
+        player: &Player,
+        market: &Market,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        limit_price: Option<Amount>,
+        quantity: Amount,
+    ) -> Result<(), ContractError> {
+        if quantity == Amount::ZERO {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let covered = match (side, limit_price) {
+            (OrderSide::Buy, Some(price)) => {
+                player.token_balance >= Self::order_value(price, quantity)
+            }
+            (OrderSide::Buy, None) => player.token_balance > Amount::ZERO,
+            (OrderSide::Sell, _) => {
+                let held = market
+                    .positions
+                    .get(&player.id)
+                    .and_then(|position| position.shares_by_outcome.get(&outcome_id))
+                    .copied()
+                    .unwrap_or_default();
+                held >= quantity
+            }
+        };
+        if covered {
+            Ok(())
+        } else {
+            Err(ContractError::InsufficientBalance)
+        }
+    }
+
+    /// Points paid for `quantity` shares at `price` points per share
+    fn order_value(price: Amount, quantity: Amount) -> Amount {
+        Amount::from_attos(mul_div(price.into(), quantity.into(), ONE))
+    }
+
+    /// Remove shares of an outcome from a player's position
+    /// Returns the part of the position's invested amount that goes with them
+    fn take_shares(
+        market: &mut Market,
+        player_id: PlayerId,
+        outcome_id: OutcomeId,
+        shares: Amount,
+    ) -> Amount {
+        let Some(position) = market.positions.get_mut(&player_id) else {
+            return Amount::ZERO;
+        };
+        let total_held = position
+            .shares_by_outcome
+            .values()
+            .fold(Amount::ZERO, |total, shares| total.saturating_add(*shares));
+        if total_held == Amount::ZERO {
+            return Amount::ZERO;
+        }
+
+        let invested = Amount::from_attos(mul_div(
+            position.total_invested.into(),
+            shares.into(),
+            total_held.into(),
+        ));
+        position.total_invested = position.total_invested.saturating_sub(invested);
+        let held = position.shares_by_outcome.entry(outcome_id).or_default();
+        *held = held.saturating_sub(shares);
+        invested
+    }
+
+    /// Add shares of an outcome, with the invested amount behind them, to a player's position
+    fn give_shares(
+        market: &mut Market,
         player_id: PlayerId,
+        outcome_id: OutcomeId,
+        shares: Amount,
+        invested: Amount,
+        current_time: Timestamp,
+    ) {
+        let position = market.positions.entry(player_id).or_insert(PlayerPosition {
+            shares_by_outcome: BTreeMap::new(),
+            total_invested: Amount::ZERO,
+            entry_time: current_time,
+        });
+        let held = position.shares_by_outcome.entry(outcome_id).or_default();
+        *held = held.saturating_add(shares);
+        position.total_invested = position.total_invested.saturating_add(invested);
+    }
+
+    /// Release a resting order's escrow to its owner
+    /// Buy orders get their points back, sell orders their shares and the invested amount behind them
+    async fn release_order(
+        &mut self,
+        market: &mut Market,
+        order: Order,
+    ) -> Result<(), ContractError> {
+        match order.side {
+            OrderSide::Buy => {
+                let mut owner = self.get_player(&order.owner).await?;
+                self.ledger_credit(
+                    &mut owner,
+                    order.escrow,
+                    LedgerAccount::market(market.id),
+                    LedgerReason::OrderRelease,
+                )
+                .await?;
+                self.state.players.insert(&order.owner, owner)?;
+            }
+            OrderSide::Sell => Self::give_shares(
+                market,
+                order.owner,
+                order.outcome_id,
+                order.remaining,
+                order.escrow,
+                order.created_at,
+            ),
+        }
+        Ok(())
+    }
+
+    /// Release every resting order of a market and drop its order book
+    /// Called before a market leaves the active state, so refunds and payouts see all shares
+    async fn release_orders(&mut self, market: &mut Market) -> Result<(), ContractError> {
+        let Some(book) = self.state.order_books.get(&market.id).await? else {
+            return Ok(());
+        };
+        for order in book.bids.into_iter().chain(book.asks) {
+            self.release_order(market, order).await?;
+        }
+        self.state.order_books.remove(&market.id)?;
+        Ok(())
+    }
+
+    /// Store the taker, market and order book after an order was matched
+    async fn store_order_trade(
+        &mut self,
+        mut taker: Player,
+        mut market: Market,
+        book: OrderBook,
+    ) -> Result<(), ContractError> {
+        let market_id = market.id;
+        let holds_position = market.positions.contains_key(&taker.id);
+        if holds_position && !taker.active_markets.contains(&market_id) {
+            taker.active_markets.push(market_id);
+            market.total_participants += 1;
+        }
+
+        self.state.players.insert(&taker.id, taker)?;
+        self.state.markets.insert(&market_id, market)?;
+        if book.bids.is_empty() && book.asks.is_empty() {
+            self.state.order_books.remove(&market_id)?;
+        } else {
+            self.state.order_books.insert(&market_id, book)?;
+        }
+        Ok(())
+    }
+
+    // ============================================================================
+    // Enhanced Leaderboard System
+    // ============================================================================
+
+    /// Update the enhanced leaderboard with sophisticated ranking algorithms
+    /// Players are ranked by total points earned (total_earned)
+    /// Guilds are ranked by total points earned by all guild members
+    async fn update_enhanced_leaderboard(&mut self) {
+        let mut top_traders = Vec::new();
+        let mut top_guilds = Vec::new();
+
+        // Collect all players and rank by total points earned
+        let mut player_scores = Vec::new();
+        self.state
+            .players
+            .for_each_index_value(|player_id, player| {
+                let player = player.into_owned();
+                // Use total_earned (total points earned) for ranking
+                let total_points: u128 = player.total_earned.into();
+                player_scores.push((player_id, player, total_points));
+                Ok(())
+            })
+            .await
+            .expect("Failed to iterate players");
+
+        // Sort by total points earned (descending)
+        player_scores.sort_by(|a, b| b.2.cmp(&a.2));
+
+        // Take top 50 players by total points earned
+        for (player_id, player, _total_points) in player_scores.into_iter().take(50) {
+            let win_rate = if player.markets_participated > 0 {
+                (player.markets_won as f64 / player.markets_participated as f64) * 100.0
+            } else {
+                0.0
+            };
+
+            top_traders.push(LeaderboardEntry {
+                player_id,
+                display_name: player.display_name,
+                total_profit: player.total_earned, // Use total_earned instead of total_profit
+                win_rate,
+                level: player.level,
+            });
+        }
+
+        // Collect all guilds and calculate total points earned by all members
+        let mut guild_scores = Vec::new();
+        self.state
+            .guilds
+            .for_each_index_value(|guild_id, guild| {
+                let guild = guild.into_owned();
+
+                // Calculate total points earned by all guild members
+                // We need to collect this separately due to async constraints
+                let guild_members = guild.members.clone();
+                let guild_name = guild.name.clone();
+                guild_scores.push((guild_id, guild_members, guild_name));
+                Ok(())
+            })
+            .await
+            .expect("Failed to iterate guilds");
+
+        // Calculate total points for each guild
+        let mut guild_totals = Vec::new();
+        for (guild_id, members, name) in guild_scores {
+            let mut total_guild_points = Amount::ZERO;
+            let member_count = members.len() as u32;
+
+            // Sum total points earned by all guild members
+            for member_id in &members {
+                if let Some(member) = self.state.players.get(member_id).await.ok().flatten() {
+                    let member = member.clone();
+                    total_guild_points = total_guild_points.saturating_add(member.total_earned);
+                }
+            }
+
+            let total_points: u128 = total_guild_points.into();
+            guild_totals.push((
+                guild_id,
+                name,
+                total_guild_points,
+                member_count,
+                total_points,
+            ));
+        }
+
+        // Sort by total points earned by guild (descending)
+        guild_totals.sort_by(|a, b| b.4.cmp(&a.4));
+
+        // Take top 20 guilds by total points earned
+        for (guild_id, name, total_guild_points, member_count, _total_points) in
+            guild_totals.into_iter().take(20)
+        {
+            top_guilds.push(GuildLeaderboardEntry {
+                guild_id,
+                name,
+                total_profit: total_guild_points, // Use total points earned instead of total_guild_profit
+                member_count,
+            });
+        }
+
+        // Update leaderboard
+        let mut leaderboard = self.state.leaderboard.get().clone();
+        leaderboard.top_traders = top_traders;
+        leaderboard.top_guilds = top_guilds;
+        leaderboard.last_updated = self.runtime.system_time();
+        self.state.leaderboard.set(leaderboard);
+    }
+
+    // ============================================================================
+    // Ledger
+    // ============================================================================
+
+    /// Credit points to a player from another ledger account and journal the entry
+    /// The caller stores the player afterwards, as with any other change to it
+    async fn ledger_credit(
+        &mut self,
+        player: &mut Player,
+        amount: Amount,
+        from: LedgerAccount,
+        reason: LedgerReason,
+    ) -> Result<(), ContractError> {
+        player.token_balance = player.token_balance.saturating_add(amount);
+        self.post_ledger_entry(from, LedgerAccount::player(player.id), amount, reason)
+            .await
+    }
+
+    /// Debit points from a player into another ledger account and journal the entry
+    /// Fails without touching the player when their balance doesn't cover the amount
+    async fn ledger_debit(
+        &mut self,
+        player: &mut Player,
+        amount: Amount,
+        to: LedgerAccount,
+        reason: LedgerReason,
+    ) -> Result<(), ContractError> {
+        if player.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+        player.token_balance = player.token_balance.saturating_sub(amount);
+        self.post_ledger_entry(LedgerAccount::player(player.id), to, amount, reason)
+            .await
+    }
+
+    /// Credit a reward from issuance, paying off the player's debt to the treasury first
+    /// Returns the part of the reward the player keeps
+    async fn credit_reward(
+        &mut self,
+        player: &mut Player,
+        reward: Amount,
+        reason: LedgerReason,
+    ) -> Result<Amount, ContractError> {
+        self.ledger_credit(player, reward, LedgerAccount::ISSUANCE, reason)
+            .await?;
+        self.repay_debt(player, reward).await
+    }
+
+    /// Pay off the player's debt to the treasury out of points they just received
+    /// Returns the part of `received` the player keeps
+    async fn repay_debt(
+        &mut self,
+        player: &mut Player,
+        received: Amount,
+    ) -> Result<Amount, ContractError> {
+        let repaid = player.debt.min(received);
+        if repaid > Amount::ZERO {
+            self.ledger_debit(
+                player,
+                repaid,
+                LedgerAccount::TREASURY,
+                LedgerReason::DebtRepayment,
+            )
+            .await?;
+            player.debt = player.debt.saturating_sub(repaid);
+            player.total_spent = player.total_spent.saturating_add(repaid);
+            self.deposit_to_treasury(TreasurySource::PredictionPenalty, repaid)
+                .await?;
+        }
+        Ok(received.saturating_sub(repaid))
+    }
+
+    /// Players who owe penalties can't move points away or take on new risk until it's repaid
+    /// They may still sell outcome shares and withdraw liquidity, which repays the debt first
+    fn ensure_no_debt(player: &Player) -> Result<(), ContractError> {
+        if player.debt > Amount::ZERO {
+            return Err(ContractError::InDebt);
+        }
+        Ok(())
+    }
+
+    /// Predictions, oracle markets and price updates only accept assets listed in the config
+    fn ensure_asset_listed(&self, asset: &str) -> Result<(), ContractError> {
+        if !self.state.config.get().has_asset(asset) {
+            return Err(ContractError::UnknownAsset);
+        }
+        Ok(())
+    }
+
+    /// Latest price of an asset, or a zero price at time zero if none was reported yet
+    async fn asset_price(&self, asset: &str) -> Result<MarketPrice, ContractError> {
+        Ok(self
+            .state
+            .asset_prices
+            .get(asset)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Check a stake against its period's limits and escrow it until the period resolves
+    async fn escrow_prediction_stake(
+        &mut self,
+        player: &mut Player,
+        period: PredictionPeriod,
+        stake: Amount,
+    ) -> Result<(), ContractError> {
+        let limits = self.state.config.get().stake_limits(period);
+        if stake < limits.min_stake || stake > limits.max_stake {
+            return Err(ContractError::InvalidStake);
+        }
+        self.ledger_debit(
+            player,
+            stake,
+            LedgerAccount::PREDICTIONS,
+            LedgerReason::PredictionStake,
+        )
+        .await
+    }
+
+    /// Add a prediction's stake to its period's pool, opening the pool in the configured mode
+    async fn add_to_prediction_pool(
+        &mut self,
+        prediction: &PlayerPrediction,
+    ) -> Result<(), ContractError> {
+        let pool_key = period_key(
+            &prediction.asset,
+            prediction.period,
+            prediction.period_start,
+        );
+        let mut pool = match self.state.prediction_pools.get(&pool_key).await? {
+            Some(pool) => pool,
+            None => {
+                let config = self.state.config.get();
+                PredictionPool::new(
+                    prediction.asset.clone(),
+                    prediction.period,
+                    prediction.period_start,
+                    config.parimutuel_predictions,
+                    config.prediction_rake_percent,
+                )
+            }
+        };
+        pool.add_stake(prediction.outcome, prediction.stake);
+        self.state.prediction_pools.insert(&pool_key, pool)?;
+        Ok(())
+    }
+
+    /// Move points from one player to another as a single journal entry
+    async fn ledger_transfer(
+        &mut self,
+        from: &mut Player,
+        to: &mut Player,
+        amount: Amount,
+        reason: LedgerReason,
+    ) -> Result<(), ContractError> {
+        if from.token_balance < amount {
+            return Err(ContractError::InsufficientBalance);
+        }
+        from.token_balance = from.token_balance.saturating_sub(amount);
+        to.token_balance = to.token_balance.saturating_add(amount);
+        self.post_ledger_entry(
+            LedgerAccount::player(from.id),
+            LedgerAccount::player(to.id),
+            amount,
+            reason,
+        )
+        .await
+    }
+
+    /// Journal `amount` points leaving the debit account for the credit account
+    /// Every balance change is posted here, so each account's running totals replay its history
+    async fn post_ledger_entry(
+        &mut self,
+        debit: LedgerAccount,
+        credit: LedgerAccount,
+        amount: Amount,
+        reason: LedgerReason,
+    ) -> Result<(), ContractError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+
+        let id = *self.state.next_ledger_entry_id.get();
+        self.state.next_ledger_entry_id.set(id + 1);
+        self.state.journal.insert(
+            &id,
+            LedgerEntry {
+                id,
+                reason,
+                debit,
+                credit,
+                amount,
+                timestamp: self.runtime.system_time(),
+            },
+        )?;
+
+        let mut debit_balance = self.ledger_balance(debit).await?;
+        debit_balance.debits = debit_balance.debits.saturating_add(amount);
+        self.state.ledger_balances.insert(&debit, debit_balance)?;
+        let mut credit_balance = self.ledger_balance(credit).await?;
+        credit_balance.credits = credit_balance.credits.saturating_add(amount);
+        self.state.ledger_balances.insert(&credit, credit_balance)?;
+
+        // Minted points join the supply and points sent away leave it
+        let mut supply = self.state.supply.get().clone();
+        if let Some(component) = supply.component_mut(debit.kind) {
+            *component = component.saturating_sub(amount);
+        }
+        if let Some(component) = supply.component_mut(credit.kind) {
+            *component = component.saturating_add(amount);
+        }
+        self.state.supply.set(supply);
+
+        // Index the entry under each player it touches, once even if it moves points to themselves
+        let credited_player = if credit.player_id == debit.player_id {
+            None
+        } else {
+            credit.player_id
+        };
+        for player_id in [debit.player_id, credited_player].into_iter().flatten() {
+            let index = self
+                .state
+                .player_journal_len
+                .get(&player_id)
+                .await?
+                .unwrap_or(0);
+            self.state.player_journal.insert(&(player_id, index), id)?;
+            self.state
+                .player_journal_len
+                .insert(&player_id, index + 1)?;
+        }
+        Ok(())
+    }
+
+    /// Get the running totals of a ledger account, starting from zero
+    async fn ledger_balance(&self, account: LedgerAccount) -> Result<LedgerBalance, ContractError> {
+        Ok(self
+            .state
+            .ledger_balances
+            .get(&account)
+            .await?
+            .unwrap_or(LedgerBalance {
+                account,
+                debits: Amount::ZERO,
+                credits: Amount::ZERO,
+            }))
+    }
+
+    // ============================================================================
+    // Platform Treasury
+    // ============================================================================
+
+    /// Add points to the platform treasury and record the inflow under its source
+    /// The points were already taken from a player, so the total supply doesn't change
+    async fn deposit_to_treasury(
+        &mut self,
+        source: TreasurySource,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        if amount == Amount::ZERO {
+            return Ok(());
+        }
+        let balance = self.state.treasury_balance.get().saturating_add(amount);
+        self.state.treasury_balance.set(balance);
+
+        let mut flow = self.treasury_flow(source).await?;
+        flow.inflow = flow.inflow.saturating_add(amount);
+        self.state.treasury_flows.insert(&source, flow)?;
+        Ok(())
+    }
+
+    /// Pay points out of the treasury to a player (Admin only)
+    ///
+    /// # Arguments
+    /// * `caller` - The player attempting to spend
+    /// * `recipient` - The player receiving the points
+    /// * `amount` - How many points to pay
+    /// * `memo` - Optional note on what the points are for
+    ///
+    /// # Returns
+    /// * `Ok(())` - Points paid successfully
+    /// * `Err(NotAdmin)` - Caller is not the admin
+    /// * `Err(PlayerNotFound)` - Recipient is not registered on this chain
+    /// * `Err(InsufficientBalance)` - Amount is zero or more than the treasury holds
+    async fn spend_treasury(
+        &mut self,
+        caller: PlayerId,
+        recipient: PlayerId,
         amount: Amount,
+        memo: Option<String>,
     ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-        let guild_id = player.guild_id.ok_or(ContractError::NotGuildMember)?;
-        if player.token_balance < amount {
+        if self.state.config.get().admin != Some(caller) {
+            return Err(ContractError::NotAdmin);
+        }
+
+        let mut player = self.get_player(&recipient).await?;
+        let balance = *self.state.treasury_balance.get();
+        if amount == Amount::ZERO || balance < amount {
             return Err(ContractError::InsufficientBalance);
         }
 
-        // Deduct contribution from player's points (no external transfer needed)
+        self.ledger_credit(
+            &mut player,
+            amount,
+            LedgerAccount::TREASURY,
+            LedgerReason::TreasurySpend,
+        )
+        .await?;
+        player.total_earned = player.total_earned.saturating_add(amount);
+        self.state.players.insert(&recipient, player)?;
+        self.state
+            .treasury_balance
+            .set(balance.saturating_sub(amount));
 
-        let mut guild = self
+        let mut flow = self.treasury_flow(TreasurySource::AdminSpend).await?;
+        flow.outflow = flow.outflow.saturating_add(amount);
+        self.state
+            .treasury_flows
+            .insert(&TreasurySource::AdminSpend, flow)?;
+
+        self.emit(Message::TreasurySpent {
+            recipient,
+            amount,
+            memo,
+        });
+        Ok(())
+    }
+
+    /// Get the running totals of a treasury source, starting from zero
+    async fn treasury_flow(&self, source: TreasurySource) -> Result<TreasuryFlow, ContractError> {
+        Ok(self
             .state
-            .guilds
-            .get(&guild_id)
+            .treasury_flows
+            .get(&source)
             .await?
-            .ok_or(ContractError::GuildNotFound)?;
-        player.token_balance = player.token_balance.saturating_sub(amount);
-        guild.shared_pool = guild.shared_pool.saturating_add(amount);
-        self.state.players.insert(&player_id, player)?;
-        self.state.guilds.insert(&guild_id, guild)?;
-        Ok(())
+            .unwrap_or(TreasuryFlow {
+                source,
+                inflow: Amount::ZERO,
+                outflow: Amount::ZERO,
+            }))
     }
 
-    /// Update the game configuration (Admin only)
-    /// Allows the admin to modify game parameters like token amounts and market settings
+    // ============================================================================
+    // Supply Reconciliation
+    // ============================================================================
+
+    /// Reset the tracked supply to a recount of every balance it is made of (Admin only)
+    /// The recount adds up player balances, market holdings, guild pools and the treasury
     ///
     /// # Arguments
-    /// * `caller` - The player attempting to update config
-    /// * `config` - The new game configuration
+    /// * `caller` - The player attempting to reconcile
     ///
     /// # Returns
-    /// * `Ok(())` - Configuration updated successfully
+    /// * `Ok(())` - Supply reconciled successfully
     /// * `Err(NotAdmin)` - Caller is not the admin
-    async fn update_game_config(
-        &mut self,
-        caller: PlayerId,
-        config: GameConfig,
-    ) -> Result<(), ContractError> {
-        let current = self.state.config.get();
-        if let Some(admin) = current.admin {
-            if caller != admin {
-                return Err(ContractError::NotAdmin);
-            }
-        } else {
+    async fn reconcile_supply(&mut self, caller: PlayerId) -> Result<(), ContractError> {
+        if self.state.config.get().admin != Some(caller) {
             return Err(ContractError::NotAdmin);
         }
-        self.state.config.set(config);
+
+        let tracked = self.state.supply.get().clone();
+        let actual = self.state.count_supply().await?;
+        let drifted = tracked.drift_from(&actual);
+        let supply = actual.total();
+        self.state.supply.set(actual);
+
+        self.emit(Message::SupplyReconciled {
+            previous_supply: tracked.total(),
+            supply,
+            drifted,
+        });
         Ok(())
     }
 
     // ============================================================================
-    // Single-File Prediction Market Game
+    // Market Creator Fee Distribution
     // ============================================================================
 
-    /// This contract implements a complete prediction market game with:
-    /// - Player progression system (levels, reputation, achievements)
-    /// - Market operations (create, trade, resolve)
-    /// - Guild system (social features, shared pools)
-    /// - Points-based economy (no external tokens needed)
-    /// - Admin controls (game configuration)
+    /// Distribute market creation fee to platform
+    /// Market creation costs 100 points, which all go to the platform treasury
+    async fn distribute_market_creator_fee(
+        &mut self,
+        _creator: PlayerId,
+        creation_fee: Amount,
+    ) -> Result<(), ContractError> {
+        // Market creation fee (100 points) goes entirely to the treasury
+        self.deposit_to_treasury(TreasurySource::MarketCreationFee, creation_fee)
+            .await?;
+
+        // Update leaderboard after fee distribution
+        self.update_enhanced_leaderboard().await;
+
+        Ok(())
+    }
+
+    /// Distribute trading fees to the market's liquidity providers and platform
+    /// Platform gets `platform_fee_percent` of the creator's fee from each trade (2% by default)
+    /// Providers share the remaining fee in proportion to their LP shares
+    async fn distribute_trading_fees(
+        &mut self,
+        market_id: MarketId,
+        creator_fee_amount: Amount,
+    ) -> Result<(), ContractError> {
+        // Platform gets its configured cut of the creator's fee
+        let config = self.state.config.get();
+        let platform_fee = Self::fee_share(
+            creator_fee_amount,
+            config.platform_fee_percent,
+            config.fee_divisor,
+        );
+        let creator_keeps = creator_fee_amount.saturating_sub(platform_fee);
+
+        // Give providers their share (creator's fee minus the platform fee)
+        self.pay_liquidity_providers(market_id, creator_keeps)
+            .await?;
+
+        // Add platform fee to the treasury
+        self.post_ledger_entry(
+            LedgerAccount::market(market_id),
+            LedgerAccount::TREASURY,
+            platform_fee,
+            LedgerReason::TradingFee,
+        )
+        .await?;
+        self.deposit_to_treasury(TreasurySource::TradingFee, platform_fee)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Pay a market's liquidity providers their pro-rata share of trading income
+    async fn pay_liquidity_providers(
+        &mut self,
+        market_id: MarketId,
+        amount: Amount,
+    ) -> Result<(), ContractError> {
+        let mut market = self.get_market(&market_id).await?;
+        for (provider_id, payout) in Self::provider_payouts(&market, amount) {
+            let mut provider = self.get_player(&provider_id).await?;
+            self.ledger_credit(
+                &mut provider,
+                payout,
+                LedgerAccount::market(market_id),
+                LedgerReason::LiquidityEarnings,
+            )
+            .await?;
+            provider.total_earned = provider.total_earned.saturating_add(payout);
+            provider.total_profit = provider.total_profit.saturating_add(payout);
+            self.state.players.insert(&provider_id, provider)?;
+
+            if let Some(position) = market.liquidity_positions.get_mut(&provider_id) {
+                position.earned = position.earned.saturating_add(payout);
+            }
+        }
+        self.state.markets.insert(&market_id, market)?;
+        Ok(())
+    }
+
+    /// Split `amount` between a market's liquidity providers in proportion to their LP shares
+    /// Rounding leftovers go to the market creator, as does everything when no shares are outstanding
+    fn provider_payouts(market: &Market, amount: Amount) -> Vec<(PlayerId, Amount)> {
+        let mut payouts = Vec::new();
+        let mut paid = Amount::ZERO;
+        if market.total_lp_shares > Amount::ZERO {
+            for (provider, position) in &market.liquidity_positions {
+                let payout = Amount::from_attos(mul_div(
+                    amount.into(),
+                    position.shares.into(),
+                    market.total_lp_shares.into(),
+                ));
+                paid = paid.saturating_add(payout);
+                payouts.push((*provider, payout));
+            }
+        }
+        payouts.push((market.creator, amount.saturating_sub(paid)));
+        payouts.retain(|(_, payout)| *payout > Amount::ZERO);
+        payouts
+    }
 
     // ============================================================================
-    // Enhanced Leaderboard System
+    // Helper Functions
     // ============================================================================
 
-    /// Update the enhanced leaderboard with sophisticated ranking algorithms
-    /// Players are ranked by total points earned (total_earned)
-    /// Guilds are ranked by total points earned by all guild members
-    async fn update_enhanced_leaderboard(&mut self) {
-        let mut top_traders = Vec::new();
-        let mut top_guilds = Vec::new();
-
-        // Collect all players and rank by total points earned
-        let mut player_scores = Vec::new();
+    /// Get a player by their ID
+    /// Helper function to retrieve player data from storage
+    async fn get_player(&self, player_id: &PlayerId) -> Result<Player, ContractError> {
         self.state
             .players
-            .for_each_index_value(|player_id, player| {
-                let player = player.into_owned();
-                // Use total_earned (total points earned) for ranking
-                let total_points: u128 = player.total_earned.into();
-                player_scores.push((player_id, player, total_points));
-                Ok(())
-            })
-            .await
-            .expect("Failed to iterate players");
+            .get(player_id)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)
+    }
 
-        // Sort by total points earned (descending)
-        player_scores.sort_by(|a, b| b.2.cmp(&a.2));
+    /// Get an account that may be hosted for a trader from another chain, such as a position holder's
+    /// Operations act for registered players only and use `get_player` instead
+    async fn get_account(&self, player_id: &PlayerId) -> Result<Player, ContractError> {
+        self.find_account(player_id)
+            .await?
+            .ok_or(ContractError::PlayerNotFound)
+    }
 
-        // Take top 50 players by total points earned
-        for (player_id, player, _total_points) in player_scores.into_iter().take(50) {
-            let win_rate = if player.markets_participated > 0 {
-                (player.markets_won as f64 / player.markets_participated as f64) * 100.0
-            } else {
-                0.0
-            };
+    /// Get a market by its ID
+    /// Helper function to retrieve market data from storage
+    async fn get_market(&self, market_id: &MarketId) -> Result<Market, ContractError> {
+        self.state
+            .markets
+            .get(market_id)
+            .await?
+            .ok_or(ContractError::MarketNotFound)
+    }
 
-            top_traders.push(LeaderboardEntry {
-                player_id,
-                display_name: player.display_name,
-                total_profit: player.total_earned, // Use total_earned instead of total_profit
-                win_rate,
-                level: player.level,
-            });
+    /// Get a market, closing it first if its close time has passed
+    /// Operations find overdue markets already closed by `process_market_closes`;
+    /// remote trades, which run as messages, close them here
+    async fn touch_market(&mut self, market_id: MarketId) -> Result<Market, ContractError> {
+        let mut market = self.get_market(&market_id).await?;
+        let expired = market
+            .close_time
+            .is_some_and(|close_time| self.runtime.system_time() >= close_time);
+        if market.status == MarketStatus::Active && expired {
+            self.release_orders(&mut market).await?;
+            market.status = MarketStatus::Closed;
+            self.set_market_status(market.clone(), MarketStatus::Closed)
+                .await?;
         }
+        Ok(market)
+    }
 
-        // Collect all guilds and calculate total points earned by all members
-        let mut guild_scores = Vec::new();
-        self.state
-            .guilds
-            .for_each_index_value(|guild_id, guild| {
-                let guild = guild.into_owned();
+    /// Calculate the fee a market charges on a trade of the given value
+    /// Uses the market's fee percentage scaled by the configured fee divisor
+    fn calculate_market_fee(&self, market: &Market, value: Amount) -> Amount {
+        Self::fee_share(
+            value,
+            market.fee_percent,
+            self.state.config.get().fee_divisor,
+        )
+    }
 
-                // Calculate total points earned by all guild members
-                // We need to collect this separately due to async constraints
-                let guild_members = guild.members.clone();
-                let guild_name = guild.name.clone();
-                guild_scores.push((guild_id, guild_members, guild_name));
-                Ok(())
-            })
-            .await
-            .expect("Failed to iterate guilds");
+    /// Percentage of an amount, with the percentage scaled by the fee divisor
+    /// Computed in attos, so trades worth a fraction of a point still pay their share
+    fn fee_share(amount: Amount, percent: u8, fee_divisor: Amount) -> Amount {
+        Amount::from_attos(mul_div(
+            amount.into(),
+            (percent as u128).saturating_mul(ONE),
+            fee_divisor.into(),
+        ))
+    }
 
-        // Calculate total points for each guild
-        let mut guild_totals = Vec::new();
-        for (guild_id, members, name) in guild_scores {
-            let mut total_guild_points = Amount::ZERO;
-            let member_count = members.len() as u32;
+    /// Check that an outcome was given and exists in a prediction market
+    fn validate_outcome(
+        market: &Market,
+        outcome_id: Option<OutcomeId>,
+    ) -> Result<OutcomeId, ContractError> {
+        match outcome_id {
+            Some(id) if (id as usize) < market.outcomes.len() => Ok(id),
+            _ => Err(ContractError::InvalidOutcome),
+        }
+    }
 
-            // Sum total points earned by all guild members
-            for member_id in &members {
-                if let Some(member) = self.state.players.get(member_id).await.ok().flatten() {
-                    let member = member.clone();
-                    total_guild_points = total_guild_points.saturating_add(member.total_earned);
-                }
-            }
+    /// Virtual payment reserve a constant-product pool starts with
+    /// A tenth of the initial amount opens the pool at the fixed 10:1 rate
+    fn initial_payment_reserve(amount: Amount) -> Amount {
+        let amount_attos: u128 = amount.into();
+        Amount::from_attos(amount_attos / 10)
+    }
 
-            let total_points: u128 = total_guild_points.into();
-            guild_totals.push((
-                guild_id,
-                name,
-                total_guild_points,
-                member_count,
-                total_points,
-            ));
-        }
+    /// Payment side y of a constant-product pool: the virtual reserve plus what buyers paid in
+    fn pool_payment_reserve(market: &Market) -> Amount {
+        Self::initial_payment_reserve(market.amount).saturating_add(market.payment_reserve)
+    }
 
-        // Sort by total points earned by guild (descending)
-        guild_totals.sort_by(|a, b| b.4.cmp(&a.4));
+    /// Outstanding shares of each outcome of a prediction market, the LMSR quantity vector
+    fn outcome_quantities(market: &Market) -> Vec<Amount> {
+        market
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.total_shares)
+            .collect()
+    }
 
-        // Take top 20 guilds by total points earned
-        for (guild_id, name, total_guild_points, member_count, _total_points) in
-            guild_totals.into_iter().take(20)
-        {
-            top_guilds.push(GuildLeaderboardEntry {
-                guild_id,
-                name,
-                total_profit: total_guild_points, // Use total points earned instead of total_guild_profit
-                member_count,
-            });
+    /// Store a trade's player and market updates, log the fill and emit the TradeExecuted event
+    /// Tracks participation the same way for every kind of trade; the player may be a hosted account
+    #[allow(clippy::too_many_arguments)]
+    async fn record_trade(
+        &mut self,
+        mut player: Player,
+        mut market: Market,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        shares: Amount,
+        price: Amount,
+        fee: Amount,
+    ) -> Result<(), ContractError> {
+        let player_id = player.id;
+        let market_id = market.id;
+
+        if !player.active_markets.contains(&market_id) {
+            player.active_markets.push(market_id);
+            market.total_participants += 1;
         }
 
-        // Update leaderboard
-        let mut leaderboard = self.state.leaderboard.get().clone();
-        leaderboard.top_traders = top_traders;
-        leaderboard.top_guilds = top_guilds;
-        leaderboard.last_updated = self.runtime.system_time();
-        self.state.leaderboard.set(leaderboard);
+        let trade = Trade {
+            id: market.trade_count,
+            market_id,
+            player_id,
+            counterparty: None,
+            outcome_id,
+            side,
+            amount: shares,
+            price: Self::unit_price(price, shares),
+            total: price,
+            fee,
+            timestamp: self.runtime.system_time(),
+        };
+        self.log_trade(&mut market, trade).await?;
+
+        player.markets_participated += 1;
+
+        self.state.markets.insert(&market_id, market)?;
+        self.store_account(player).await?;
+
+        self.emit(Message::TradeExecuted {
+            player_id,
+            market_id,
+            outcome_id,
+            shares,
+            price,
+        });
+        Ok(())
     }
 
-    // ============================================================================
-    // Market Creator Fee Distribution
-    // ============================================================================
+    /// Reward a registered player for a trade they made on this chain
+    /// Remote trades go without: their account's gains are sent home as the trade's refund,
+    /// and rewards would be sent along with them
+    async fn reward_trade(&mut self, player_id: &PlayerId) -> Result<(), ContractError> {
+        let mut player = self.get_player(player_id).await?;
+        // Update reputation for trading
+        player.reputation = player.reputation.saturating_add(1);
+        self.add_experience(&mut player, 10).await?;
+        self.state.players.insert(player_id, player.clone())?;
+
+        // Check for achievements after trading (first buy / first sell achievements)
+        self.check_achievements(&mut player).await
+    }
+
+    /// Append a fill to its market's trade log and its outcome's candles
+    /// The log is append-only, so a trade's ID is its position in the log
+    async fn log_trade(&mut self, market: &mut Market, trade: Trade) -> Result<(), ContractError> {
+        self.update_candles(
+            CandleSource::Market(market.id),
+            trade.outcome_id,
+            trade.price,
+            trade.amount,
+            trade.timestamp,
+        )
+        .await?;
+        self.state.trades.insert(&(market.id, trade.id), trade)?;
+        market.trade_count += 1;
+        Ok(())
+    }
 
-    /// Distribute market creation fee to platform
-    /// Market creation costs 100 points, which all go to platform total supply
-    async fn distribute_market_creator_fee(
+    /// Fold a price into the current candle of a series at every resolution
+    /// Price feed candles use outcome zero
+    async fn update_candles(
         &mut self,
-        _creator: PlayerId,
-        creation_fee: Amount,
+        source: CandleSource,
+        outcome_id: OutcomeId,
+        price: Amount,
+        volume: Amount,
+        timestamp: Timestamp,
     ) -> Result<(), ContractError> {
-        // Market creation fee (100 points) goes entirely to platform total supply
-        let current_supply = self.state.total_supply.get();
-        self.state
-            .total_supply
-            .set(current_supply.saturating_add(creation_fee));
+        for resolution in CandleResolution::ALL {
+            let start = resolution.candle_start(timestamp);
+            let key = (source.clone(), outcome_id, resolution, start.micros());
+            let candle = match self.state.candles.get(&key).await? {
+                Some(mut candle) => {
+                    candle.high = candle.high.max(price);
+                    candle.low = candle.low.min(price);
+                    candle.close = price;
+                    candle.volume = candle.volume.saturating_add(volume);
+                    candle.trade_count += 1;
+                    candle
+                }
+                None => Candle {
+                    start,
+                    open: price,
+                    high: price,
+                    low: price,
+                    close: price,
+                    volume,
+                    trade_count: 1,
+                },
+            };
+            self.state.candles.insert(&key, candle)?;
+        }
+        Ok(())
+    }
 
-        // Update leaderboard after fee distribution
-        self.update_enhanced_leaderboard().await;
+    /// Points paid per unit traded, for `total` points exchanged for `amount` units
+    fn unit_price(total: Amount, amount: Amount) -> Amount {
+        if amount == Amount::ZERO {
+            return Amount::ZERO;
+        }
+        Amount::from_attos(mul_div(total.into(), ONE, amount.into()))
+    }
+
+    /// Check that the caller may manage a market
+    /// Only the market creator and the admin can close or cancel a market
+    fn ensure_market_authority(
+        &self,
+        caller: PlayerId,
+        market: &Market,
+    ) -> Result<(), ContractError> {
+        if caller == market.creator || self.state.config.get().admin == Some(caller) {
+            Ok(())
+        } else {
+            Err(ContractError::Unauthorized)
+        }
+    }
 
+    /// Return a market's remaining liquidity, and the payment reserve of a constant-product pool,
+    /// to its liquidity providers in proportion to their LP shares
+    async fn return_market_liquidity(&mut self, market: &mut Market) -> Result<(), ContractError> {
+        let pool = market
+            .total_liquidity
+            .saturating_add(market.payment_reserve);
+        for (provider_id, payout) in Self::provider_payouts(market, pool) {
+            let mut provider = self.get_player(&provider_id).await?;
+            self.ledger_credit(
+                &mut provider,
+                payout,
+                LedgerAccount::market(market.id),
+                LedgerReason::LiquidityWithdrawal,
+            )
+            .await?;
+            self.state.players.insert(&provider_id, provider)?;
+        }
+        market.total_liquidity = Amount::ZERO;
+        market.payment_reserve = Amount::ZERO;
+        market.liquidity_positions.clear();
+        market.total_lp_shares = Amount::ZERO;
         Ok(())
     }
 
-    /// Distribute trading fees to market creator and platform
-    /// Platform gets 2% of the creator's fee from each trade
-    /// Creator gets the remaining fee (98% of their fee percentage)
-    async fn distribute_trading_fees(
+    /// Move a market to a new status and store it
+    /// The market leaves the active markets of its creator and every position holder,
+    /// and the new status is broadcast to the global market registry
+    async fn set_market_status(
         &mut self,
-        market_id: MarketId,
-        creator_fee_amount: Amount,
+        mut market: Market,
+        status: MarketStatus,
     ) -> Result<(), ContractError> {
-        let market = self.get_market(&market_id).await?;
-
-        // Platform gets 2% of the creator's fee
-        let platform_fee_percent = 2_u128; // 2% of creator's fee
-        let config = self.state.config.get();
-        let fee_divisor = config.fee_divisor.into();
+        let market_id = market.id;
+        market.status = status;
 
-        let creator_fee_tokens: u128 = creator_fee_amount.into();
-        let platform_fee_tokens = creator_fee_tokens
-            .saturating_mul(platform_fee_percent)
-            .saturating_div(fee_divisor);
-        let platform_fee = Amount::from_tokens(platform_fee_tokens);
-        let creator_keeps = creator_fee_amount.saturating_sub(platform_fee);
+        let participants: Vec<PlayerId> = std::iter::once(market.creator)
+            .chain(market.positions.keys().copied())
+            .collect();
+        for participant in &participants {
+            if let Some(mut account) = self.find_account(participant).await? {
+                account.active_markets.retain(|id| *id != market_id);
+                self.store_account(account).await?;
+            }
+        }
 
-        // Give creator their share (creator's fee minus 2% platform fee)
-        let mut creator_player = self.get_player(&market.creator).await?;
-        creator_player.token_balance = creator_player.token_balance.saturating_add(creator_keeps);
-        creator_player.total_earned = creator_player.total_earned.saturating_add(creator_keeps);
-        creator_player.total_profit = creator_player.total_profit.saturating_add(creator_keeps);
-        self.state.players.insert(&market.creator, creator_player)?;
+        self.state.markets.insert(&market_id, market)?;
+        self.state.market_close_times.remove(&market_id)?;
+        if matches!(status, MarketStatus::Resolved | MarketStatus::Cancelled) {
+            self.send_remote_payouts(&participants).await?;
+        }
 
-        // Add platform fee (2% of creator's fee) to total supply
-        let current_supply = self.state.total_supply.get();
-        self.state
-            .total_supply
-            .set(current_supply.saturating_add(platform_fee));
+        // Broadcast status change to all chains for horizontal scaling
+        self.broadcast_global_market_status(market_id, status).await;
 
         Ok(())
     }
 
-    // ============================================================================
-    // Helper Functions
-    // ============================================================================
+    /// Fill in the post-operation balances of a receipt
+    /// Reads the signer's balance and, when set, the market liquidity and guild pool
+    async fn complete_receipt(
+        &self,
+        player_id: PlayerId,
+        mut receipt: OperationReceipt,
+    ) -> OperationReceipt {
+        if let Ok(Some(player)) = self.state.players.get(&player_id).await {
+            receipt.token_balance = Some(player.token_balance);
+        }
+        if let Some(market_id) = receipt.market_id {
+            if let Ok(Some(market)) = self.state.markets.get(&market_id).await {
+                receipt.market_liquidity = Some(market.total_liquidity);
+            }
+        }
+        if let Some(guild_id) = receipt.guild_id {
+            if let Ok(Some(guild)) = self.state.guilds.get(&guild_id).await {
+                receipt.guild_pool = Some(guild.shared_pool);
+            }
+        }
+        receipt
+    }
 
-    /// Get a player by their ID
-    /// Helper function to retrieve player data from storage
-    async fn get_player(&self, player_id: &PlayerId) -> Result<Player, ContractError> {
-        self.state
-            .players
-            .get(player_id)
-            .await?
-            .ok_or(ContractError::PlayerNotFound)
+    /// Save the state changed so far and send the messages prepared with it,
+    /// so that a later rollback keeps both
+    async fn checkpoint(&mut self) {
+        self.state.save().await.expect("Failed to save state");
+        self.send_outbox();
     }
 
-    /// Get a market by its ID
-    /// Helper function to retrieve market data from storage
-    async fn get_market(&self, market_id: &MarketId) -> Result<Market, ContractError> {
-        self.state
-            .markets
-            .get(market_id)
-            .await?
-            .ok_or(ContractError::MarketNotFound)
+    /// Discard the state changes and messages prepared since the last checkpoint
+    fn rollback(&mut self) {
+        self.state.rollback();
+        self.outbox.clear();
+    }
+
+    /// Queue an event for this chain, sent once the changes behind it are kept
+    fn emit(&mut self, message: Message) {
+        let destination = self.runtime.chain_id();
+        self.outbox.push(OutgoingMessage {
+            message,
+            destination,
+            tracked: false,
+        });
+    }
+
+    /// Queue an authenticated, tracked message for another chain, sent once the changes behind it are kept
+    fn send_tracked(&mut self, message: Message, destination: ChainId) {
+        self.outbox.push(OutgoingMessage {
+            message,
+            destination,
+            tracked: true,
+        });
+    }
+
+    /// Send every queued message
+    fn send_outbox(&mut self) {
+        for outgoing in std::mem::take(&mut self.outbox) {
+            let message = self.runtime.prepare_message(outgoing.message);
+            if outgoing.tracked {
+                message
+                    .with_authentication()
+                    .with_tracking()
+                    .send_to(outgoing.destination);
+            } else {
+                message.send_to(outgoing.destination);
+            }
+        }
     }
 
     /// Generate a unique market ID
@@ -1402,12 +5122,10 @@ impl PredictionMarketContract {
             if player.experience_points >= total_points_needed {
                 player.level += 1;
                 // Emit PlayerLeveledUp event for each level up
-                self.runtime
-                    .prepare_message(Message::PlayerLeveledUp {
-                        player_id: player.id,
-                        new_level: player.level,
-                    })
-                    .send_to(self.runtime.chain_id());
+                self.emit(Message::PlayerLeveledUp {
+                    player_id: player.id,
+                    new_level: player.level,
+                });
                 // Don't subtract - we track total experience points, not incremental
                 // Continue checking if they can level up multiple times
             } else {
@@ -1437,9 +5155,12 @@ impl PredictionMarketContract {
                     {
                         // Award achievement
                         player.achievements_earned.push(achievement_id);
-                        player.token_balance = player
-                            .token_balance
-                            .saturating_add(achievement.reward_tokens);
+                        self.credit_reward(
+                            player,
+                            achievement.reward_tokens,
+                            LedgerReason::AchievementReward,
+                        )
+                        .await?;
                         player.total_earned = player
                             .total_earned
                             .saturating_add(achievement.reward_tokens);
@@ -1448,12 +5169,10 @@ impl PredictionMarketContract {
                         new_achievements.push(achievement_id);
 
                         // Send achievement notification
-                        self.runtime
-                            .prepare_message(Message::AchievementUnlocked {
-                                player_id: player.id,
-                                achievement_id,
-                            })
-                            .send_to(self.runtime.chain_id());
+                        self.emit(Message::AchievementUnlocked {
+                            player_id: player.id,
+                            achievement_id,
+                        });
                     }
                 }
             }
@@ -1485,356 +5204,170 @@ impl PredictionMarketContract {
                         }
                     }
                 }
-                Ok(created_count >= 1)
-            }
-            AchievementRequirement::FirstBuy => {
-                // Check if player has made at least one buy
-                // Player has participated in markets (bought points from a market)
-                Ok(player.markets_participated > 0)
-            }
-            AchievementRequirement::FirstSell => {
-                // Check if player has sold points
-                // A player has sold if they've spent points (sold points reduce balance)
-                // We can also check if they've participated in markets as a proxy
-                Ok(player.total_spent > Amount::ZERO)
-            }
-            AchievementRequirement::JoinGuild => Ok(player.guild_id.is_some()),
-            AchievementRequirement::ReachLevel(level) => Ok(player.level >= *level),
-            // Legacy requirements (kept for backward compatibility)
-            AchievementRequirement::WinMarkets(count) => Ok(player.markets_won >= *count),
-            AchievementRequirement::WinStreak(streak) => Ok(player.win_streak >= *streak),
-            AchievementRequirement::TotalProfit(profit) => Ok(player.total_profit >= *profit),
-            AchievementRequirement::ParticipateInMarkets(count) => {
-                Ok(player.markets_participated >= *count)
-            }
-            AchievementRequirement::CreateMarkets(count) => {
-                // Count markets created by this player
-                let mut created_count = 0;
-                for market_id in &player.active_markets {
-                    if let Some(market) = self.state.markets.get(market_id).await? {
-                        if market.creator == player.id {
-                            created_count += 1;
-                        }
-                    }
-                }
-                Ok(created_count >= *count)
-            }
-        }
-    }
-
-    // ============================================================================
-    //  Price Prediction Functions
-    // ============================================================================
-
-    /// The price prediction model predicts the price of real assets market price from crypto price API providers
-    /// like CoinMarketCap, CoinGecko, etc.
-    /// Players can predict if the market will rise or fall daily, weekly and monthly and earn point tokens
-    /// based on the accuracy of the prediction.
-    ///
-    /// IMPORTANT: All price comparisons use data from crypto API providers:
-    /// - Admin calls update_market_price() with price from crypto API (CoinMarketCap, CoinGecko, etc.)
-    /// - Initial price is captured from crypto API when period starts
-    /// - End price is captured from crypto API when period ends
-    /// - Player's prediction is compared against actual outcome from crypto API price changes
-    ///
-    /// The outcome will depend on the change of crypto API price:
-    /// - Rise: end_price > initial_price (crypto API price increased from initial)
-    /// - Fall: end_price < initial_price (crypto API price decreased from initial)
-    /// - Neutral: end_price == initial_price (crypto API price stayed the same as initial)
-
-    /// Make a daily prediction for market price movement
-    /// Players predict if the market price will rise, fall, or stay neutral over the next 24 hours
-    ///
-    /// # Arguments
-    /// * `player_id` - The player making the prediction
-    /// * `outcome` - The predicted outcome (Rise, Fall, or Neutral)
-    /// * `current_time` - Current timestamp
-    ///
-    /// # Returns
-    /// * `Ok(())` - Prediction recorded successfully
-    /// * `Err(PlayerNotFound)` - Player doesn't exist
-    async fn predict_daily_outcome(
-        &mut self,
-        player_id: PlayerId,
-        outcome: PriceOutcome,
-        current_time: Timestamp,
-    ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-
-        // Calculate the current day period start (midnight of current day)
-        let period_start = self.get_daily_period_start(current_time);
-
-        // Check if player already has a prediction for this period
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
-            player_id,
-            PredictionPeriod::Daily,
-            period_start.micros()
-        );
-
-        if self.state.predictions.contains_key(&prediction_key).await? {
-            return Err(ContractError::InvalidOutcome); // Reuse error: already predicted for this period
-        }
-
-        // Create the prediction
-        let prediction = PlayerPrediction {
-            player_id,
-            period: PredictionPeriod::Daily,
-            outcome,
-            prediction_time: current_time,
-            period_start,
-            resolved: false,
-            correct: None,
-        };
-
-        // Store the prediction
-        self.state
-            .predictions
-            .insert(&prediction_key, prediction.clone())?;
-
-        // Update reputation for making a prediction
-        player.reputation = player.reputation.saturating_add(2);
-        self.state.players.insert(&player_id, player)?;
-
-        // Initialize period price data if it doesn't exist
-        let period_key = format!("{:?}_{}", PredictionPeriod::Daily, period_start.micros());
-        if !self.state.period_prices.contains_key(&period_key).await? {
-            // Capture the initial market price from crypto API when the period starts
-            // This price comes from crypto API providers (CoinMarketCap, CoinGecko, etc.)
-            // via the update_market_price function called by admin
-            let initial_price = self.state.current_market_price.get();
-            let period_price_data = PeriodPriceData {
-                period_start,
-                period_end: Timestamp::from(
-                    period_start
-                        .micros()
-                        .saturating_add(24 * 60 * 60 * 1_000_000),
-                ), // 24 hours later
-                start_price: Some(initial_price.clone()), // Initial crypto API price at period start
-                end_price: None, // Will be set from crypto API when period ends
-                outcome: None,
-                resolved: false,
-            };
-            self.state
-                .period_prices
-                .insert(&period_key, period_price_data)?;
-        }
-
-        // Send prediction message
-        self.runtime
-            .prepare_message(Message::PredictionMade {
-                player_id,
-                period: PredictionPeriod::Daily,
-                outcome,
-            })
-            .send_to(self.runtime.chain_id());
-
-        Ok(())
-    }
-
-    /// Make a weekly prediction for market price movement
-    /// Players predict if the market price will rise, fall, or stay neutral over the next 7 days
-    ///
-    /// # Arguments
-    /// * `player_id` - The player making the prediction
-    /// * `outcome` - The predicted outcome (Rise, Fall, or Neutral)
-    /// * `current_time` - Current timestamp
-    ///
-    /// # Returns
-    /// * `Ok(())` - Prediction recorded successfully
-    /// * `Err(PlayerNotFound)` - Player doesn't exist
-    async fn predict_weekly_outcome(
-        &mut self,
-        player_id: PlayerId,
-        outcome: PriceOutcome,
-        current_time: Timestamp,
-    ) -> Result<(), ContractError> {
-        let mut player = self.get_player(&player_id).await?;
-
-        // Calculate the current week period start (start of current week)
-        let period_start = self.get_weekly_period_start(current_time);
-
-        // Check if player already has a prediction for this period
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
-            player_id,
-            PredictionPeriod::Weekly,
-            period_start.micros()
-        );
-
-        if self.state.predictions.contains_key(&prediction_key).await? {
-            return Err(ContractError::InvalidOutcome); // Already predicted for this period
-        }
-
-        // Create the prediction
-        let prediction = PlayerPrediction {
-            player_id,
-            period: PredictionPeriod::Weekly,
-            outcome,
-            prediction_time: current_time,
-            period_start,
-            resolved: false,
-            correct: None,
-        };
-
-        // Store the prediction
-        self.state
-            .predictions
-            .insert(&prediction_key, prediction.clone())?;
-
-        // Update reputation for making a prediction
-        player.reputation = player.reputation.saturating_add(2);
-        self.state.players.insert(&player_id, player)?;
-
-        // Initialize period price data if it doesn't exist
-        let period_key = format!("{:?}_{}", PredictionPeriod::Weekly, period_start.micros());
-        if !self.state.period_prices.contains_key(&period_key).await? {
-            // Capture the initial market price from crypto API when the period starts
-            // This price comes from crypto API providers (CoinMarketCap, CoinGecko, etc.)
-            // via the update_market_price function called by admin
-            let initial_price = self.state.current_market_price.get();
-            let period_price_data = PeriodPriceData {
-                period_start,
-                period_end: Timestamp::from(
-                    period_start
-                        .micros()
-                        .saturating_add(7 * 24 * 60 * 60 * 1_000_000),
-                ), // 7 days later
-                start_price: Some(initial_price.clone()), // Initial crypto API price at period start
-                end_price: None, // Will be set from crypto API when period ends
-                outcome: None,
-                resolved: false,
-            };
-            self.state
-                .period_prices
-                .insert(&period_key, period_price_data)?;
+                Ok(created_count >= 1)
+            }
+            AchievementRequirement::FirstBuy => {
+                // Check if player has made at least one buy
+                // Player has participated in markets (bought points from a market)
+                Ok(player.markets_participated > 0)
+            }
+            AchievementRequirement::FirstSell => {
+                // Check if player has sold points
+                // A player has sold if they've spent points (sold points reduce balance)
+                // We can also check if they've participated in markets as a proxy
+                Ok(player.total_spent > Amount::ZERO)
+            }
+            AchievementRequirement::JoinGuild => Ok(player.guild_id.is_some()),
+            AchievementRequirement::ReachLevel(level) => Ok(player.level >= *level),
+            // Legacy requirements (kept for backward compatibility)
+            AchievementRequirement::WinMarkets(count) => Ok(player.markets_won >= *count),
+            AchievementRequirement::WinStreak(streak) => Ok(player.win_streak >= *streak),
+            AchievementRequirement::TotalProfit(profit) => Ok(player.total_profit >= *profit),
+            AchievementRequirement::ParticipateInMarkets(count) => {
+                Ok(player.markets_participated >= *count)
+            }
+            AchievementRequirement::CreateMarkets(count) => {
+                // Count markets created by this player
+                let mut created_count = 0;
+                for market_id in &player.active_markets {
+                    if let Some(market) = self.state.markets.get(market_id).await? {
+                        if market.creator == player.id {
+                            created_count += 1;
+                        }
+                    }
+                }
+                Ok(created_count >= *count)
+            }
         }
+    }
 
-        // Send prediction message
-        self.runtime
-            .prepare_message(Message::PredictionMade {
-                player_id,
-                period: PredictionPeriod::Weekly,
-                outcome,
-            })
-            .send_to(self.runtime.chain_id());
+    // ============================================================================
+    //  Price Prediction Functions
+    // ============================================================================
 
-        Ok(())
-    }
+    /// The price prediction model predicts the price of real assets market price from crypto price API providers
+    /// like CoinMarketCap, CoinGecko, etc.
+    /// Players can predict if the market will rise or fall daily, weekly and monthly and earn point tokens
+    /// based on the accuracy of the prediction.
+    ///
+    /// IMPORTANT: All price comparisons use data from crypto API providers:
+    /// - Admin calls update_market_price() with price from crypto API (CoinMarketCap, CoinGecko, etc.)
+    /// - Initial price is captured from crypto API when period starts
+    /// - End price is captured from crypto API when period ends
+    /// - Player's prediction is compared against actual outcome from crypto API price changes
+    ///
+    /// The outcome will depend on the change of crypto API price:
+    /// - Rise: end_price > initial_price (crypto API price increased from initial)
+    /// - Fall: end_price < initial_price (crypto API price decreased from initial)
+    /// - Neutral: end_price == initial_price (crypto API price stayed the same as initial)
 
-    /// Make a monthly prediction for market price movement
-    /// Players predict if the market price will rise, fall, or stay neutral over the next 30 days
+    /// Make a prediction for market price movement over a daily, weekly or monthly period
+    /// Players predict if the market price will rise, fall, or stay neutral over the current period
     ///
     /// # Arguments
     /// * `player_id` - The player making the prediction
+    /// * `asset` - The asset whose price is predicted
+    /// * `period` - The period predicted over (Daily, Weekly, or Monthly)
     /// * `outcome` - The predicted outcome (Rise, Fall, or Neutral)
+    /// * `stake` - Points escrowed on the prediction
     /// * `current_time` - Current timestamp
     ///
     /// # Returns
-    /// * `Ok(())` - Prediction recorded successfully
+    /// * `Ok(period_start)` - Prediction recorded successfully
     /// * `Err(PlayerNotFound)` - Player doesn't exist
-    async fn predict_monthly_outcome(
+    /// * `Err(InDebt)` - Player owes unpaid penalties from earlier predictions
+    /// * `Err(UnknownAsset)` - Asset is not listed in the config
+    /// * `Err(InvalidOutcome)` - Player already predicted this asset for the current period
+    /// * `Err(InvalidStake)` - Stake is outside the period's limits
+    /// * `Err(InsufficientBalance)` - Player can't cover the stake
+    async fn predict_outcome(
         &mut self,
         player_id: PlayerId,
+        asset: AssetSymbol,
+        period: PredictionPeriod,
         outcome: PriceOutcome,
+        stake: Amount,
         current_time: Timestamp,
-    ) -> Result<(), ContractError> {
+    ) -> Result<Timestamp, ContractError> {
         let mut player = self.get_player(&player_id).await?;
+        Self::ensure_no_debt(&player)?;
+        self.ensure_asset_listed(&asset)?;
 
-        // Calculate the current month period start (start of current month)
-        let period_start = self.get_monthly_period_start(current_time);
+        // Calculate the start of the current period (midnight, start of week or month)
+        let period_start = self.get_period_start(period, current_time);
 
         // Check if player already has a prediction for this period
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
-            player_id,
-            PredictionPeriod::Monthly,
-            period_start.micros()
-        );
+        let prediction_key = prediction_key(player_id, &asset, period, period_start);
 
         if self.state.predictions.contains_key(&prediction_key).await? {
-            return Err(ContractError::InvalidOutcome); // Already predicted for this period
+            return Err(ContractError::InvalidOutcome); // Reuse error: already predicted for this period
         }
+        self.escrow_prediction_stake(&mut player, period, stake)
+            .await?;
 
         // Create the prediction
         let prediction = PlayerPrediction {
             player_id,
-            period: PredictionPeriod::Monthly,
+            asset: asset.clone(),
+            period,
             outcome,
             prediction_time: current_time,
             period_start,
             resolved: false,
             correct: None,
+            stake,
         };
 
         // Store the prediction
         self.state
             .predictions
             .insert(&prediction_key, prediction.clone())?;
+        self.add_to_prediction_pool(&prediction).await?;
 
         // Update reputation for making a prediction
         player.reputation = player.reputation.saturating_add(2);
         self.state.players.insert(&player_id, player)?;
 
-        // Initialize period price data if it doesn't exist
-        let period_key = format!("{:?}_{}", PredictionPeriod::Monthly, period_start.micros());
-        if !self.state.period_prices.contains_key(&period_key).await? {
-            // Capture the initial market price from crypto API when the period starts
-            // This price comes from crypto API providers (CoinMarketCap, CoinGecko, etc.)
-            // via the update_market_price function called by admin
-            let initial_price = self.state.current_market_price.get();
-            let period_price_data = PeriodPriceData {
-                period_start,
-                period_end: Timestamp::from(
-                    period_start
-                        .micros()
-                        .saturating_add(30 * 24 * 60 * 60 * 1_000_000),
-                ), // 30 days later
-                start_price: Some(initial_price.clone()), // Initial crypto API price at period start
-                end_price: None, // Will be set from crypto API when period ends
-                outcome: None,
-                resolved: false,
-            };
-            self.state
-                .period_prices
-                .insert(&period_key, period_price_data)?;
-        }
+        self.open_period_prices(&asset, period, period_start)
+            .await?;
 
         // Send prediction message
-        self.runtime
-            .prepare_message(Message::PredictionMade {
-                player_id,
-                period: PredictionPeriod::Monthly,
-                outcome,
-            })
-            .send_to(self.runtime.chain_id());
+        self.emit(Message::PredictionMade {
+            player_id,
+            asset,
+            period,
+            outcome,
+        });
 
-        Ok(())
+        Ok(period_start)
     }
 
-    /// Update the current market price from crypto price API providers (Admin only)
+    /// Update an asset's market price from crypto price API providers (Admin only)
     /// This function is called by an admin to update market prices from external APIs
     /// like CoinMarketCap, CoinGecko, etc.
-    /// When prices are updated, the contract automatically resolves predictions for expired periods
+    /// When prices are updated, the contract automatically resolves the asset's predictions for expired periods
     ///
     /// # Arguments
     /// * `caller` - The player calling this function (must be admin)
+    /// * `asset` - The asset the price is for, as listed in the config
     /// * `price` - The current market price from crypto API provider (e.g., CoinMarketCap, CoinGecko)
     /// * `current_time` - Current timestamp
     ///
     /// # Returns
     /// * `Ok(())` - Price updated successfully
     /// * `Err(NotAdmin)` - Caller is not authorized
+    /// * `Err(UnknownAsset)` - Asset is not listed in the config
     ///
     /// # Usage Example:
     /// ```rust
     /// // Admin fetches price from CoinGecko API (e.g., BTC price = $50,000)
     /// let crypto_price = Amount::from_tokens(50000); // Convert to Amount
-    /// contract.update_market_price(admin_id, crypto_price, current_time).await?;
+    /// contract.update_market_price(admin_id, "BTC".to_string(), crypto_price, current_time).await?;
     /// ```
     async fn update_market_price(
         &mut self,
         caller: PlayerId,
+        asset: AssetSymbol,
         price: Amount,
         current_time: Timestamp,
     ) -> Result<(), ContractError> {
@@ -1851,21 +5384,32 @@ impl PredictionMarketContract {
             return Err(ContractError::NotAdmin);
         }
 
-        // Update current market price with data from crypto API provider
+        self.ensure_asset_listed(&asset)?;
+
+        // Update the asset's market price with data from crypto API provider
         let market_price = MarketPrice {
             price, // Price from crypto API (CoinMarketCap, CoinGecko, etc.)
             timestamp: current_time,
         };
-        self.state.current_market_price.set(market_price);
+        self.state.asset_prices.insert(&asset, market_price)?;
+        self.update_candles(
+            CandleSource::PriceFeed(asset.clone()),
+            0,
+            price,
+            Amount::ZERO,
+            current_time,
+        )
+        .await?;
+
+        // Try to resolve the asset's expired periods and their predictions
+        // This compares end_price (from crypto API) to initial_price (from crypto API)
+        self.resolve_expired_predictions(&asset, current_time)
+            .await?;
 
         // Broadcast price update to all chains for horizontal scaling
-        self.broadcast_global_price_update(price, current_time)
+        self.broadcast_global_price_update(asset, price, current_time)
             .await;
 
-        // Try to resolve expired periods and their predictions
-        // This compares end_price (from crypto API) to initial_price (from crypto API)
-        self.resolve_expired_predictions(current_time).await?;
-
         Ok(())
     }
 
@@ -1900,6 +5444,140 @@ impl PredictionMarketContract {
         Timestamp::from(month_start)
     }
 
+    /// Calculate the start timestamp of the period of the given type containing `timestamp`
+    fn get_period_start(&self, period: PredictionPeriod, timestamp: Timestamp) -> Timestamp {
+        match period {
+            PredictionPeriod::Daily => self.get_daily_period_start(timestamp),
+            PredictionPeriod::Weekly => self.get_weekly_period_start(timestamp),
+            PredictionPeriod::Monthly => self.get_monthly_period_start(timestamp),
+        }
+    }
+
+    /// Length of a period in microseconds, as used for the period ends of its price data
+    fn period_length_micros(period: PredictionPeriod) -> u64 {
+        match period {
+            PredictionPeriod::Daily => 24 * 60 * 60 * 1_000_000,
+            PredictionPeriod::Weekly => 7 * 24 * 60 * 60 * 1_000_000,
+            PredictionPeriod::Monthly => 30 * 24 * 60 * 60 * 1_000_000, // Approximate
+        }
+    }
+
+    /// Initialize an asset's price data for a period if it doesn't exist, returning its key
+    /// The start price is the asset's current price from crypto API providers (CoinMarketCap,
+    /// CoinGecko, etc.), set by the admin through update_market_price
+    async fn open_period_prices(
+        &mut self,
+        asset: &str,
+        period: PredictionPeriod,
+        period_start: Timestamp,
+    ) -> Result<String, ContractError> {
+        let period_key = period_key(asset, period, period_start);
+        if !self.state.period_prices.contains_key(&period_key).await? {
+            let initial_price = self.asset_price(asset).await?;
+            let period_price_data = PeriodPriceData {
+                asset: asset.to_string(),
+                period,
+                period_start,
+                period_end: Timestamp::from(
+                    period_start
+                        .micros()
+                        .saturating_add(Self::period_length_micros(period)),
+                ),
+                start_price: Some(initial_price), // Initial crypto API price at period start
+                end_price: None,                  // Will be set from crypto API when period ends
+                outcome: None,
+                resolved: false,
+            };
+            self.state
+                .period_prices
+                .insert(&period_key, period_price_data)?;
+        }
+        Ok(period_key)
+    }
+
+    /// Register an oracle market to be resolved with its period's price data
+    /// Captures the period's start price if no prediction has done so yet
+    async fn track_oracle_market(
+        &mut self,
+        market_id: MarketId,
+        condition: OracleCondition,
+    ) -> Result<(), ContractError> {
+        let period_key = self
+            .open_period_prices(&condition.asset, condition.period, condition.period_start)
+            .await?;
+
+        let mut market_ids = self
+            .state
+            .oracle_markets
+            .get(&period_key)
+            .await?
+            .unwrap_or_default();
+        market_ids.push(market_id);
+        self.state.oracle_markets.insert(&period_key, market_ids)?;
+        Ok(())
+    }
+
+    /// Resolve the oracle markets of a period once its start and end prices are known
+    /// "Yes" (outcome 0) wins when the price moved as a market's condition states, "No" (outcome 1) otherwise
+    async fn resolve_oracle_markets(
+        &mut self,
+        asset: &str,
+        period: PredictionPeriod,
+        period_start: Timestamp,
+    ) -> Result<(), ContractError> {
+        let period_key = period_key(asset, period, period_start);
+        let Some(market_ids) = self.state.oracle_markets.get(&period_key).await? else {
+            return Ok(());
+        };
+        let Some(period_data) = self.state.period_prices.get(&period_key).await? else {
+            return Ok(());
+        };
+        let (Some(initial_price), Some(end_price)) =
+            (period_data.start_price, period_data.end_price)
+        else {
+            return Ok(());
+        };
+        let actual_outcome =
+            self.calculate_outcome_from_prices(initial_price.price, end_price.price);
+
+        // Markets that can't be paid out are skipped before any write, so an error here is a
+        // real failure and fails the price update as a whole, leaving every market listed
+        for market_id in market_ids {
+            self.resolve_oracle_market(market_id, actual_outcome)
+                .await?;
+        }
+        self.state.oracle_markets.remove(&period_key)?;
+        Ok(())
+    }
+
+    /// Pay out an oracle market from its period's actual outcome
+    /// Markets that are gone or no longer open are skipped
+    async fn resolve_oracle_market(
+        &mut self,
+        market_id: MarketId,
+        actual_outcome: PriceOutcome,
+    ) -> Result<(), ContractError> {
+        let Some(mut market) = self.state.markets.get(&market_id).await? else {
+            return Ok(());
+        };
+        // Cancelled markets have already refunded their traders
+        if !matches!(market.status, MarketStatus::Active | MarketStatus::Closed) {
+            return Ok(());
+        }
+        let Some(condition) = market.oracle.clone() else {
+            return Ok(());
+        };
+
+        let outcome_id = if condition.outcome == actual_outcome {
+            0
+        } else {
+            1
+        };
+        self.settle_outcome(&mut market, outcome_id).await?;
+        self.return_market_liquidity(&mut market).await?;
+        self.set_market_status(market, MarketStatus::Resolved).await
+    }
+
     /// Resolve a prediction by comparing it to the actual outcome
     /// This function calculates whether a player's prediction was correct
     async fn resolve_prediction(
@@ -1913,7 +5591,7 @@ impl PredictionMarketContract {
         }
 
         // Get period price data
-        let period_key = format!("{:?}_{}", period, period_start.micros());
+        let period_key = period_key(&prediction.asset, period, period_start);
 
         if let Some(mut period_data) = self
             .state
@@ -1947,41 +5625,55 @@ impl PredictionMarketContract {
                 self.state.period_prices.insert(&period_key, period_data)?;
 
                 // Update prediction in storage
-                let prediction_key = format!(
-                    "{:?}_{:?}_{}",
+                let prediction_key = prediction_key(
                     prediction.player_id,
+                    &prediction.asset,
                     period,
-                    period_start.micros()
+                    period_start,
                 );
                 self.state
                     .predictions
                     .insert(&prediction_key, prediction.clone())?;
 
                 // Award or penalize based on prediction correctness
-                if is_correct {
-                    // Correct prediction: award points based on period (Daily: 100, Weekly: 500, Monthly: 1000)
-                    self.award_prediction_reward(&prediction.player_id, period)
+                let pool = self.state.prediction_pools.get(&period_key).await?;
+                let paid = if let Some(pool) = pool.as_ref().filter(|pool| pool.parimutuel) {
+                    // Parimutuel: the player's share of the pool, nothing is minted
+                    self.settle_pool_prediction(prediction, pool, actual_outcome)
+                        .await?
+                } else if is_correct {
+                    // Correct prediction: return the stake plus the period's reward on it
+                    let reward = self
+                        .award_prediction_reward(&prediction.player_id, period, prediction.stake)
                         .await?;
                     // If player is in a guild, award all guild members the same amount
-                    self.award_guild_prediction_reward(&prediction.player_id, period)
+                    self.award_guild_prediction_reward(&prediction.player_id, period, reward)
                         .await?;
+                    prediction.stake
                 } else {
-                    // Wrong prediction: deduct points based on period (Daily: 100, Weekly: 500, Monthly: 1000)
-                    self.penalize_prediction_loss(&prediction.player_id, period)
-                        .await?;
-                    // If player is in a guild, deduct the same amount from all guild members
-                    self.penalize_guild_prediction_loss(&prediction.player_id, period)
+                    // Wrong prediction: the escrowed stake is forfeited
+                    self.penalize_prediction_loss(&prediction.player_id, prediction.stake)
                         .await?;
+                    // If player is in a guild, the other members are penalized too
+                    self.penalize_guild_prediction_loss(
+                        &prediction.player_id,
+                        period,
+                        prediction.stake,
+                    )
+                    .await?;
+                    prediction.stake
+                };
+                if let Some(pool) = pool {
+                    self.record_pool_settlement(&period_key, pool, paid).await?;
                 }
 
                 // Send resolution message
-                self.runtime
-                    .prepare_message(Message::PredictionResolved {
-                        player_id: prediction.player_id,
-                        period,
-                        correct: is_correct,
-                    })
-                    .send_to(self.runtime.chain_id());
+                self.emit(Message::PredictionResolved {
+                    player_id: prediction.player_id,
+                    asset: prediction.asset.clone(),
+                    period,
+                    correct: is_correct,
+                });
             }
         }
 
@@ -2007,26 +5699,27 @@ impl PredictionMarketContract {
         }
     }
 
-    /// Resolve all expired predictions when market price is updated
+    /// Resolve an asset's expired predictions when its market price is updated
     async fn resolve_expired_predictions(
         &mut self,
+        asset: &str,
         current_time: Timestamp,
     ) -> Result<(), ContractError> {
-        // Check daily, weekly, and monthly periods
-        let periods = vec![
-            PredictionPeriod::Daily,
-            PredictionPeriod::Weekly,
-            PredictionPeriod::Monthly,
-        ];
-
-        for period in periods {
-            let period_start = match period {
-                PredictionPeriod::Daily => self.get_daily_period_start(current_time),
-                PredictionPeriod::Weekly => self.get_weekly_period_start(current_time),
-                PredictionPeriod::Monthly => self.get_monthly_period_start(current_time),
-            };
+        // Collect every stored period of this asset that is still unresolved
+        // The periods containing current_time have not ended yet, so earlier ones must be scanned
+        let mut pending_periods = Vec::new();
+        self.state
+            .period_prices
+            .for_each_index_value(|_period_key, period_data| {
+                if period_data.asset == asset && !period_data.resolved {
+                    pending_periods.push((period_data.period, period_data.period_start));
+                }
+                Ok(())
+            })
+            .await?;
 
-            let period_key = format!("{:?}_{}", period, period_start.micros());
+        for (period, period_start) in pending_periods {
+            let period_key = period_key(asset, period, period_start);
 
             if let Some(mut period_data) = self
                 .state
@@ -2040,8 +5733,8 @@ impl PredictionMarketContract {
                     // If initial price (start_price) is not set, set it from current crypto API price
                     // This shouldn't normally happen, but handle it as a fallback
                     if period_data.start_price.is_none() {
-                        let current_price = self.state.current_market_price.get(); // From crypto API
-                        period_data.start_price = Some(current_price.clone());
+                        let current_price = self.asset_price(asset).await?; // From crypto API
+                        period_data.start_price = Some(current_price);
                         self.state
                             .period_prices
                             .insert(&period_key, period_data.clone())?;
@@ -2049,37 +5742,48 @@ impl PredictionMarketContract {
                     // If end price is not set, set it from current crypto API price when period ends
                     // The admin should have called update_market_price() with the latest crypto API price
                     if period_data.end_price.is_none() {
-                        let current_price = self.state.current_market_price.get(); // From crypto API (CoinMarketCap, CoinGecko, etc.)
-                        period_data.end_price = Some(current_price.clone());
+                        let current_price = self.asset_price(asset).await?; // From crypto API (CoinMarketCap, CoinGecko, etc.)
+                        period_data.end_price = Some(current_price);
                         self.state
                             .period_prices
                             .insert(&period_key, period_data.clone())?;
                     }
 
-                    // Now resolve all predictions for this period
-                    // Iterate through all predictions and resolve those matching this period
-                    let period_start_micros = period_start.micros();
-                    let period_str = format!("{:?}", period);
-                    let period_suffix = format!("_{}_{}", period_str, period_start_micros);
+                    // Markets betting on this period resolve from the same prices
+                    self.resolve_oracle_markets(asset, period, period_start)
+                        .await?;
+
+                    // The period is settled once its prices are in, whether or not anyone
+                    // predicted on it, so later price updates stop rescanning it
+                    if let (Some(initial_price), Some(end_price)) =
+                        (&period_data.start_price, &period_data.end_price)
+                    {
+                        period_data.outcome =
+                            Some(self.calculate_outcome_from_prices(
+                                initial_price.price,
+                                end_price.price,
+                            ));
+                    }
+                    period_data.resolved = true;
+                    self.state
+                        .period_prices
+                        .insert(&period_key, period_data.clone())?;
 
-                    // Collect all prediction keys for this period
+                    // Now resolve all of the asset's predictions for this period
                     let mut prediction_keys_to_resolve = Vec::new();
                     self.state
                         .predictions
                         .for_each_index_value(|pred_key, prediction| {
-                            // Check if this prediction matches the current period
-                            // Prediction keys are in format: "{:?}_{:?}_{}", so we check the suffix
-                            if pred_key.ends_with(&period_suffix) {
-                                // Check if prediction is not already resolved
-                                let pred = prediction.clone();
-                                if !pred.resolved {
-                                    prediction_keys_to_resolve.push(pred_key.clone());
-                                }
+                            if prediction.asset == asset
+                                && prediction.period == period
+                                && prediction.period_start == period_start
+                                && !prediction.resolved
+                            {
+                                prediction_keys_to_resolve.push(pred_key.clone());
                             }
                             Ok(())
                         })
-                        .await
-                        .expect("Failed to iterate predictions");
+                        .await?;
 
                     // Resolve each prediction for this period
                     for pred_key in prediction_keys_to_resolve {
@@ -2091,17 +5795,17 @@ impl PredictionMarketContract {
                             .await?
                             .map(|p| p.clone())
                         {
-                            if !prediction.resolved {
-                                // Try to resolve this prediction
-                                // This will emit PredictionResolved event if resolution succeeds
-                                if let Ok(()) = self
-                                    .resolve_prediction(&mut prediction, period, period_start)
-                                    .await
-                                {
-                                    // Prediction successfully resolved and PredictionResolved event emitted
-                                    // The event is emitted inside resolve_prediction
-                                }
-                                // Note: If resolution fails (e.g., prices not ready), it will be resolved on-demand later
+                            // A predictor without an account here can't be paid and is skipped
+                            // before any write; any other error fails the whole price update
+                            if !prediction.resolved
+                                && self
+                                    .state
+                                    .players
+                                    .contains_key(&prediction.player_id)
+                                    .await?
+                            {
+                                self.resolve_prediction(&mut prediction, period, period_start)
+                                    .await?;
                             }
                         }
                     }
@@ -2113,23 +5817,31 @@ impl PredictionMarketContract {
     }
 
     /// Award rewards to a player for a correct prediction
-    /// Player gets points based on period: Daily: 100, Weekly: 500, Monthly: 1000
+    /// Player gets the stake back plus the period's `reward_percent` of it, and the reward is returned
     async fn award_prediction_reward(
         &mut self,
         player_id: &PlayerId,
         period: PredictionPeriod,
-    ) -> Result<(), ContractError> {
+        stake: Amount,
+    ) -> Result<Amount, ContractError> {
         let mut player = self.get_player(player_id).await?;
 
-        // Award points based on period
-        let reward = match period {
-            PredictionPeriod::Daily => Amount::from_tokens(100), // Daily: 100 points
-            PredictionPeriod::Weekly => Amount::from_tokens(500), // Weekly: 500 points
-            PredictionPeriod::Monthly => Amount::from_tokens(1000), // Monthly: 1000 points
-        };
+        // Release the escrowed stake
+        self.ledger_credit(
+            &mut player,
+            stake,
+            LedgerAccount::PREDICTIONS,
+            LedgerReason::PredictionStake,
+        )
+        .await?;
+
+        // Reward scales with the stake
+        let reward_percent = self.state.config.get().stake_limits(period).reward_percent;
+        let reward = Amount::from_attos(mul_div(stake.into(), reward_percent.into(), 100));
 
         // Award tokens
-        player.token_balance = player.token_balance.saturating_add(reward);
+        self.credit_reward(&mut player, reward, LedgerReason::PredictionReward)
+            .await?;
         player.total_earned = player.total_earned.saturating_add(reward);
         player.total_profit = player.total_profit.saturating_add(reward);
 
@@ -2151,12 +5863,6 @@ impl PredictionMarketContract {
         self.add_experience(&mut player, xp_reward).await?;
         self.state.players.insert(player_id, player.clone())?;
 
-        // Update total supply
-        let current_supply = self.state.total_supply.get();
-        self.state
-            .total_supply
-            .set(current_supply.saturating_add(reward));
-
         // Update leaderboard after awarding points
         self.update_enhanced_leaderboard().await;
 
@@ -2169,35 +5875,27 @@ impl PredictionMarketContract {
         )
         .await;
 
-        Ok(())
+        Ok(reward)
     }
 
     /// Penalize a player for a wrong prediction
-    /// Player loses points based on period: Daily: 100, Weekly: 500, Monthly: 1000
+    /// Player forfeits the escrowed stake, so the loss never exceeds what was staked
     async fn penalize_prediction_loss(
         &mut self,
         player_id: &PlayerId,
-        period: PredictionPeriod,
+        stake: Amount,
     ) -> Result<(), ContractError> {
         let mut player = self.get_player(player_id).await?;
 
-        // Deduct points based on period
-        let penalty = match period {
-            PredictionPeriod::Daily => Amount::from_tokens(100), // Daily: 100 points
-            PredictionPeriod::Weekly => Amount::from_tokens(500), // Weekly: 500 points
-            PredictionPeriod::Monthly => Amount::from_tokens(1000), // Monthly: 1000 points
-        };
-
-        // Deduct tokens (ensure balance doesn't go negative)
-        if player.token_balance >= penalty {
-            player.token_balance = player.token_balance.saturating_sub(penalty);
-            player.total_spent = player.total_spent.saturating_add(penalty);
-        } else {
-            // If player doesn't have enough, set balance to zero
-            let deducted = player.token_balance;
-            player.token_balance = Amount::ZERO;
-            player.total_spent = player.total_spent.saturating_add(deducted);
-        }
+        // Move the escrowed stake to the treasury
+        self.post_ledger_entry(
+            LedgerAccount::PREDICTIONS,
+            LedgerAccount::TREASURY,
+            stake,
+            LedgerReason::PredictionPenalty,
+        )
+        .await?;
+        player.total_spent = player.total_spent.saturating_add(stake);
 
         // Reset win streak on loss
         player.win_streak = 0;
@@ -2206,40 +5904,32 @@ impl PredictionMarketContract {
 
         self.state.players.insert(player_id, player)?;
 
-        // Update total supply (burn tokens)
-        let current_supply = self.state.total_supply.get();
-        let to_burn = penalty.min(*current_supply);
-        self.state
-            .total_supply
-            .set(current_supply.saturating_sub(to_burn));
+        // The stake goes to the treasury
+        self.deposit_to_treasury(TreasurySource::PredictionPenalty, stake)
+            .await?;
 
         Ok(())
     }
 
     /// Award rewards to all guild members when a guild member makes a correct prediction
-    /// Every player in the guild gets points based on period: Daily: 100, Weekly: 500, Monthly: 1000
+    /// Every player in the guild gets the reward the predicting member earned on their stake
     async fn award_guild_prediction_reward(
         &mut self,
         player_id: &PlayerId,
         period: PredictionPeriod,
+        reward: Amount,
     ) -> Result<(), ContractError> {
         let player = self.get_player(player_id).await?;
 
         // Check if player is in a guild
         if let Some(guild_id) = player.guild_id {
             if let Some(guild) = self.state.guilds.get(&guild_id).await?.map(|g| g.clone()) {
-                // Award points based on period
-                let reward = match period {
-                    PredictionPeriod::Daily => Amount::from_tokens(100), // Daily: 100 points
-                    PredictionPeriod::Weekly => Amount::from_tokens(500), // Weekly: 500 points
-                    PredictionPeriod::Monthly => Amount::from_tokens(1000), // Monthly: 1000 points
-                };
-
                 // Award points to each guild member
                 for member_id in &guild.members {
                     let mut member = self.get_player(member_id).await?;
 
-                    member.token_balance = member.token_balance.saturating_add(reward);
+                    self.credit_reward(&mut member, reward, LedgerReason::PredictionReward)
+                        .await?;
                     member.total_earned = member.total_earned.saturating_add(reward);
 
                     // Award XP to guild members
@@ -2252,16 +5942,6 @@ impl PredictionMarketContract {
                     self.state.players.insert(member_id, member)?;
                 }
 
-                // Update total supply
-                let member_count = guild.members.len() as u128;
-                let reward_tokens: u128 = reward.into();
-                let total_reward_tokens = reward_tokens.saturating_mul(member_count);
-                let total_reward = Amount::from_tokens(total_reward_tokens);
-                let current_supply = self.state.total_supply.get();
-                self.state
-                    .total_supply
-                    .set(current_supply.saturating_add(total_reward));
-
                 // Update leaderboard after awarding guild points
                 self.update_enhanced_leaderboard().await;
             }
@@ -2270,56 +5950,139 @@ impl PredictionMarketContract {
         Ok(())
     }
 
-    /// Penalize all guild members when a guild member makes a wrong prediction
-    /// Every player in the guild loses points based on period: Daily: 100, Weekly: 500, Monthly: 1000
+    /// Penalize the other guild members when a guild member makes a wrong prediction
+    /// Each loses the predicting member's stake, capped at the period's minimum stake so that
+    /// nobody is charged more because someone else chose to stake more;
+    /// the predicting member already forfeited their stake
     async fn penalize_guild_prediction_loss(
         &mut self,
         player_id: &PlayerId,
         period: PredictionPeriod,
+        stake: Amount,
     ) -> Result<(), ContractError> {
         let player = self.get_player(player_id).await?;
+        let min_stake = self.state.config.get().stake_limits(period).min_stake;
+        let penalty = stake.min(min_stake);
 
         // Check if player is in a guild
         if let Some(guild_id) = player.guild_id {
             if let Some(guild) = self.state.guilds.get(&guild_id).await?.map(|g| g.clone()) {
-                // Deduct points based on period
-                let penalty = match period {
-                    PredictionPeriod::Daily => Amount::from_tokens(100), // Daily: 100 points
-                    PredictionPeriod::Weekly => Amount::from_tokens(500), // Weekly: 500 points
-                    PredictionPeriod::Monthly => Amount::from_tokens(1000), // Monthly: 1000 points
-                };
-
-                // Deduct points from each guild member
-                for member_id in &guild.members {
+                // Deduct points from each other guild member
+                let mut collected = Amount::ZERO;
+                for member_id in guild.members.iter().filter(|member| *member != player_id) {
                     let mut member = self.get_player(member_id).await?;
 
                     // Deduct tokens (ensure balance doesn't go negative)
-                    if member.token_balance >= penalty {
-                        member.token_balance = member.token_balance.saturating_sub(penalty);
-                        member.total_spent = member.total_spent.saturating_add(penalty);
-                    } else {
-                        // If member doesn't have enough, set balance to zero
-                        let deducted = member.token_balance;
-                        member.token_balance = Amount::ZERO;
-                        member.total_spent = member.total_spent.saturating_add(deducted);
-                    }
+                    let deducted = penalty.min(member.token_balance);
+                    self.ledger_debit(
+                        &mut member,
+                        deducted,
+                        LedgerAccount::TREASURY,
+                        LedgerReason::PredictionPenalty,
+                    )
+                    .await?;
+                    member.total_spent = member.total_spent.saturating_add(deducted);
+                    member.debt = member.debt.saturating_add(penalty.saturating_sub(deducted));
+                    collected = collected.saturating_add(deducted);
 
                     self.state.players.insert(member_id, member)?;
                 }
 
-                // Update total supply (burn tokens)
-                let member_count = guild.members.len() as u128;
-                let penalty_tokens: u128 = penalty.into();
-                let total_penalty_tokens = penalty_tokens.saturating_mul(member_count);
-                let total_penalty = Amount::from_tokens(total_penalty_tokens);
-                let current_supply = self.state.total_supply.get();
-                let to_burn = total_penalty.min(*current_supply);
-                self.state
-                    .total_supply
-                    .set(current_supply.saturating_sub(to_burn));
+                // The penalties go to the treasury
+                self.deposit_to_treasury(TreasurySource::PredictionPenalty, collected)
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settle a prediction from its parimutuel pool
+    /// Winners receive their share of the pool after the rake; if nobody picked the actual
+    /// outcome every stake is refunded. Returns what was paid out of the pool
+    async fn settle_pool_prediction(
+        &mut self,
+        prediction: &PlayerPrediction,
+        pool: &PredictionPool,
+        actual_outcome: PriceOutcome,
+    ) -> Result<Amount, ContractError> {
+        let player_id = prediction.player_id;
+        let mut player = self.get_player(&player_id).await?;
+        let payout = pool.payout(prediction.outcome, prediction.stake, actual_outcome);
+
+        self.ledger_credit(
+            &mut player,
+            payout,
+            LedgerAccount::PREDICTIONS,
+            LedgerReason::PredictionPayout,
+        )
+        .await?;
+        self.repay_debt(&mut player, payout).await?;
+
+        if prediction.outcome == actual_outcome {
+            let winnings = payout.saturating_sub(prediction.stake);
+            player.total_earned = player.total_earned.saturating_add(winnings);
+            player.total_profit = player.total_profit.saturating_add(winnings);
+
+            // Update prediction win statistics
+            player.markets_won += 1;
+            player.win_streak += 1;
+            if player.win_streak > player.best_win_streak {
+                player.best_win_streak = player.win_streak;
             }
+            player.reputation = player.reputation.saturating_add(10);
+
+            let xp_reward = match prediction.period {
+                PredictionPeriod::Daily => 50,
+                PredictionPeriod::Weekly => 250,
+                PredictionPeriod::Monthly => 500,
+            };
+            self.add_experience(&mut player, xp_reward).await?;
+        } else {
+            player.total_spent = player
+                .total_spent
+                .saturating_add(prediction.stake.saturating_sub(payout));
+            player.win_streak = 0;
+            player.reputation = player.reputation.saturating_sub(5);
         }
+        self.state.players.insert(&player_id, player.clone())?;
+
+        self.update_enhanced_leaderboard().await;
+        self.broadcast_global_player_updated(
+            player_id,
+            player.total_earned,
+            player.total_profit,
+            player.level,
+        )
+        .await;
 
+        Ok(payout)
+    }
+
+    /// Record what a resolved prediction took out of its pool
+    /// Once every prediction is settled, whatever is left (the rake and rounding) goes to the treasury
+    async fn record_pool_settlement(
+        &mut self,
+        pool_key: &str,
+        mut pool: PredictionPool,
+        paid: Amount,
+    ) -> Result<(), ContractError> {
+        pool.paid_out = pool.paid_out.saturating_add(paid);
+        pool.settled += 1;
+        if pool.settled >= pool.predictions {
+            let rake = pool.held();
+            self.post_ledger_entry(
+                LedgerAccount::PREDICTIONS,
+                LedgerAccount::TREASURY,
+                rake,
+                LedgerReason::PredictionRake,
+            )
+            .await?;
+            self.deposit_to_treasury(TreasurySource::PredictionRake, rake)
+                .await?;
+            pool.paid_out = pool.total();
+        }
+        self.state.prediction_pools.insert(pool_key, pool)?;
         Ok(())
     }
 
@@ -2378,6 +6141,13 @@ impl PredictionMarketContract {
         {
             // Register locally
             let _ = self.state.subscribed_chains.insert(&chain_id, current_time);
+            // The creator chain hears of every chain this way, so the broadcasts
+            // made there (like the admin's price updates) reach all of them
+            let creator_chain_id = self.runtime.application_creator_chain_id();
+            let _ = self
+                .state
+                .subscribed_chains
+                .insert(&creator_chain_id, current_time);
 
             // Broadcast registration to all other chains
             // This allows other chains to know about this chain
@@ -2410,11 +6180,7 @@ impl PredictionMarketContract {
         // Send message to each subscribed chain
         // Use with_authentication() and with_tracking() for reliable delivery
         for target_chain_id in chains_to_notify {
-            self.runtime
-                .prepare_message(message.clone())
-                .with_authentication()
-                .with_tracking()
-                .send_to(target_chain_id);
+            self.send_tracked(message.clone(), target_chain_id);
         }
     }
 
@@ -2482,7 +6248,10 @@ impl PredictionMarketContract {
             status: MarketStatus::Active,
             created_at: current_time,
         };
-        let _ = self.state.global_markets.insert(&market_id, global_market);
+        let _ = self
+            .state
+            .global_markets
+            .insert(&(chain_id, market_id), global_market);
 
         // Broadcast to all subscribed chains (proper cross-chain messaging)
         self.broadcast_to_all_chains(Message::GlobalMarketCreated {
@@ -2495,6 +6264,40 @@ impl PredictionMarketContract {
         .await;
     }
 
+    /// Broadcast a market status change to all chains for horizontal scaling
+    async fn broadcast_global_market_status(&mut self, market_id: MarketId, status: MarketStatus) {
+        let chain_id = self.runtime.chain_id();
+
+        // Generate unique message ID for deduplication
+        let content = format!("{}:{:?}", market_id, status);
+        let message_id = self.generate_message_id("GlobalMarketStatusUpdated", &content);
+
+        // Update local global registry
+        if let Some(mut global_market) = self
+            .state
+            .global_markets
+            .get(&(chain_id, market_id))
+            .await
+            .ok()
+            .flatten()
+        {
+            global_market.status = status;
+            let _ = self
+                .state
+                .global_markets
+                .insert(&(chain_id, market_id), global_market);
+        }
+
+        // Broadcast to all subscribed chains (proper cross-chain messaging)
+        self.broadcast_to_all_chains(Message::GlobalMarketStatusUpdated {
+            market_id,
+            status,
+            chain_id,
+            message_id,
+        })
+        .await;
+    }
+
     /// Broadcast guild creation to all chains for horizontal scaling
     async fn broadcast_global_guild_created(
         &mut self,
@@ -2580,16 +6383,22 @@ impl PredictionMarketContract {
         .await;
     }
 
-    /// Broadcast price update to all chains for horizontal scaling
-    async fn broadcast_global_price_update(&mut self, price: Amount, timestamp: Timestamp) {
+    /// Broadcast an asset's price update to all chains for horizontal scaling
+    async fn broadcast_global_price_update(
+        &mut self,
+        asset: AssetSymbol,
+        price: Amount,
+        timestamp: Timestamp,
+    ) {
         let chain_id = self.runtime.chain_id();
 
         // Generate unique message ID for deduplication
-        let content = format!("{}:{}", price, timestamp.micros());
+        let content = format!("{}:{}:{}", asset, price, timestamp.micros());
         let message_id = self.generate_message_id("GlobalPriceUpdate", &content);
 
         // Broadcast to all subscribed chains (proper cross-chain messaging)
         self.broadcast_to_all_chains(Message::GlobalPriceUpdate {
+            asset,
             price,
             timestamp,
             chain_id,
@@ -1,24 +1,32 @@
 use async_graphql::{Request, Response};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{Amount, ContractAbi, ServiceAbi},
+    linera_base_types::{Amount, ChainId, ContractAbi, ServiceAbi, Timestamp},
 };
 use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
+pub mod pricing;
 pub mod state;
 
 // Re-export types for convenience
 pub use state::{
-    AchievementId, GameConfig, GlobalGuildInfo, GlobalMarketInfo, GlobalPlayerInfo, Guild, GuildId,
-    Leaderboard, Market, MarketId, MarketStatus, OutcomeId, Player, PlayerId, PlayerPrediction,
-    PredictionPeriod, PriceOutcome,
+    AchievementId, AirdropCredit, AssetSymbol, Candle, CandleResolution, CandleSource, GameConfig,
+    GlobalGuildInfo, GlobalMarketInfo, GlobalPlayerInfo, Guild, GuildId, Leaderboard,
+    LedgerAccount, LedgerAccountKind, LedgerBalance, LedgerCheck, LedgerEntry, LedgerEntryId,
+    LedgerPage, LedgerReason, LiquidityPosition, Market, MarketId, MarketOutcome, MarketStatus,
+    MarketType, OracleCondition, OrderId, OrderSide, OutcomeId, OutcomePrice, PendingResolution,
+    Player, PlayerId, PlayerPrediction, PoolOdds, PredictionPeriod, PredictionPool, PriceCondition,
+    PriceOutcome, RemoteTrade, RemoteTradeId, RemoteTradeStatus, StakeLimits, SupplyComponent,
+    SupplyComponents, SupplyReport, Trade, TradeId, TradePage, TreasuryFlow, TreasuryReport,
+    TreasurySource,
 };
 
 pub struct PredictiveManagerAbi;
 
 impl ContractAbi for PredictiveManagerAbi {
     type Operation = Operation;
-    type Response = ();
+    type Response = OperationResponse;
 }
 
 impl ServiceAbi for PredictiveManagerAbi {
@@ -36,25 +44,103 @@ pub enum Operation {
         display_name: Option<String>,
     },
     ClaimDailyReward,
+    TransferPoints {
+        to: PlayerId, // Recipient, on this chain or another one
+        amount: Amount,
+        memo: Option<String>, // Note for the recipient, at most 280 characters
+    },
 
     // Market operations
     CreateMarket {
         title: String,
         amount: Amount,
-        fee_percent: u8, // Fee percentage seller wants to charge (0-100)
+        fee_percent: u8,       // Fee percentage seller wants to charge (0-100)
+        outcomes: Vec<String>, // Outcome names of a prediction market, empty for a point market
+        market_type: MarketType,
+        close_time: Option<Timestamp>, // Trading stops at this time
+        resolution_time: Option<Timestamp>, // Earliest resolution of a prediction market
+        oracle: Option<PriceCondition>, // Resolve a Yes/No prediction market from the price feed
     },
     BuyShares {
-        market_id: MarketId, // Market to buy points from
+        market_id: MarketId,           // Market to buy points from
+        chain_id: Option<ChainId>,     // Chain hosting the market, none for a market on this chain
+        outcome_id: Option<OutcomeId>, // Outcome to buy shares of (prediction markets only)
         amount: Amount,
+        max_payment: Option<Amount>, // Fail if the trade would cost more, fee included (required on a remote market)
+        min_received: Option<Amount>, // Fail if fewer points (or shares) would be received
+        deadline: Option<Timestamp>, // Fail if the trade executes after this time
     },
     SellShares {
-        market_id: MarketId, // Market to sell points to
+        market_id: MarketId,           // Market to sell points to
+        chain_id: Option<ChainId>,     // Chain hosting the market, none for a market on this chain
+        outcome_id: Option<OutcomeId>, // Outcome to sell shares of (prediction markets only)
+        amount: Amount,
+        max_payment: Option<Amount>, // Fail if more points (or shares) would be given up
+        min_received: Option<Amount>, // Fail if fewer points would be received, net of the fee
+        deadline: Option<Timestamp>, // Fail if the trade executes after this time
+    },
+    CloseMarket {
+        market_id: MarketId, // Stop trading (creator or admin)
+    },
+    CancelMarket {
+        market_id: MarketId, // Refund positions and return liquidity (creator or admin)
+    },
+    ResolveMarket {
+        market_id: MarketId, // Settle the market (admin, or propose as the prediction market's creator)
+        winning_outcome: Option<OutcomeId>, // Required for prediction markets
+    },
+    ChallengeResolution {
+        market_id: MarketId, // Dispute a proposed resolution by posting the challenge bond
+        outcome_id: OutcomeId, // Outcome the challenger says should win
+    },
+    VoteOnDispute {
+        market_id: MarketId,
+        uphold: bool, // Back the proposed outcome, or the challenger's
+    },
+    SettleDispute {
+        market_id: MarketId, // Decide a challenged resolution (admin only)
+        uphold: bool,
+    },
+    AddLiquidity {
+        market_id: MarketId, // Deposit points into the market's pool for LP shares
         amount: Amount,
     },
+    WithdrawLiquidity {
+        market_id: MarketId, // Redeem LP shares for their part of the pool
+        shares: Amount,
+    },
+
+    // Order book operations (prediction markets)
+    PlaceLimitOrder {
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        price: Amount, // Points per share, at most 1
+        quantity: Amount,
+    },
+    PlaceMarketOrder {
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        side: OrderSide,
+        quantity: Amount, // Fills at the best resting prices, the rest is dropped
+    },
+    CancelOrder {
+        market_id: MarketId,
+        order_id: OrderId,
+    },
 
     // Point minting (Admin only)
     MintPoints {
+        recipient: PlayerId, // Player credited with the new points
+        amount: Amount,
+    },
+    Airdrop {
+        credits: Vec<AirdropCredit>, // At most `max_airdrop_recipients` entries
+    },
+    SpendTreasury {
+        recipient: PlayerId, // Player paid out of the platform treasury (admin only)
         amount: Amount,
+        memo: Option<String>,
     },
 
     // Guild operations
@@ -73,20 +159,119 @@ pub enum Operation {
     UpdateGameConfig {
         config: GameConfig,
     },
+    ReconcileSupply, // Reset the tracked supply to a recount of every balance
 
     // Price prediction operations
     PredictDailyOutcome {
+        asset: AssetSymbol, // One of the assets listed in `GameConfig`
         outcome: PriceOutcome,
+        stake: Amount, // Escrowed until the period resolves, within the period's `StakeLimits`
     },
     PredictWeeklyOutcome {
+        asset: AssetSymbol, // One of the assets listed in `GameConfig`
         outcome: PriceOutcome,
+        stake: Amount, // Escrowed until the period resolves, within the period's `StakeLimits`
     },
     PredictMonthlyOutcome {
+        asset: AssetSymbol, // One of the assets listed in `GameConfig`
         outcome: PriceOutcome,
+        stake: Amount, // Escrowed until the period resolves, within the period's `StakeLimits`
     },
 
     // Admin operations for price updates
     UpdateMarketPrice {
+        asset: AssetSymbol,
         price: Amount,
     },
 }
+
+/// Outcome of a single operation, returned as the operation's response in the block
+pub type OperationResponse = Result<OperationReceipt, OperationError>;
+
+/// Details of a successful operation
+/// Ids are only set when the operation touched that entity; balances are read after execution
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct OperationReceipt {
+    pub market_id: Option<MarketId>,
+    pub guild_id: Option<GuildId>,
+    pub period_start: Option<Timestamp>, // Period a prediction was recorded for
+    pub token_balance: Option<Amount>,   // Signer's balance after the operation
+    pub market_liquidity: Option<Amount>,
+    pub guild_pool: Option<Amount>,
+    pub fees_charged: Amount,
+    pub order_id: Option<OrderId>,
+    pub filled_quantity: Option<Amount>, // Shares filled by an order
+}
+
+/// Serializable mirror of the contract's `ContractError`, one variant per error case
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, Error)]
+pub enum OperationError {
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("player already exists")]
+    PlayerAlreadyExists,
+    #[error("daily reward already claimed")]
+    DailyRewardAlreadyClaimed,
+    #[error("insufficient balance")]
+    InsufficientBalance,
+    #[error("market not active")]
+    MarketNotActive,
+    #[error("invalid outcome")]
+    InvalidOutcome,
+    #[error("player not found")]
+    PlayerNotFound,
+    #[error("market not found")]
+    MarketNotFound,
+    #[error("guild not found")]
+    GuildNotFound,
+    #[error("already in guild")]
+    AlreadyInGuild,
+    #[error("not a guild member")]
+    NotGuildMember,
+    #[error("not admin")]
+    NotAdmin,
+    #[error("not resolved")]
+    NotResolved,
+    #[error("insufficient level")]
+    InsufficientLevel,
+    #[error("invalid price")]
+    InvalidPrice,
+    #[error("order not found")]
+    OrderNotFound,
+    #[error("no matching orders")]
+    NoMatchingOrders,
+    #[error("order would cross your own resting order")]
+    SelfCrossingOrder,
+    #[error("invalid deadline")]
+    InvalidDeadline,
+    #[error("resolution time not reached")]
+    ResolutionTooEarly,
+    #[error("slippage limit exceeded")]
+    SlippageExceeded,
+    #[error("trade deadline passed")]
+    DeadlineExpired,
+    #[error("market is resolved by its price oracle")]
+    OracleMarket,
+    #[error("market has no resolution open to challenge")]
+    NotDisputable,
+    #[error("market is not disputed")]
+    NotDisputed,
+    #[error("already voted")]
+    AlreadyVoted,
+    #[error("invalid game config")]
+    InvalidConfig,
+    #[error("remote buys need a max payment to escrow")]
+    MaxPaymentRequired,
+    #[error("invalid transfer")]
+    InvalidTransfer,
+    #[error("invalid airdrop")]
+    InvalidAirdrop,
+    #[error("player has unpaid debt")]
+    InDebt,
+    #[error("stake outside the period's limits")]
+    InvalidStake,
+    #[error("asset not listed")]
+    UnknownAsset,
+    #[error("storage error: {0}")]
+    View(String),
+}
@@ -0,0 +1,214 @@
+// ============================================================================
+// Fixed-Point Pricing
+// ============================================================================
+//
+// Market prices are computed with integer arithmetic only, so the contract and
+// the service get identical results natively and in Wasm.
+// Fixed-point numbers use 18 decimals, the same scale as `Amount` attos.
+
+use linera_sdk::linera_base_types::Amount;
+
+/// 1.0 in 18-decimal fixed point
+pub const ONE: u128 = 1_000_000_000_000_000_000;
+
+/// ln(2) in 18-decimal fixed point
+const LN_2: u128 = 693_147_180_559_945_309;
+
+/// exp(-x) rounds to zero at 18 decimals for any x above this
+const EXP_ZERO_THRESHOLD: u128 = 42 * ONE;
+
+/// Compute `a * b / c` rounded down, without overflowing the intermediate product
+/// Amounts are stored in attos, so products of two amounts quickly exceed `u128`
+pub fn mul_div(a: u128, b: u128, c: u128) -> u128 {
+    const LOW_MASK: u128 = u64::MAX as u128;
+
+    // 256-bit product as (high, low) halves
+    let (a_high, a_low) = (a >> 64, a & LOW_MASK);
+    let (b_high, b_low) = (b >> 64, b & LOW_MASK);
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let middle = (low_low >> 64) + (high_low & LOW_MASK) + (low_high & LOW_MASK);
+    let low = (low_low & LOW_MASK) | (middle << 64);
+    let high = a_high * b_high + (high_low >> 64) + (low_high >> 64) + (middle >> 64);
+
+    if high == 0 {
+        return low / c;
+    }
+
+    // Binary long division of the 256-bit product; saturates if the quotient doesn't fit
+    let mut remainder: u128 = 0;
+    let mut quotient: u128 = 0;
+    for bit in (0..256).rev() {
+        let next = if bit >= 128 {
+            (high >> (bit - 128)) & 1
+        } else {
+            (low >> bit) & 1
+        };
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | next;
+        if carry == 1 || remainder >= c {
+            remainder = remainder.wrapping_sub(c);
+            if bit >= 128 {
+                return u128::MAX;
+            }
+            quotient |= 1 << bit;
+        }
+    }
+    quotient
+}
+
+/// Compute exp(-x) for a non-negative fixed-point `x`
+/// Uses exp(-x) = 2^-k * exp(-r) with r in [0, ln 2), then a Taylor series for exp(-r)
+fn exp_neg(x: u128) -> u128 {
+    if x >= EXP_ZERO_THRESHOLD {
+        return 0;
+    }
+
+    let k = x / LN_2;
+    let r = (x % LN_2) as i128;
+
+    let one = ONE as i128;
+    let mut term = one;
+    let mut sum = one;
+    let mut n = 1;
+    while term != 0 {
+        term = -term * r / (n * one);
+        sum += term;
+        n += 1;
+    }
+
+    (sum as u128) >> k
+}
+
+/// Compute ln(y) for a fixed-point `y` of at least 1.0
+/// Uses ln(y) = k * ln 2 + ln(m) with y = 2^k * m and m in [1, 2),
+/// then ln(m) = 2 * atanh((m - 1) / (m + 1)) as a power series
+fn ln(y: u128) -> u128 {
+    let mut k = 0;
+    let mut m = y;
+    while m >= 2 * ONE {
+        m >>= 1;
+        k += 1;
+    }
+
+    let z = (m - ONE) * ONE / (m + ONE);
+    let z_squared = z * z / ONE;
+    let mut power = z;
+    let mut series = 0;
+    let mut n = 1;
+    while power != 0 {
+        series += power / n;
+        power = power * z_squared / ONE;
+        n += 2;
+    }
+
+    k * LN_2 + 2 * series
+}
+
+// ============================================================================
+// Logarithmic Market Scoring Rule (LMSR)
+// ============================================================================
+//
+// Prediction markets price outcome shares with Hanson's LMSR. With q_i shares
+// outstanding for outcome i and liquidity parameter b, the cost function is
+//     C(q) = b * ln(sum_i exp(q_i / b))
+// A trade costs the change in C, and the price of outcome i is
+//     p_i = exp(q_i / b) / sum_j exp(q_j / b)
+// which is also the implied probability of that outcome.
+// Each winning share pays out one point, and the market maker can lose at most
+// b * ln(n) for n outcomes.
+
+/// Terms exp((q_i - max q) / b) of the cost function, with their sum
+/// Subtracting the largest quantity keeps every exponent at or below zero
+fn lmsr_terms(quantities: &[Amount], b: Amount) -> (u128, Vec<u128>, u128) {
+    let b: u128 = b.into();
+    let max = quantities
+        .iter()
+        .map(|quantity| u128::from(*quantity))
+        .max()
+        .unwrap_or(0);
+    let terms: Vec<u128> = quantities
+        .iter()
+        .map(|quantity| exp_neg(mul_div(max - u128::from(*quantity), ONE, b)))
+        .collect();
+    let sum = terms.iter().sum();
+    (max, terms, sum)
+}
+
+/// The LMSR cost function C(q) for outstanding `quantities` and liquidity parameter `b`
+pub fn lmsr_cost(quantities: &[Amount], b: Amount) -> Amount {
+    let (max, _, sum) = lmsr_terms(quantities, b);
+    Amount::from_attos(max.saturating_add(mul_div(b.into(), ln(sum), ONE)))
+}
+
+/// Cost of buying `shares` of `outcome`: C(q + shares) - C(q)
+pub fn lmsr_buy_cost(quantities: &[Amount], b: Amount, outcome: usize, shares: Amount) -> Amount {
+    let mut after = quantities.to_vec();
+    after[outcome] = after[outcome].saturating_add(shares);
+    lmsr_cost(&after, b).saturating_sub(lmsr_cost(quantities, b))
+}
+
+/// Proceeds of selling `shares` of `outcome`: C(q) - C(q - shares)
+pub fn lmsr_sell_proceeds(
+    quantities: &[Amount],
+    b: Amount,
+    outcome: usize,
+    shares: Amount,
+) -> Amount {
+    let mut after = quantities.to_vec();
+    after[outcome] = after[outcome].saturating_sub(shares);
+    lmsr_cost(quantities, b).saturating_sub(lmsr_cost(&after, b))
+}
+
+/// Current price of one share of each outcome, in points
+/// A share pays one point if its outcome wins, so prices are implied probabilities
+pub fn lmsr_prices(quantities: &[Amount], b: Amount) -> Vec<Amount> {
+    let (_, terms, sum) = lmsr_terms(quantities, b);
+    terms
+        .into_iter()
+        .map(|term| Amount::from_attos(mul_div(term, ONE, sum)))
+        .collect()
+}
+
+/// The largest liquidity parameter whose worst-case loss b * ln(n) is covered by `subsidy`
+pub fn lmsr_liquidity_parameter(subsidy: Amount, outcome_count: usize) -> Amount {
+    let ln_outcomes = ln(outcome_count as u128 * ONE);
+    Amount::from_attos(mul_div(subsidy.into(), ONE, ln_outcomes))
+}
+
+// ============================================================================
+// Constant-Product Market Maker
+// ============================================================================
+//
+// Constant-product point markets keep x * y = k between their point reserve x
+// and their payment reserve y, so the price y / x of a point rises as the pool
+// is drained and falls as points are sold back into it.
+
+/// Payment needed to take `amount` out of the point reserve: y * amount / (x - amount)
+/// `amount` must be less than the point reserve
+pub fn constant_product_buy_cost(
+    point_reserve: Amount,
+    payment_reserve: Amount,
+    amount: Amount,
+) -> Amount {
+    let remaining = point_reserve.saturating_sub(amount);
+    Amount::from_attos(mul_div(
+        payment_reserve.into(),
+        amount.into(),
+        remaining.into(),
+    ))
+}
+
+/// Payment released by adding `amount` to the point reserve: y * amount / (x + amount)
+pub fn constant_product_sell_proceeds(
+    point_reserve: Amount,
+    payment_reserve: Amount,
+    amount: Amount,
+) -> Amount {
+    let after = point_reserve.saturating_add(amount);
+    if after == Amount::ZERO {
+        return Amount::ZERO;
+    }
+    Amount::from_attos(mul_div(payment_reserve.into(), amount.into(), after.into()))
+}
@@ -1,5 +1,6 @@
 #![cfg_attr(target_arch = "wasm32", no_main)]
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use async_graphql::{EmptySubscription, Object, Schema};
@@ -10,6 +11,7 @@ use linera_sdk::{
     Service, ServiceRuntime,
 };
 
+use predictive_manager::pricing::lmsr_prices;
 use predictive_manager::state::*;
 use predictive_manager::Operation;
 
@@ -90,13 +92,198 @@ impl Service for PredictiveManagerService {
 
 struct QueryRoot;
 
+/// Trades returned per page when no limit is given
+const DEFAULT_TRADE_PAGE: u32 = 50;
+/// Most trades returned in one page
+const MAX_TRADE_PAGE: u32 = 200;
+/// Most candles a single query may span
+const MAX_CANDLES: u64 = 1_440;
+
 #[Object]
 impl QueryRoot {
     /// Get total supply of points
     async fn total_supply(&self, ctx: &async_graphql::Context<'_>) -> Amount {
         let state_wrapper = ctx.data_unchecked::<StateWrapper>();
         let state = unsafe { state_wrapper.state() };
-        *state.total_supply.get()
+        state.supply.get().total()
+    }
+
+    /// Compare the tracked supply, component by component, with a recount of every balance
+    async fn supply_report(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<SupplyReport> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let tracked = state.supply.get().clone();
+        let actual = state
+            .count_supply()
+            .await
+            .map_err(|e| async_graphql::Error::new(format!("Failed to count supply: {:?}", e)))?;
+        Ok(SupplyReport {
+            total_supply: tracked.total(),
+            drifted: tracked.drift_from(&actual),
+            tracked,
+            actual_supply: actual.total(),
+            actual,
+        })
+    }
+
+    /// Get the platform treasury's balance and its inflows and outflows by source
+    async fn treasury(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<TreasuryReport> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let mut flows = Vec::new();
+        state
+            .treasury_flows
+            .for_each_index_value(|_source, flow| {
+                flows.push(flow.into_owned());
+                Ok(())
+            })
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!("Failed to iterate treasury flows: {:?}", e))
+            })?;
+        let total_inflow = flows.iter().fold(Amount::ZERO, |total, flow| {
+            total.saturating_add(flow.inflow)
+        });
+        let total_outflow = flows.iter().fold(Amount::ZERO, |total, flow| {
+            total.saturating_add(flow.outflow)
+        });
+        Ok(TreasuryReport {
+            balance: *state.treasury_balance.get(),
+            total_inflow,
+            total_outflow,
+            flows,
+        })
+    }
+
+    /// Journal entries that moved points into or out of a player's balance, oldest first
+    /// Pass a page's `next_cursor` as `after` to continue
+    async fn ledger(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        player_id: PlayerId,
+        after: Option<u64>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<LedgerPage> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let count = state.player_journal_len.get(&player_id).await?.unwrap_or(0);
+
+        let limit = limit.unwrap_or(DEFAULT_TRADE_PAGE).clamp(1, MAX_TRADE_PAGE) as usize;
+        let mut entries = Vec::new();
+        let mut index = after.map_or(0, |after| after.saturating_add(1));
+        while index < count && entries.len() < limit {
+            let Some(entry_id) = state.player_journal.get(&(player_id, index)).await? else {
+                break;
+            };
+            index += 1;
+            if let Some(entry) = state.journal.get(&entry_id).await? {
+                entries.push(entry);
+            }
+        }
+
+        let next_cursor = if index < count {
+            index.checked_sub(1)
+        } else {
+            None
+        };
+        Ok(LedgerPage {
+            entries,
+            next_cursor,
+        })
+    }
+
+    /// Running debit and credit totals of every ledger account
+    async fn ledger_balances(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<Vec<LedgerBalance>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let mut balances = Vec::new();
+        state
+            .ledger_balances
+            .for_each_index_value(|_account, balance| {
+                balances.push(balance.into_owned());
+                Ok(())
+            })
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!("Failed to iterate ledger balances: {:?}", e))
+            })?;
+        Ok(balances)
+    }
+
+    /// Check the ledger's invariants: total debits equal total credits,
+    /// and every account's credits minus debits equal its token balance, hosted accounts included
+    async fn ledger_check(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<LedgerCheck> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let balances = self.ledger_balances(ctx).await?;
+        let total_debits = balances.iter().fold(Amount::ZERO, |total, balance| {
+            total.saturating_add(balance.debits)
+        });
+        let total_credits = balances.iter().fold(Amount::ZERO, |total, balance| {
+            total.saturating_add(balance.credits)
+        });
+
+        let mut journaled = BTreeMap::new();
+        for balance in &balances {
+            if let Some(player_id) = balance.account.player_id {
+                journaled.insert(player_id, balance.clone());
+            }
+        }
+        let mut mismatched_players = Vec::new();
+        for accounts in [&state.players, &state.hosted_accounts] {
+            accounts
+                .for_each_index_value(|player_id, player| {
+                    let matches = match journaled.remove(&player_id) {
+                        Some(balance) => {
+                            balance.debits <= balance.credits
+                                && balance.credits.saturating_sub(balance.debits)
+                                    == player.token_balance
+                        }
+                        None => player.token_balance == Amount::ZERO,
+                    };
+                    if !matches {
+                        mismatched_players.push(player_id);
+                    }
+                    Ok(())
+                })
+                .await
+                .map_err(|e| {
+                    async_graphql::Error::new(format!("Failed to iterate players: {:?}", e))
+                })?;
+        }
+        // Journaled players without an account here still hold points on paper
+        mismatched_players.extend(
+            journaled
+                .into_iter()
+                .filter(|(_, balance)| balance.credits != balance.debits)
+                .map(|(player_id, _)| player_id),
+        );
+
+        Ok(LedgerCheck {
+            balanced: total_debits == total_credits && mismatched_players.is_empty(),
+            total_debits,
+            total_credits,
+            mismatched_players,
+        })
+    }
+
+    /// Get the current game configuration
+    async fn config(&self, ctx: &async_graphql::Context<'_>) -> GameConfig {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        state.config.get().clone()
     }
 
     /// Get total points earned by a player (mirrors contract's get_player_total_points)
@@ -203,6 +390,41 @@ impl QueryRoot {
             .ok_or_else(|| async_graphql::Error::new("Player not found"))
     }
 
+    /// Get the account this chain hosts for a trader from another chain, if any
+    async fn hosted_account(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        player_id: PlayerId,
+    ) -> async_graphql::Result<Option<Player>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        Ok(state.hosted_accounts.get(&player_id).await?)
+    }
+
+    /// Players who owe unpaid penalties, largest debt first
+    async fn debtors(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<Vec<Player>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let mut debtors = Vec::new();
+        state
+            .players
+            .for_each_index_value(|_player_id, player| {
+                if player.debt > Amount::ZERO {
+                    debtors.push(player.into_owned());
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!("Failed to iterate players: {:?}", e))
+            })?;
+        debtors.sort_by(|a, b| b.debt.cmp(&a.debt));
+        Ok(debtors)
+    }
+
     /// Get a market by its ID (mirrors contract's get_market)
     async fn market(
         &self,
@@ -219,12 +441,231 @@ impl QueryRoot {
             .ok_or_else(|| async_graphql::Error::new("Market not found"))
     }
 
+    /// Get the current LMSR price of each outcome of a prediction market
+    /// Prices are in points per share and double as the implied probability of each outcome
+    async fn outcome_prices(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        market_id: MarketId,
+    ) -> async_graphql::Result<Vec<OutcomePrice>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let market = state
+            .markets
+            .get(&market_id)
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Market not found"))?;
+        if market.market_type != MarketType::Prediction {
+            return Err(async_graphql::Error::new(
+                "Market is not a prediction market",
+            ));
+        }
+
+        let quantities: Vec<Amount> = market
+            .outcomes
+            .iter()
+            .map(|outcome| outcome.total_shares)
+            .collect();
+        let prices = lmsr_prices(&quantities, market.liquidity_parameter);
+        Ok(market
+            .outcomes
+            .into_iter()
+            .zip(prices)
+            .map(|(outcome, price)| OutcomePrice {
+                outcome_id: outcome.id,
+                name: outcome.name,
+                price,
+            })
+            .collect())
+    }
+
+    /// Get the open orders for one outcome of a market, with depth aggregated by price level
+    /// Bids are listed highest price first and asks lowest price first, oldest first within a price
+    async fn order_book(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+    ) -> async_graphql::Result<OrderBookDepth> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let book = state.order_books.get(&market_id).await?.unwrap_or_default();
+
+        let bids: Vec<Order> = book
+            .bids
+            .into_iter()
+            .filter(|order| order.outcome_id == outcome_id)
+            .collect();
+        let asks: Vec<Order> = book
+            .asks
+            .into_iter()
+            .filter(|order| order.outcome_id == outcome_id)
+            .collect();
+        Ok(OrderBookDepth {
+            market_id,
+            outcome_id,
+            bid_levels: price_levels(&bids),
+            ask_levels: price_levels(&asks),
+            bids,
+            asks,
+        })
+    }
+
+    /// Get a page of a market's trade log, oldest first
+    /// Pass the previous page's `nextCursor` as `after` to continue; `playerId` matches either side of a trade
+    #[allow(clippy::too_many_arguments)]
+    async fn trades(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        market_id: MarketId,
+        player_id: Option<PlayerId>,
+        from: Option<Timestamp>,
+        to: Option<Timestamp>,
+        after: Option<TradeId>,
+        limit: Option<u32>,
+    ) -> async_graphql::Result<TradePage> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let market = state
+            .markets
+            .get(&market_id)
+            .await?
+            .ok_or_else(|| async_graphql::Error::new("Market not found"))?;
+
+        let limit = limit.unwrap_or(DEFAULT_TRADE_PAGE).clamp(1, MAX_TRADE_PAGE) as usize;
+        let mut trades = Vec::new();
+        let mut id = after.map_or(0, |after| after.saturating_add(1));
+        while id < market.trade_count && trades.len() < limit {
+            let Some(trade) = state.trades.get(&(market_id, id)).await? else {
+                break;
+            };
+            id += 1;
+
+            // The log is in time order, so nothing after `to` can match
+            if to.is_some_and(|to| trade.timestamp > to) {
+                id = market.trade_count;
+                break;
+            }
+            let in_range = from.is_none_or(|from| trade.timestamp >= from);
+            let involves_player = player_id.is_none_or(|player_id| {
+                trade.player_id == player_id || trade.counterparty == Some(player_id)
+            });
+            if in_range && involves_player {
+                trades.push(trade);
+            }
+        }
+
+        let next_cursor = if id < market.trade_count {
+            id.checked_sub(1)
+        } else {
+            None
+        };
+        Ok(TradePage {
+            trades,
+            next_cursor,
+        })
+    }
+
+    /// Get the OHLCV candles of a market outcome, or of an asset's price feed when `marketId` is null
+    /// Candles are listed oldest first; intervals without trades have no candle
+    #[allow(clippy::too_many_arguments)]
+    async fn candles(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        market_id: Option<MarketId>,
+        outcome_id: Option<OutcomeId>,
+        asset: Option<AssetSymbol>,
+        resolution: CandleResolution,
+        from: Timestamp,
+        to: Timestamp,
+    ) -> async_graphql::Result<Vec<Candle>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let source = match (market_id, asset) {
+            (Some(market_id), _) => CandleSource::Market(market_id),
+            (None, Some(asset)) => CandleSource::PriceFeed(asset),
+            (None, None) => {
+                return Err(async_graphql::Error::new(
+                    "Either marketId or asset is required",
+                ))
+            }
+        };
+        let outcome_id = outcome_id.unwrap_or_default();
+        let step = resolution.micros();
+        let first = resolution.candle_start(from).micros();
+        let last = resolution.candle_start(to).micros();
+        if first > last || (last - first) / step >= MAX_CANDLES {
+            return Err(async_graphql::Error::new(format!(
+                "Range must span between 1 and {MAX_CANDLES} candles"
+            )));
+        }
+
+        let mut candles = Vec::new();
+        for start in (first..=last).step_by(step as usize) {
+            let key = (source.clone(), outcome_id, resolution, start);
+            if let Some(candle) = state.candles.get(&key).await? {
+                candles.push(candle);
+            }
+        }
+        Ok(candles)
+    }
+
+    /// Get a player's LP positions across all markets
+    async fn liquidity_positions(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        player_id: PlayerId,
+    ) -> async_graphql::Result<Vec<LiquidityPosition>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let mut positions = Vec::new();
+        state
+            .markets
+            .for_each_index_value(|_key, market| {
+                if let Some(position) = market.liquidity_positions.get(&player_id) {
+                    positions.push(position.clone());
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!("Failed to iterate markets: {:?}", e))
+            })?;
+        Ok(positions)
+    }
+
+    /// Get a player's trades on markets hosted on other chains, oldest first
+    /// Pending trades still hold their escrow; settled ones show the refund and fee
+    async fn remote_trades(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        player_id: PlayerId,
+    ) -> async_graphql::Result<Vec<RemoteTrade>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let mut trades = Vec::new();
+        state
+            .remote_trades
+            .for_each_index_value(|_trade_id, trade| {
+                if trade.player_id == player_id {
+                    trades.push(trade.into_owned());
+                }
+                Ok(())
+            })
+            .await
+            .map_err(|e| {
+                async_graphql::Error::new(format!("Failed to iterate remote trades: {:?}", e))
+            })?;
+        Ok(trades)
+    }
+
     /// Get the result of a player's daily prediction (mirrors contract's get_daily_outcome)
-    /// Returns whether the player's prediction was correct
+    /// Returns whether the player's prediction on the asset for the latest resolved period was correct
     async fn get_daily_outcome(
         &self,
         ctx: &async_graphql::Context<'_>,
         player_id: PlayerId,
+        asset: AssetSymbol,
     ) -> async_graphql::Result<bool> {
         let state_wrapper = ctx.data_unchecked::<StateWrapper>();
         let state = unsafe { state_wrapper.state() };
@@ -240,29 +681,24 @@ impl QueryRoot {
         let runtime = state_wrapper.runtime();
         let current_time = runtime.system_time();
         let period_start = get_daily_period_start(current_time);
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
+        latest_prediction_outcome(
+            state,
             player_id,
+            &asset,
             PredictionPeriod::Daily,
-            period_start.micros()
-        );
-
-        // Get the prediction
-        if let Some(prediction) = state.predictions.get(&prediction_key).await? {
-            if let Some(correct) = prediction.correct {
-                return Ok(correct);
-            }
-        }
-
-        Ok(false) // Prediction not found or not resolved
+            period_start,
+            24 * 60 * 60 * 1_000_000,
+        )
+        .await
     }
 
     /// Get the result of a player's weekly prediction (mirrors contract's get_weekly_outcome)
-    /// Returns whether the player's prediction was correct
+    /// Returns whether the player's prediction on the asset for the latest resolved period was correct
     async fn get_weekly_outcome(
         &self,
         ctx: &async_graphql::Context<'_>,
         player_id: PlayerId,
+        asset: AssetSymbol,
     ) -> async_graphql::Result<bool> {
         let state_wrapper = ctx.data_unchecked::<StateWrapper>();
         let state = unsafe { state_wrapper.state() };
@@ -278,29 +714,24 @@ impl QueryRoot {
         let runtime = state_wrapper.runtime();
         let current_time = runtime.system_time();
         let period_start = get_weekly_period_start(current_time);
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
+        latest_prediction_outcome(
+            state,
             player_id,
+            &asset,
             PredictionPeriod::Weekly,
-            period_start.micros()
-        );
-
-        // Get the prediction
-        if let Some(prediction) = state.predictions.get(&prediction_key).await? {
-            if let Some(correct) = prediction.correct {
-                return Ok(correct);
-            }
-        }
-
-        Ok(false) // Prediction not found or not resolved
+            period_start,
+            7 * 24 * 60 * 60 * 1_000_000,
+        )
+        .await
     }
 
     /// Get the result of a player's monthly prediction (mirrors contract's get_monthly_outcome)
-    /// Returns whether the player's prediction was correct
+    /// Returns whether the player's prediction on the asset for the latest resolved period was correct
     async fn get_monthly_outcome(
         &self,
         ctx: &async_graphql::Context<'_>,
         player_id: PlayerId,
+        asset: AssetSymbol,
     ) -> async_graphql::Result<bool> {
         let state_wrapper = ctx.data_unchecked::<StateWrapper>();
         let state = unsafe { state_wrapper.state() };
@@ -316,21 +747,63 @@ impl QueryRoot {
         let runtime = state_wrapper.runtime();
         let current_time = runtime.system_time();
         let period_start = get_monthly_period_start(current_time);
-        let prediction_key = format!(
-            "{:?}_{:?}_{}",
+        latest_prediction_outcome(
+            state,
             player_id,
+            &asset,
             PredictionPeriod::Monthly,
-            period_start.micros()
-        );
+            period_start,
+            30 * 24 * 60 * 60 * 1_000_000,
+        )
+        .await
+    }
 
-        // Get the prediction
-        if let Some(prediction) = state.predictions.get(&prediction_key).await? {
-            if let Some(correct) = prediction.correct {
-                return Ok(correct);
-            }
-        }
+    /// Get the stakes pooled on an asset's prediction period, by predicted outcome
+    /// Defaults to the current period when `periodStart` is null
+    async fn prediction_pool(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        asset: AssetSymbol,
+        period: PredictionPeriod,
+        period_start: Option<Timestamp>,
+    ) -> async_graphql::Result<Option<PredictionPool>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let period_start = period_start
+            .unwrap_or_else(|| get_period_start(period, state_wrapper.runtime().system_time()));
+        let pool_key = period_key(&asset, period, period_start);
+        Ok(state.prediction_pools.get(&pool_key).await?)
+    }
 
-        Ok(false) // Prediction not found or not resolved
+    /// Get the live odds of each outcome of an asset's prediction period
+    /// Shares double as implied probabilities; payouts are points per point staked, after the rake
+    async fn pool_odds(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        asset: AssetSymbol,
+        period: PredictionPeriod,
+        period_start: Option<Timestamp>,
+    ) -> async_graphql::Result<Vec<PoolOdds>> {
+        let state_wrapper = ctx.data_unchecked::<StateWrapper>();
+        let state = unsafe { state_wrapper.state() };
+        let period_start = period_start
+            .unwrap_or_else(|| get_period_start(period, state_wrapper.runtime().system_time()));
+        let pool_key = period_key(&asset, period, period_start);
+        let pool = state
+            .prediction_pools
+            .get(&pool_key)
+            .await?
+            .unwrap_or_else(|| {
+                let config = state.config.get();
+                PredictionPool::new(
+                    asset,
+                    period,
+                    period_start,
+                    config.parimutuel_predictions,
+                    config.prediction_rake_percent,
+                )
+            });
+        Ok(pool.odds())
     }
 
     /// Get global leaderboard (aggregated across all chains)
@@ -375,7 +848,7 @@ impl QueryRoot {
         let mut markets = Vec::new();
         state
             .global_markets
-            .for_each_index_value(|_market_id, market| {
+            .for_each_index_value(|_key, market| {
                 markets.push(market.into_owned());
                 Ok(())
             })
@@ -408,7 +881,58 @@ impl QueryRoot {
     }
 }
 
+/// Look up whether a player's prediction was correct
+/// A period can only resolve once it has ended, so the previous period is checked
+/// when the prediction for the current one is still open
+async fn latest_prediction_outcome(
+    state: &PredictionMarketState,
+    player_id: PlayerId,
+    asset: &str,
+    period: PredictionPeriod,
+    period_start: Timestamp,
+    period_micros: u64,
+) -> async_graphql::Result<bool> {
+    let previous_start = Timestamp::from(period_start.micros().saturating_sub(period_micros));
+    for start in [period_start, previous_start] {
+        let prediction_key = prediction_key(player_id, asset, period, start);
+        if let Some(prediction) = state.predictions.get(&prediction_key).await? {
+            if let Some(correct) = prediction.correct {
+                return Ok(correct);
+            }
+        }
+    }
+
+    Ok(false) // Prediction not found or not resolved
+}
+
+/// Aggregate orders sorted by price into one level per price
+fn price_levels(orders: &[Order]) -> Vec<PriceLevel> {
+    let mut levels: Vec<PriceLevel> = Vec::new();
+    for order in orders {
+        match levels.last_mut() {
+            Some(level) if level.price == order.price => {
+                level.quantity = level.quantity.saturating_add(order.remaining);
+                level.order_count += 1;
+            }
+            _ => levels.push(PriceLevel {
+                price: order.price,
+                quantity: order.remaining,
+                order_count: 1,
+            }),
+        }
+    }
+    levels
+}
+
 // Helper functions for period calculations
+fn get_period_start(period: PredictionPeriod, timestamp: Timestamp) -> Timestamp {
+    match period {
+        PredictionPeriod::Daily => get_daily_period_start(timestamp),
+        PredictionPeriod::Weekly => get_weekly_period_start(timestamp),
+        PredictionPeriod::Monthly => get_monthly_period_start(timestamp),
+    }
+}
+
 fn get_daily_period_start(timestamp: Timestamp) -> Timestamp {
     let one_day_micros = 24 * 60 * 60 * 1_000_000;
     let day_start = (timestamp.micros() / one_day_micros) * one_day_micros;
@@ -438,7 +962,7 @@ mod tests {
     };
     use serde_json::json;
 
-    use super::{PredictionMarketState, PredictiveManagerService};
+    use super::{PredictionMarketState, PredictiveManagerService, SupplyComponents};
 
     #[test]
     fn query() {
@@ -447,7 +971,10 @@ mod tests {
         let mut state = PredictionMarketState::load(runtime.root_view_storage_context())
             .blocking_wait()
             .expect("Failed to read from mock key value store");
-        state.total_supply.set(total_supply);
+        state.supply.set(SupplyComponents {
+            player_balances: total_supply,
+            ..SupplyComponents::default()
+        });
 
         let service = PredictiveManagerService { state, runtime };
         let request = Request::new("{ totalSupply }");
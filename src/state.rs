@@ -1,21 +1,50 @@
 use linera_sdk::linera_base_types::{AccountOwner, Amount, ChainId, Timestamp};
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
+use linera_sdk::views::{
+    linera_views, MapView, RegisterView, RootView, ViewError, ViewStorageContext,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+use crate::pricing::{mul_div, ONE};
+use crate::OperationError;
+
 pub type MarketId = u64;
 pub type PlayerId = AccountOwner;
 pub type OutcomeId = u32;
 pub type GuildId = u64;
 pub type AchievementId = u32;
+pub type OrderId = u64;
+pub type TradeId = u64;
+pub type RemoteTradeId = u64;
+pub type LedgerEntryId = u64;
+pub type AssetSymbol = String;
 
-#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::InputObject)]
+#[derive(
+    Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject, async_graphql::InputObject,
+)]
+#[graphql(input_name = "GameConfigInput")]
+#[serde(default)] // Keys missing from a config file, such as an older config.json, keep their defaults
 pub struct GameConfig {
     pub admin: Option<AccountOwner>,
     pub initial_player_tokens: Amount,
     pub daily_login_reward: Amount,
     pub market_creation_cost: Amount,
+    pub min_creator_level: u32,      // Level needed to create a market
+    pub min_creator_balance: Amount, // Points a player must hold to create a market
+    pub min_seller_level: u32,       // Level needed to sell points to a point market
+    pub platform_fee_percent: u8,    // Platform's cut of each trading fee (0-100)
+    pub fixed_rate_exchange: u64,    // Points received per point paid in a fixed-rate market
+    pub max_airdrop_recipients: u32, // Most credits a single airdrop may carry
     pub fee_divisor: Amount,
+    pub strict_operations: bool, // Reject the whole block when any operation fails
+    pub dispute_period: u64, // Microseconds a resolution can be challenged, zero settles at once
+    pub challenge_bond: Amount, // Points posted to propose or challenge a resolution
+    pub daily_stake: StakeLimits,
+    pub weekly_stake: StakeLimits,
+    pub monthly_stake: StakeLimits,
+    pub parimutuel_predictions: bool, // Winners split each period's pool instead of minted rewards
+    pub prediction_rake_percent: u8,  // Platform's cut of each parimutuel pool (0-100)
+    pub assets: Vec<AssetSymbol>,     // Assets with a price feed that players can predict
 }
 
 impl Default for GameConfig {
@@ -25,11 +54,69 @@ impl Default for GameConfig {
             initial_player_tokens: Amount::from_tokens(100),
             daily_login_reward: Amount::from_tokens(10),
             market_creation_cost: Amount::from_tokens(100),
+            min_creator_level: 5,
+            min_creator_balance: Amount::from_tokens(10000),
+            min_seller_level: 5,
+            platform_fee_percent: 2,
+            fixed_rate_exchange: 10,
+            max_airdrop_recipients: 100,
             fee_divisor: Amount::from_tokens(100),
+            strict_operations: false,
+            dispute_period: 0,
+            challenge_bond: Amount::from_tokens(100),
+            daily_stake: StakeLimits {
+                min_stake: Amount::from_tokens(10),
+                max_stake: Amount::from_tokens(100),
+                reward_percent: 100,
+            },
+            weekly_stake: StakeLimits {
+                min_stake: Amount::from_tokens(20),
+                max_stake: Amount::from_tokens(500),
+                reward_percent: 100,
+            },
+            monthly_stake: StakeLimits {
+                min_stake: Amount::from_tokens(30),
+                max_stake: Amount::from_tokens(1000),
+                reward_percent: 100,
+            },
+            parimutuel_predictions: false,
+            prediction_rake_percent: 5,
+            assets: vec!["BTC".to_string(), "ETH".to_string(), "SOL".to_string()],
+        }
+    }
+}
+
+impl GameConfig {
+    pub fn has_asset(&self, asset: &str) -> bool {
+        self.assets.iter().any(|listed| listed == asset)
+    }
+
+    pub fn stake_limits(&self, period: PredictionPeriod) -> StakeLimits {
+        match period {
+            PredictionPeriod::Daily => self.daily_stake,
+            PredictionPeriod::Weekly => self.weekly_stake,
+            PredictionPeriod::Monthly => self.monthly_stake,
         }
     }
 }
 
+/// Stakes a player may put on a prediction of one period, and what a correct one pays
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Serialize,
+    Deserialize,
+    async_graphql::SimpleObject,
+    async_graphql::InputObject,
+)]
+#[graphql(input_name = "StakeLimitsInput")]
+pub struct StakeLimits {
+    pub min_stake: Amount,
+    pub max_stake: Amount,
+    pub reward_percent: u32, // Paid on top of the returned stake, as a percentage of it
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
 pub struct Market {
     pub id: MarketId,
@@ -42,16 +129,502 @@ pub struct Market {
     pub total_liquidity: Amount, // Total points available in this market
     pub positions: BTreeMap<PlayerId, PlayerPosition>,
     pub total_participants: u64,
+    pub outcomes: Vec<MarketOutcome>, // Empty for point-exchange markets
+    pub winning_outcome: Option<OutcomeId>, // Set once a prediction market is resolved
+    pub liquidity_parameter: Amount,  // LMSR `b` of a prediction market, zero for a point market
+    pub market_type: MarketType,
+    pub payment_reserve: Amount, // Points paid into a constant-product pool
+    pub close_time: Option<Timestamp>, // Trading stops at this time
+    pub resolution_time: Option<Timestamp>, // Earliest time a prediction market can be resolved
+    pub liquidity_positions: BTreeMap<PlayerId, LiquidityPosition>, // LP shares by provider
+    pub total_lp_shares: Amount,
+    pub trade_count: u64,                // Entries in the market's trade log
+    pub oracle: Option<OracleCondition>, // Price condition that resolves an oracle market
+    pub resolution: Option<PendingResolution>, // Proposed outcome waiting out its dispute period
+}
+
+/// Outcome proposed for a prediction market, paid out once its dispute period ends
+/// A challenge puts it to a reputation-weighted vote, the losing side forfeits its bond
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct PendingResolution {
+    pub proposer: PlayerId,
+    pub outcome_id: OutcomeId,
+    pub proposer_bond: Amount, // Zero when proposed by the admin
+    pub deadline: Timestamp,   // End of the dispute period, or of the vote once challenged
+    pub challenger: Option<PlayerId>,
+    pub challenge_outcome: Option<OutcomeId>,
+    pub challenge_bond: Amount,
+    pub uphold_weight: u64,   // Reputation of voters upholding the proposal
+    pub overturn_weight: u64, // Reputation of voters backing the challenge
+    pub voters: Vec<PlayerId>,
+}
+
+/// Price movement an oracle market bets on, over the period it is created in
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::InputObject)]
+pub struct PriceCondition {
+    pub asset: AssetSymbol,
+    pub period: PredictionPeriod,
+    pub outcome: PriceOutcome, // "Yes" wins if the asset's price moves this way over the period
+}
+
+/// Where the platform treasury's points come from, or go to
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, async_graphql::Enum,
+)]
+pub enum TreasurySource {
+    MarketCreationFee, // Fee paid to create a market
+    TradingFee,        // Platform's cut of trading fees
+    PredictionPenalty, // Points lost on wrong price predictions
+    PredictionRake,    // Platform's cut of parimutuel prediction pools
+    AdminSpend,        // Paid out by the admin
+}
+
+/// Points the treasury has received from, and paid out to, one source
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct TreasuryFlow {
+    pub source: TreasurySource,
+    pub inflow: Amount,
+    pub outflow: Amount,
+}
+
+/// Treasury balance with its flows by source
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct TreasuryReport {
+    pub balance: Amount,
+    pub total_inflow: Amount,
+    pub total_outflow: Amount,
+    pub flows: Vec<TreasuryFlow>,
+}
+
+/// Why a ledger entry moved points
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum LedgerReason {
+    Registration, // Starting balance of a new player
+    DailyReward,
+    AchievementReward,
+    MarketCreationFee,
+    LiquidityDeposit,    // Initial or added liquidity of a market
+    LiquidityWithdrawal, // Liquidity handed back to its provider
+    LiquidityEarnings,   // Providers' share of a market's trading income
+    Trade,               // Payments to and proceeds from a market, fees included
+    TradingFee,          // Platform's cut of a market's fees
+    OrderEscrow,         // Points locked by a resting buy order
+    OrderFill,           // Points paid between the two sides of a matched order
+    OrderRelease,        // Escrow handed back when an order is cancelled or released
+    MarketPayout,        // Winnings of a resolved market
+    MarketRefund,        // Investments handed back by a cancelled market
+    DisputeBond,         // Bonds posted on a resolution, and their settlement
+    Transfer,
+    Mint,
+    TreasurySpend,
+    PredictionReward,
+    PredictionStake, // Stake escrowed by a prediction, and returned when it's correct
+    PredictionPenalty,
+    PredictionPayout, // Share of a parimutuel pool paid to a winner, or a refund when nobody won
+    PredictionRake,   // Platform's cut of a parimutuel pool, with its rounding remainder
+    DebtRepayment,    // Part of a reward withheld to pay off unpaid penalties
+    GuildContribution,
+    RemoteTrade, // Escrow sent to a market on another chain, and what comes back
+}
+
+/// Kind of account a ledger entry moves points out of or into
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum LedgerAccountKind {
+    Player,      // A player's token balance
+    Market,      // Points held by a market: liquidity, payments, bonds and order escrow
+    Guild,       // A guild's shared pool
+    Treasury,    // The platform treasury
+    Predictions, // Stakes escrowed by unresolved predictions
+    Issuance,    // Where newly minted points come from
+    Chain,       // Points sent to, or received from, another chain
+}
+
+/// One side of a ledger entry; only the id matching its kind is set
+#[derive(
+    Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::SimpleObject,
+)]
+pub struct LedgerAccount {
+    pub kind: LedgerAccountKind,
+    pub player_id: Option<PlayerId>,
+    pub market_id: Option<MarketId>,
+    pub guild_id: Option<GuildId>,
+    pub chain_id: Option<ChainId>,
+}
+
+impl LedgerAccount {
+    pub const TREASURY: Self = Self::of_kind(LedgerAccountKind::Treasury);
+    pub const ISSUANCE: Self = Self::of_kind(LedgerAccountKind::Issuance);
+    pub const PREDICTIONS: Self = Self::of_kind(LedgerAccountKind::Predictions);
+
+    const fn of_kind(kind: LedgerAccountKind) -> Self {
+        Self {
+            kind,
+            player_id: None,
+            market_id: None,
+            guild_id: None,
+            chain_id: None,
+        }
+    }
+
+    pub fn player(player_id: PlayerId) -> Self {
+        Self {
+            player_id: Some(player_id),
+            ..Self::of_kind(LedgerAccountKind::Player)
+        }
+    }
+
+    pub fn market(market_id: MarketId) -> Self {
+        Self {
+            market_id: Some(market_id),
+            ..Self::of_kind(LedgerAccountKind::Market)
+        }
+    }
+
+    pub fn guild(guild_id: GuildId) -> Self {
+        Self {
+            guild_id: Some(guild_id),
+            ..Self::of_kind(LedgerAccountKind::Guild)
+        }
+    }
+
+    pub fn chain(chain_id: ChainId) -> Self {
+        Self {
+            chain_id: Some(chain_id),
+            ..Self::of_kind(LedgerAccountKind::Chain)
+        }
+    }
+}
+
+/// Where this chain's points sit; the total supply is their sum
+/// Points minted, burned or sent between chains enter and leave through the issuance and chain accounts
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, async_graphql::SimpleObject,
+)]
+pub struct SupplyComponents {
+    pub player_balances: Amount,
+    pub market_liquidity: Amount, // Liquidity, payment reserves, dispute bonds and buy order escrow
+    pub guild_pools: Amount,
+    pub treasury: Amount,
+    pub prediction_stakes: Amount, // Still held by prediction pools
+}
+
+impl SupplyComponents {
+    pub fn total(&self) -> Amount {
+        self.player_balances
+            .saturating_add(self.market_liquidity)
+            .saturating_add(self.guild_pools)
+            .saturating_add(self.treasury)
+            .saturating_add(self.prediction_stakes)
+    }
+
+    /// The component holding the points of an account kind, if they count towards the supply
+    pub fn component_mut(&mut self, kind: LedgerAccountKind) -> Option<&mut Amount> {
+        match kind {
+            LedgerAccountKind::Player => Some(&mut self.player_balances),
+            LedgerAccountKind::Market => Some(&mut self.market_liquidity),
+            LedgerAccountKind::Guild => Some(&mut self.guild_pools),
+            LedgerAccountKind::Treasury => Some(&mut self.treasury),
+            LedgerAccountKind::Predictions => Some(&mut self.prediction_stakes),
+            LedgerAccountKind::Issuance | LedgerAccountKind::Chain => None,
+        }
+    }
+
+    /// Components that differ from another count
+    pub fn drift_from(&self, other: &Self) -> Vec<SupplyComponent> {
+        [
+            (
+                SupplyComponent::PlayerBalances,
+                self.player_balances,
+                other.player_balances,
+            ),
+            (
+                SupplyComponent::MarketLiquidity,
+                self.market_liquidity,
+                other.market_liquidity,
+            ),
+            (
+                SupplyComponent::GuildPools,
+                self.guild_pools,
+                other.guild_pools,
+            ),
+            (SupplyComponent::Treasury, self.treasury, other.treasury),
+            (
+                SupplyComponent::PredictionStakes,
+                self.prediction_stakes,
+                other.prediction_stakes,
+            ),
+        ]
+        .into_iter()
+        .filter(|(_, ours, theirs)| ours != theirs)
+        .map(|(component, _, _)| component)
+        .collect()
+    }
+}
+
+/// One part of the total supply
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, async_graphql::Enum)]
+pub enum SupplyComponent {
+    PlayerBalances,
+    MarketLiquidity,
+    GuildPools,
+    Treasury,
+    PredictionStakes,
+}
+
+/// Supply as tracked by the contract next to a recount of every balance it is made of
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct SupplyReport {
+    pub total_supply: Amount, // Tracked total
+    pub tracked: SupplyComponents,
+    pub actual_supply: Amount, // Recounted total
+    pub actual: SupplyComponents,
+    pub drifted: Vec<SupplyComponent>, // Components whose tracked amount differs from the recount
+}
+
+/// Journal entry: `amount` points leave the debit account for the credit account
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct LedgerEntry {
+    pub id: LedgerEntryId,
+    pub reason: LedgerReason,
+    pub debit: LedgerAccount,
+    pub credit: LedgerAccount,
+    pub amount: Amount,
+    pub timestamp: Timestamp,
+}
+
+/// Running totals of one ledger account
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct LedgerBalance {
+    pub account: LedgerAccount,
+    pub debits: Amount,  // Points that left the account
+    pub credits: Amount, // Points that came into it
+}
+
+/// Page of a player's journal entries, oldest first
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct LedgerPage {
+    pub entries: Vec<LedgerEntry>,
+    pub next_cursor: Option<u64>, // Pass as `after` to fetch the next page
+}
+
+/// Result of checking the journal's totals against each other and against player balances
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct LedgerCheck {
+    pub balanced: bool, // Debits equal credits and no player is mismatched
+    pub total_debits: Amount,
+    pub total_credits: Amount,
+    pub mismatched_players: Vec<PlayerId>, // Balance differs from the player's net ledger credits
+}
+
+/// Points minted to one player by an airdrop
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::InputObject)]
+pub struct AirdropCredit {
+    pub player_id: PlayerId,
+    pub amount: Amount,
+}
+
+/// Price condition of an oracle market, tied to the period price data that resolves it
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct OracleCondition {
+    pub asset: AssetSymbol,
+    pub period: PredictionPeriod,
+    pub period_start: Timestamp,
+    pub outcome: PriceOutcome,
+}
+
+/// A player's share of a market's liquidity pool
+/// Trading fees are split between providers in proportion to their shares
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct LiquidityPosition {
+    pub market_id: MarketId,
+    pub provider: PlayerId,
+    pub shares: Amount,
+    pub deposited: Amount, // Points deposited, net of points withdrawn
+    pub earned: Amount,    // Fees and fixed-rate sale proceeds paid to this provider
+}
+
+/// How a market prices its trades
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, async_graphql::Enum)]
+pub enum MarketType {
+    FixedRate,       // Point market, pay a tenth of the points bought
+    ConstantProduct, // Point market backed by an x * y = k pool, the price moves with depth
+    Prediction,      // Outcome shares priced by the LMSR market maker
+}
+
+/// A named outcome of a prediction market
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct MarketOutcome {
+    pub id: OutcomeId,
+    pub name: String,
+    pub total_shares: Amount, // Shares held by all players
+}
+
+/// Current LMSR price of one share of an outcome
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct OutcomePrice {
+    pub outcome_id: OutcomeId,
+    pub name: String,
+    pub price: Amount, // Points per share, equal to the implied probability (1 = certain)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, async_graphql::Enum)]
 pub enum MarketStatus {
     Active,
     Closed,
+    Disputed, // Resolution challenged, waiting for the vote or the admin
     Resolved,
     Cancelled,
 }
 
+/// Side of a limit or market order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, async_graphql::Enum)]
+pub enum OrderSide {
+    Buy,  // Buy outcome shares with points
+    Sell, // Sell outcome shares for points
+}
+
+/// A limit order resting on a market's order book
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct Order {
+    pub id: OrderId,
+    pub market_id: MarketId,
+    pub outcome_id: OutcomeId,
+    pub owner: PlayerId,
+    pub side: OrderSide,
+    pub price: Amount,     // Points per share
+    pub quantity: Amount,  // Shares ordered
+    pub remaining: Amount, // Shares not filled yet
+    pub escrow: Amount, // Points held for a buy order, or the invested amount behind a sell order's shares
+    pub created_at: Timestamp,
+}
+
+/// Progress of a trade sent to the chain hosting its market
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, async_graphql::Enum)]
+pub enum RemoteTradeStatus {
+    Pending,  // Escrowed, waiting for the hosting chain
+    Settled,  // Executed, the unused escrow and any proceeds returned
+    Rejected, // Refused by the hosting chain, the escrow returned
+    Bounced,  // Never reached the market, the escrow returned
+}
+
+/// A trade on a market hosted on another chain, with the points escrowed for it on this chain
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct RemoteTrade {
+    pub id: RemoteTradeId,
+    pub player_id: PlayerId,
+    pub market_id: MarketId,
+    pub chain_id: ChainId, // Chain hosting the market
+    pub outcome_id: Option<OutcomeId>,
+    pub side: OrderSide,
+    pub amount: Amount,
+    pub escrow: Amount, // Points held here until the trade settles
+    pub refund: Amount, // Points returned at settlement: unused escrow plus any proceeds
+    pub fee: Amount,
+    pub status: RemoteTradeStatus,
+    pub error: Option<String>, // Why the hosting chain rejected the trade
+    pub requested_at: Timestamp,
+}
+
+/// A fill recorded in a market's trade log
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct Trade {
+    pub id: TradeId, // Position in the market's trade log
+    pub market_id: MarketId,
+    pub player_id: PlayerId,
+    pub counterparty: Option<PlayerId>, // Maker of an order book fill, none when trading with the market
+    pub outcome_id: OutcomeId,          // Always 0 in a point market
+    pub side: OrderSide,                // Side of `player_id`
+    pub amount: Amount,                 // Points (or shares) traded
+    pub price: Amount,                  // Points paid per unit
+    pub total: Amount,                  // Points exchanged, before the fee
+    pub fee: Amount,
+    pub timestamp: Timestamp,
+}
+
+/// One page of a market's trade log
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct TradePage {
+    pub trades: Vec<Trade>,
+    pub next_cursor: Option<TradeId>, // Pass as `after` to get the next page, none on the last page
+}
+
+/// Length of the interval a candle aggregates
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, async_graphql::Enum,
+)]
+pub enum CandleResolution {
+    Minute,
+    Hour,
+    Day,
+}
+
+impl CandleResolution {
+    /// Every resolution, maintained together for each price series
+    pub const ALL: [CandleResolution; 3] = [Self::Minute, Self::Hour, Self::Day];
+
+    /// Length of one candle in microseconds
+    pub fn micros(self) -> u64 {
+        match self {
+            Self::Minute => 60 * 1_000_000,
+            Self::Hour => 60 * 60 * 1_000_000,
+            Self::Day => 24 * 60 * 60 * 1_000_000,
+        }
+    }
+
+    /// Start of the candle containing `timestamp`
+    pub fn candle_start(self, timestamp: Timestamp) -> Timestamp {
+        Timestamp::from(timestamp.micros() / self.micros() * self.micros())
+    }
+}
+
+/// Open, high, low, close and volume of a price series over one interval
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct Candle {
+    pub start: Timestamp,
+    pub open: Amount,
+    pub high: Amount,
+    pub low: Amount,
+    pub close: Amount,
+    pub volume: Amount,   // Units traded, always zero for the price feed
+    pub trade_count: u32, // Trades (or price updates) aggregated
+}
+
+/// What a series of candles follows: a market's trades or an asset's price feed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum CandleSource {
+    Market(MarketId),
+    PriceFeed(AssetSymbol),
+}
+
+/// Key of a stored candle: source, outcome (zero for a price feed), resolution and start in microseconds
+pub type CandleKey = (CandleSource, OutcomeId, CandleResolution, u64);
+
+/// Open limit orders of a market, across all its outcomes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub bids: Vec<Order>, // Highest price first, oldest first within a price
+    pub asks: Vec<Order>, // Lowest price first, oldest first within a price
+}
+
+/// Open shares at one price of an order book
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct PriceLevel {
+    pub price: Amount,
+    pub quantity: Amount,
+    pub order_count: u32,
+}
+
+/// One outcome's side of an order book, with its depth aggregated by price level
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct OrderBookDepth {
+    pub market_id: MarketId,
+    pub outcome_id: OutcomeId,
+    pub bids: Vec<Order>,
+    pub asks: Vec<Order>,
+    pub bid_levels: Vec<PriceLevel>,
+    pub ask_levels: Vec<PriceLevel>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerPosition {
     pub shares_by_outcome: BTreeMap<OutcomeId, Amount>,
@@ -79,6 +652,7 @@ pub struct Player {
     pub guild_id: Option<GuildId>,
     pub achievements_earned: Vec<AchievementId>,
     pub active_markets: Vec<MarketId>,
+    pub debt: Amount, // Penalties the balance couldn't cover, repaid from later rewards
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
@@ -103,10 +677,24 @@ pub struct LeaderboardEntry {
     pub player_id: PlayerId,
     pub display_name: Option<String>,
     pub total_profit: Amount,
+    #[serde(with = "f64_bits")]
     pub win_rate: f64,
     pub level: u32,
 }
 
+/// BCS has no floating point support, so floats are stored as their IEEE-754 bits
+mod f64_bits {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &f64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(value.to_bits())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+        u64::deserialize(deserializer).map(f64::from_bits)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
 pub struct Guild {
     pub id: GuildId,
@@ -165,7 +753,7 @@ pub enum PriceOutcome {
 }
 
 /// Represents the period type for predictions
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, async_graphql::Enum)]
 pub enum PredictionPeriod {
     Daily,   // Daily prediction (24 hours)
     Weekly,  // Weekly prediction (7 days)
@@ -176,12 +764,143 @@ pub enum PredictionPeriod {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlayerPrediction {
     pub player_id: PlayerId,
+    pub asset: AssetSymbol,
     pub period: PredictionPeriod,
     pub outcome: PriceOutcome,
     pub prediction_time: Timestamp,
     pub period_start: Timestamp, // Start timestamp of the prediction period
     pub resolved: bool,          // Whether the prediction has been resolved
     pub correct: Option<bool>,   // None if not resolved, Some(true/false) if resolved
+    pub stake: Amount,           // Escrowed until resolution, returned with the reward if correct
+}
+
+/// Stakes on one period's predictions, pooled by predicted outcome
+/// In parimutuel mode the winners split the pool after the rake; otherwise it only tracks escrow
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct PredictionPool {
+    pub asset: AssetSymbol,
+    pub period: PredictionPeriod,
+    pub period_start: Timestamp,
+    pub parimutuel: bool, // Settlement mode, fixed when the pool opens
+    pub rake_percent: u8, // Fixed when the pool opens
+    pub rise_stake: Amount,
+    pub fall_stake: Amount,
+    pub neutral_stake: Amount,
+    pub predictions: u32, // Predictions staked into the pool
+    pub settled: u32,     // Predictions resolved so far
+    pub paid_out: Amount, // Points that have left the pool, to players or the treasury
+}
+
+impl PredictionPool {
+    pub fn new(
+        asset: AssetSymbol,
+        period: PredictionPeriod,
+        period_start: Timestamp,
+        parimutuel: bool,
+        rake_percent: u8,
+    ) -> Self {
+        Self {
+            asset,
+            period,
+            period_start,
+            parimutuel,
+            rake_percent,
+            rise_stake: Amount::ZERO,
+            fall_stake: Amount::ZERO,
+            neutral_stake: Amount::ZERO,
+            predictions: 0,
+            settled: 0,
+            paid_out: Amount::ZERO,
+        }
+    }
+
+    pub fn total(&self) -> Amount {
+        self.rise_stake
+            .saturating_add(self.fall_stake)
+            .saturating_add(self.neutral_stake)
+    }
+
+    /// Points the pool still holds
+    pub fn held(&self) -> Amount {
+        self.total().saturating_sub(self.paid_out)
+    }
+
+    pub fn staked_on(&self, outcome: PriceOutcome) -> Amount {
+        match outcome {
+            PriceOutcome::Rise => self.rise_stake,
+            PriceOutcome::Fall => self.fall_stake,
+            PriceOutcome::Neutral => self.neutral_stake,
+        }
+    }
+
+    pub fn add_stake(&mut self, outcome: PriceOutcome, stake: Amount) {
+        let staked = match outcome {
+            PriceOutcome::Rise => &mut self.rise_stake,
+            PriceOutcome::Fall => &mut self.fall_stake,
+            PriceOutcome::Neutral => &mut self.neutral_stake,
+        };
+        *staked = staked.saturating_add(stake);
+        self.predictions += 1;
+    }
+
+    /// What the winners share once the rake is taken
+    pub fn net(&self) -> Amount {
+        let total: u128 = self.total().into();
+        let rake = mul_div(total, self.rake_percent.into(), 100);
+        Amount::from_attos(total.saturating_sub(rake))
+    }
+
+    /// What a stake on `predicted` receives when the period settles on `actual`
+    /// Winners split the net pool by stake; if nobody picked `actual`, every stake is refunded
+    pub fn payout(&self, predicted: PriceOutcome, stake: Amount, actual: PriceOutcome) -> Amount {
+        let winning = self.staked_on(actual);
+        if winning == Amount::ZERO {
+            return stake;
+        }
+        if predicted != actual {
+            return Amount::ZERO;
+        }
+        Amount::from_attos(mul_div(stake.into(), self.net().into(), winning.into()))
+    }
+
+    /// Live share of the pool and payout per point staked on each outcome
+    pub fn odds(&self) -> Vec<PoolOdds> {
+        let total: u128 = self.total().into();
+        let net: u128 = self.net().into();
+        [
+            PriceOutcome::Rise,
+            PriceOutcome::Fall,
+            PriceOutcome::Neutral,
+        ]
+        .into_iter()
+        .map(|outcome| {
+            let staked = self.staked_on(outcome);
+            let (share, payout) = if staked == Amount::ZERO {
+                (Amount::ZERO, Amount::ZERO)
+            } else {
+                (
+                    Amount::from_attos(mul_div(staked.into(), ONE, total)),
+                    Amount::from_attos(mul_div(net, ONE, staked.into())),
+                )
+            };
+            PoolOdds {
+                outcome,
+                staked,
+                share,
+                payout,
+            }
+        })
+        .collect()
+    }
+}
+
+/// Live standing of one outcome in a prediction pool
+#[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+pub struct PoolOdds {
+    pub outcome: PriceOutcome,
+    pub staked: Amount,
+    pub share: Amount, // Fraction of the pool staked on the outcome, its implied probability
+    pub payout: Amount, // Points paid per point staked if the outcome wins, after the rake
 }
 
 /// Stores market price data at a specific timestamp
@@ -195,6 +914,8 @@ pub struct MarketPrice {
 /// Stores price data for a specific period
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeriodPriceData {
+    pub asset: AssetSymbol,
+    pub period: PredictionPeriod,
     pub period_start: Timestamp,
     pub period_end: Timestamp,
     pub start_price: Option<MarketPrice>, // Price at period start
@@ -203,6 +924,25 @@ pub struct PeriodPriceData {
     pub resolved: bool,
 }
 
+/// Key of an asset's price data, pool and oracle markets for one period
+pub fn period_key(asset: &str, period: PredictionPeriod, period_start: Timestamp) -> String {
+    format!("{}_{:?}_{}", asset, period, period_start.micros())
+}
+
+/// Key of a player's prediction on an asset for one period
+pub fn prediction_key(
+    player_id: PlayerId,
+    asset: &str,
+    period: PredictionPeriod,
+    period_start: Timestamp,
+) -> String {
+    format!(
+        "{:?}_{}",
+        player_id,
+        period_key(asset, period, period_start)
+    )
+}
+
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
 pub struct PredictionMarketState {
@@ -212,15 +952,38 @@ pub struct PredictionMarketState {
     pub leaderboard: RegisterView<Leaderboard>,
     pub guilds: MapView<GuildId, Guild>,
     pub achievements: MapView<AchievementId, Achievement>,
-    pub total_supply: RegisterView<Amount>,
+    pub supply: RegisterView<SupplyComponents>, // Tracked parts of the total supply
+    pub treasury_balance: RegisterView<Amount>, // Platform fees and penalties held by the platform
+    pub treasury_flows: MapView<TreasurySource, TreasuryFlow>, // Running totals by source
+    // Double-entry ledger of every balance change
+    pub journal: MapView<LedgerEntryId, LedgerEntry>, // Append-only, in posting order
+    pub next_ledger_entry_id: RegisterView<LedgerEntryId>,
+    pub ledger_balances: MapView<LedgerAccount, LedgerBalance>, // Running totals by account
+    pub player_journal: MapView<(PlayerId, u64), LedgerEntryId>, // Each player's entries in order
+    pub player_journal_len: MapView<PlayerId, u64>,
     pub next_market_id: RegisterView<MarketId>,
+    // Order book state
+    pub order_books: MapView<MarketId, OrderBook>, // Open limit orders per market
+    pub next_order_id: RegisterView<OrderId>,
+    // Trade history
+    pub trades: MapView<(MarketId, TradeId), Trade>, // Append-only trade log of each market
+    pub candles: MapView<CandleKey, Candle>,         // OHLCV candles of markets and the price feed
+    // Cross-chain trading
+    pub remote_trades: MapView<RemoteTradeId, RemoteTrade>, // Trades sent from this chain to markets elsewhere
+    pub next_remote_trade_id: RegisterView<RemoteTradeId>,
+    pub remote_traders: MapView<PlayerId, ChainId>, // Home chains of players trading here from elsewhere
+    pub hosted_accounts: MapView<PlayerId, Player>, // Their accounts, kept apart from the players registered here
     // Price prediction state
-    pub predictions: MapView<String, PlayerPrediction>, // Key: format!("{player_id}_{period}_{period_start}")
-    pub period_prices: MapView<String, PeriodPriceData>, // Key: format!("{period}_{period_start}")
-    pub current_market_price: RegisterView<MarketPrice>, // Current market price (updated by admin)
+    pub predictions: MapView<String, PlayerPrediction>, // Key: prediction_key()
+    pub period_prices: MapView<String, PeriodPriceData>, // Key: period_key()
+    pub prediction_pools: MapView<String, PredictionPool>, // Same key as period_prices
+    pub oracle_markets: MapView<String, Vec<MarketId>>, // Unresolved oracle markets, same key as period_prices
+    pub pending_resolutions: MapView<MarketId, Timestamp>, // Dispute deadlines of proposed resolutions
+    pub market_close_times: MapView<MarketId, Timestamp>, // Close times of active markets, closed before each operation
+    pub asset_prices: MapView<AssetSymbol, MarketPrice>, // Latest price of each asset (updated by admin)
     // Global state for horizontal scaling (cross-chain)
     pub global_players: MapView<PlayerId, GlobalPlayerInfo>, // Registry of all players across all chains
-    pub global_markets: MapView<MarketId, GlobalMarketInfo>, // Registry of all markets across all chains
+    pub global_markets: MapView<(ChainId, MarketId), GlobalMarketInfo>, // Registry of all markets across all chains, by hosting chain
     pub global_guilds: MapView<GuildId, GlobalGuildInfo>, // Registry of all guilds across all chains
     pub global_leaderboard: RegisterView<Leaderboard>, // Aggregated leaderboard across all chains
     // Chain registry for cross-chain messaging
@@ -232,6 +995,73 @@ pub struct PredictionMarketState {
     pub leaderboard_update_count: RegisterView<u64>, // Counter of updates since last broadcast
 }
 
+impl PredictionMarketState {
+    /// Recount the supply from player balances (hosted accounts included), market holdings,
+    /// guild pools, the treasury and what prediction pools still hold
+    pub async fn count_supply(&self) -> Result<SupplyComponents, ViewError> {
+        let mut supply = SupplyComponents {
+            treasury: *self.treasury_balance.get(),
+            ..SupplyComponents::default()
+        };
+        self.players
+            .for_each_index_value(|_player_id, player| {
+                supply.player_balances =
+                    supply.player_balances.saturating_add(player.token_balance);
+                Ok(())
+            })
+            .await?;
+        self.hosted_accounts
+            .for_each_index_value(|_player_id, account| {
+                supply.player_balances =
+                    supply.player_balances.saturating_add(account.token_balance);
+                Ok(())
+            })
+            .await?;
+        self.markets
+            .for_each_index_value(|_market_id, market| {
+                let bonds = market
+                    .resolution
+                    .as_ref()
+                    .map_or(Amount::ZERO, |resolution| {
+                        resolution
+                            .proposer_bond
+                            .saturating_add(resolution.challenge_bond)
+                    });
+                supply.market_liquidity = supply
+                    .market_liquidity
+                    .saturating_add(market.total_liquidity)
+                    .saturating_add(market.payment_reserve)
+                    .saturating_add(bonds);
+                Ok(())
+            })
+            .await?;
+        self.order_books
+            .for_each_index_value(|_market_id, book| {
+                for order in book.bids.iter().chain(&book.asks) {
+                    if order.side == OrderSide::Buy {
+                        supply.market_liquidity =
+                            supply.market_liquidity.saturating_add(order.escrow);
+                    }
+                }
+                Ok(())
+            })
+            .await?;
+        self.guilds
+            .for_each_index_value(|_guild_id, guild| {
+                supply.guild_pools = supply.guild_pools.saturating_add(guild.shared_pool);
+                Ok(())
+            })
+            .await?;
+        self.prediction_pools
+            .for_each_index_value(|_key, pool| {
+                supply.prediction_stakes = supply.prediction_stakes.saturating_add(pool.held());
+                Ok(())
+            })
+            .await?;
+        Ok(supply)
+    }
+}
+
 /// Global player information for cross-chain coordination
 #[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
 pub struct GlobalPlayerInfo {
@@ -281,6 +1111,56 @@ pub enum Message {
         shares: Amount,
         price: Amount,
     },
+    MarketResolved {
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+    },
+    PointsMinted {
+        player_id: PlayerId,
+        amount: Amount,
+    },
+    TreasurySpent {
+        recipient: PlayerId,
+        amount: Amount,
+        memo: Option<String>,
+    },
+    SupplyReconciled {
+        previous_supply: Amount,
+        supply: Amount,
+        drifted: Vec<SupplyComponent>,
+    },
+    PointsTransferred {
+        from: PlayerId,
+        to: PlayerId,
+        amount: Amount,
+        memo: Option<String>,
+    },
+    ResolutionProposed {
+        market_id: MarketId,
+        outcome_id: OutcomeId,
+        deadline: Timestamp,
+    },
+    ResolutionChallenged {
+        market_id: MarketId,
+        challenger: PlayerId,
+        outcome_id: OutcomeId,
+    },
+    DisputeSettled {
+        market_id: MarketId,
+        upheld: bool,
+    },
+    LiquidityAdded {
+        market_id: MarketId,
+        provider: PlayerId,
+        amount: Amount,
+        shares: Amount,
+    },
+    LiquidityWithdrawn {
+        market_id: MarketId,
+        provider: PlayerId,
+        amount: Amount,
+        shares: Amount,
+    },
     PlayerLeveledUp {
         player_id: PlayerId,
         new_level: u32,
@@ -295,11 +1175,13 @@ pub enum Message {
     },
     PredictionMade {
         player_id: PlayerId,
+        asset: AssetSymbol,
         period: PredictionPeriod,
         outcome: PriceOutcome,
     },
     PredictionResolved {
         player_id: PlayerId,
+        asset: AssetSymbol,
         period: PredictionPeriod,
         correct: bool,
     },
@@ -311,6 +1193,12 @@ pub enum Message {
         chain_id: ChainId,
         message_id: String, // Unique message ID for deduplication
     },
+    GlobalMarketStatusUpdated {
+        market_id: MarketId,
+        status: MarketStatus,
+        chain_id: ChainId,
+        message_id: String, // Unique message ID for deduplication
+    },
     GlobalPlayerRegistered {
         player_id: PlayerId,
         display_name: Option<String>,
@@ -340,11 +1228,45 @@ pub enum Message {
         message_id: String,   // Unique message ID for deduplication
     },
     GlobalPriceUpdate {
+        asset: AssetSymbol,
         price: Amount,
         timestamp: Timestamp,
         chain_id: ChainId,
         message_id: String, // Unique message ID for deduplication
     },
+    // Cross-chain trading
+    RemoteTradeRequest {
+        trade_id: RemoteTradeId,
+        trader: PlayerId,
+        level: u32, // Trader's level on their home chain, for the seller level gate
+        home_chain: ChainId,
+        market_id: MarketId,
+        outcome_id: Option<OutcomeId>,
+        side: OrderSide,
+        amount: Amount,
+        escrow: Amount, // Points the trade may spend, returned if it bounces
+        max_payment: Option<Amount>,
+        min_received: Option<Amount>,
+        deadline: Option<Timestamp>,
+    },
+    RemoteTradeSettled {
+        trade_id: RemoteTradeId,
+        trader: PlayerId,
+        refund: Amount, // Unused escrow plus any proceeds
+        fee: Amount,
+        error: Option<OperationError>,
+    },
+    RemotePayout {
+        player_id: PlayerId,
+        amount: Amount, // Resolution payouts and refunds of a remote trader's positions
+    },
+    // Cross-chain transfers
+    TransferCredit {
+        from: PlayerId,
+        to: PlayerId,
+        amount: Amount, // Already debited from the sender, refunded if the message bounces
+        memo: Option<String>,
+    },
     // Chain registration for cross-chain coordination
     ChainRegistered {
         chain_id: ChainId,
@@ -7,10 +7,14 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 use linera_sdk::{
-    linera_base_types::Amount,
+    linera_base_types::{AccountOwner, Amount},
     test::{QueryOutcome, TestValidator},
 };
-use predictive_manager::{GameConfig, Operation, PriceOutcome};
+use predictive_manager::pricing::{
+    constant_product_buy_cost, constant_product_sell_proceeds, lmsr_buy_cost,
+    lmsr_liquidity_parameter, lmsr_prices, lmsr_sell_proceeds,
+};
+use predictive_manager::{GameConfig, MarketType, Operation, PriceOutcome};
 use proptest::prelude::*;
 
 // Configure proptest to run fewer cases for faster execution
@@ -83,6 +87,7 @@ proptest! {
             >()
             .await;
             let mut chain = validator.new_chain().await;
+            let player_id = AccountOwner::from(chain.public_key());
 
             let config = GameConfig::default();
             let application_id = chain
@@ -107,6 +112,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::MintPoints {
+                            recipient: player_id,
                             amount: Amount::from_tokens(20000), // Enough for market creation
                         },
                     );
@@ -122,6 +128,11 @@ proptest! {
                             title: title.clone(),
                             amount: Amount::from_tokens(amount),
                             fee_percent,
+                            outcomes: Vec::new(),
+                            market_type: MarketType::FixedRate,
+                            close_time: None,
+                            resolution_time: None,
+                            oracle: None,
                         },
                     );
                 })
@@ -155,6 +166,7 @@ proptest! {
             >()
             .await;
             let mut chain = validator.new_chain().await;
+            let player_id = AccountOwner::from(chain.public_key());
 
             let config = GameConfig::default();
             let application_id = chain
@@ -179,6 +191,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::MintPoints {
+                            recipient: player_id,
                             amount: Amount::from_tokens(1000000),
                         },
                     );
@@ -194,6 +207,11 @@ proptest! {
                             title: "Test Market".to_string(),
                             amount: Amount::from_tokens(10000),
                             fee_percent: 5,
+                            outcomes: Vec::new(),
+                            market_type: MarketType::FixedRate,
+                            close_time: None,
+                            resolution_time: None,
+                            oracle: None,
                         },
                     );
                 })
@@ -209,7 +227,11 @@ proptest! {
                         application_id,
                         Operation::BuyShares {
                             market_id,
+                            outcome_id: None,
                             amount: Amount::from_tokens(buy_amount),
+                            max_payment: None,
+                            min_received: None,
+                            deadline: None,
                         },
                     );
                 })
@@ -273,7 +295,11 @@ proptest! {
                 .add_block(|block| {
                     block.with_operation(
                         application_id,
-                        Operation::PredictDailyOutcome { outcome },
+                        Operation::PredictDailyOutcome {
+                            asset: "BTC".to_string(),
+                            outcome,
+                            stake: Amount::from_tokens(10),
+                        },
                     );
                 })
                 .await;
@@ -283,7 +309,11 @@ proptest! {
                 .add_block(|block| {
                     block.with_operation(
                         application_id,
-                        Operation::PredictWeeklyOutcome { outcome },
+                        Operation::PredictWeeklyOutcome {
+                            asset: "BTC".to_string(),
+                            outcome,
+                            stake: Amount::from_tokens(20),
+                        },
                     );
                 })
                 .await;
@@ -293,7 +323,11 @@ proptest! {
                 .add_block(|block| {
                     block.with_operation(
                         application_id,
-                        Operation::PredictMonthlyOutcome { outcome },
+                        Operation::PredictMonthlyOutcome {
+                            asset: "BTC".to_string(),
+                            outcome,
+                            stake: Amount::from_tokens(30),
+                        },
                     );
                 })
                 .await;
@@ -447,6 +481,68 @@ proptest! {
         assert!(amount2 <= u128::MAX);
     }
 
+    /// Test LMSR prices are probabilities and trades are priced consistently
+    #[test]
+    fn fuzz_lmsr_pricing(
+        quantities in prop::collection::vec(0u128..=100000u128, 2..=8),
+        subsidy in 1u128..=100000u128,
+        shares in 1u128..=10000u128,
+        outcome in 0usize..8,
+    ) {
+        let quantities: Vec<Amount> = quantities.into_iter().map(Amount::from_tokens).collect();
+        let outcome = outcome % quantities.len();
+        let b = lmsr_liquidity_parameter(Amount::from_tokens(subsidy), quantities.len());
+        let shares = Amount::from_tokens(shares);
+
+        // Prices are implied probabilities, so they add up to one point (rounded down per outcome)
+        let prices = lmsr_prices(&quantities, b);
+        let total = prices
+            .iter()
+            .fold(Amount::ZERO, |total, price| total.saturating_add(*price));
+        assert!(total <= Amount::from_tokens(1));
+        assert!(total >= Amount::from_tokens(1).saturating_sub(Amount::from_attos(quantities.len() as u128)));
+
+        // No share costs more than the one point it can pay out
+        let cost = lmsr_buy_cost(&quantities, b, outcome, shares);
+        assert!(cost <= shares.saturating_add(Amount::from_attos(1_000_000_000)));
+
+        // Selling the shares right back returns exactly what they cost
+        let mut after = quantities.clone();
+        after[outcome] = after[outcome].saturating_add(shares);
+        assert_eq!(lmsr_sell_proceeds(&after, b, outcome, shares), cost);
+
+        // Buying never makes the outcome cheaper
+        assert!(lmsr_prices(&after, b)[outcome] >= prices[outcome]);
+    }
+
+    /// Test constant-product pools get pricier with size and never pay back more than they took
+    #[test]
+    fn fuzz_constant_product_pricing(
+        point_reserve in 3u128..=1000000u128,
+        payment_reserve in 1u128..=1000000u128,
+        amount in 1u128..=1000000u128,
+    ) {
+        // Keep twice the amount below the point reserve, which can never be drained
+        let amount = amount % ((point_reserve - 1) / 2) + 1;
+        let point_reserve = Amount::from_tokens(point_reserve);
+        let payment_reserve = Amount::from_tokens(payment_reserve);
+        let amount = Amount::from_tokens(amount);
+
+        // Buying twice as much costs at least twice as much
+        let cost = constant_product_buy_cost(point_reserve, payment_reserve, amount);
+        let double_cost =
+            constant_product_buy_cost(point_reserve, payment_reserve, amount.saturating_add(amount));
+        assert!(double_cost >= cost.saturating_add(cost));
+
+        // Selling the points right back returns at most what they cost
+        let proceeds = constant_product_sell_proceeds(
+            point_reserve.saturating_sub(amount),
+            payment_reserve.saturating_add(cost),
+            amount,
+        );
+        assert!(proceeds <= cost);
+    }
+
     /// Test price update with fuzzed prices
     #[test]
     fn fuzz_price_update(
@@ -485,6 +581,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::UpdateMarketPrice {
+                            asset: "BTC".to_string(),
                             price: Amount::from_tokens(price),
                         },
                     );
@@ -522,6 +619,7 @@ proptest! {
             >()
             .await;
             let mut chain = validator.new_chain().await;
+            let player_id = AccountOwner::from(chain.public_key());
 
             let config = GameConfig::default();
             let application_id = chain
@@ -546,6 +644,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::MintPoints {
+                            recipient: player_id,
                             amount: Amount::from_tokens(1000000),
                         },
                     );
@@ -566,29 +665,46 @@ proptest! {
                         title: string_val.clone(),
                         amount: Amount::from_tokens(amount_val.min(10000)),
                         fee_percent: (amount_val % 101) as u8,
+                        outcomes: Vec::new(),
+                        market_type: MarketType::FixedRate,
+                        close_time: None,
+                        resolution_time: None,
+                        oracle: None,
                     },
                     4 => Operation::BuyShares {
                         market_id: 0,
+                        outcome_id: None,
                         amount: Amount::from_tokens(amount_val),
+                        max_payment: None,
+                        min_received: None,
+                        deadline: None,
                     },
                     5 => Operation::SellShares {
                         market_id: 0,
+                        outcome_id: None,
                         amount: Amount::from_tokens(amount_val),
+                        max_payment: None,
+                        min_received: None,
+                        deadline: None,
                     },
                     6 => Operation::CreateGuild {
                         name: string_val.clone(),
                     },
                     7 => Operation::PredictDailyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: match amount_val % 3 {
                             0 => PriceOutcome::Rise,
                             1 => PriceOutcome::Fall,
                             _ => PriceOutcome::Neutral,
                         },
+                        stake: Amount::from_tokens(amount_val),
                     },
                     8 => Operation::MintPoints {
+                        recipient: player_id,
                         amount: Amount::from_tokens(amount_val),
                     },
                     9 => Operation::UpdateMarketPrice {
+                        asset: "BTC".to_string(),
                         price: Amount::from_tokens(amount_val),
                     },
                     _ => Operation::ClaimDailyReward,
@@ -711,6 +827,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::UpdateMarketPrice {
+                            asset: "BTC".to_string(),
                             price: Amount::from_tokens(price1),
                         },
                     );
@@ -723,6 +840,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::UpdateMarketPrice {
+                            asset: "BTC".to_string(),
                             price: Amount::from_tokens(price2),
                         },
                     );
@@ -752,6 +870,7 @@ proptest! {
             >()
             .await;
             let mut chain = validator.new_chain().await;
+            let player_id = AccountOwner::from(chain.public_key());
 
             let config = GameConfig::default();
             let application_id = chain
@@ -777,6 +896,7 @@ proptest! {
                     block.with_operation(
                         application_id,
                         Operation::MintPoints {
+                            recipient: player_id,
                             amount: Amount::from_tokens(mint_amount),
                         },
                     );
@@ -793,6 +913,11 @@ proptest! {
                                 title: "Test Market".to_string(),
                                 amount: Amount::from_tokens(spend_amount.min(10000)),
                                 fee_percent: 5,
+                                outcomes: Vec::new(),
+                                market_type: MarketType::FixedRate,
+                                close_time: None,
+                                resolution_time: None,
+                                oracle: None,
                             },
                         );
                     })
@@ -845,6 +970,7 @@ proptest! {
             >()
             .await;
             let mut chain = validator.new_chain().await;
+            let player_id = AccountOwner::from(chain.public_key());
 
             let config = GameConfig::default();
             let application_id = chain
@@ -877,6 +1003,7 @@ proptest! {
                                 block.with_operation(
                                     application_id,
                                     Operation::MintPoints {
+                                        recipient: player_id,
                                         amount: Amount::from_tokens(amount),
                                     },
                                 );
@@ -895,6 +1022,11 @@ proptest! {
                                             title: "Test Market".to_string(),
                                             amount: Amount::from_tokens(amount.min(10000)),
                                             fee_percent: 5,
+                                            outcomes: Vec::new(),
+                                            market_type: MarketType::FixedRate,
+                                            close_time: None,
+                                            resolution_time: None,
+                                            oracle: None,
                                         },
                                     );
                                 })
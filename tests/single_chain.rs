@@ -10,10 +10,13 @@
 #![cfg(not(target_arch = "wasm32"))]
 
 use linera_sdk::{
-    linera_base_types::Amount,
-    test::{QueryOutcome, TestValidator},
+    linera_base_types::{AccountOwner, AccountSecretKey, Amount, ApplicationId, Timestamp},
+    test::{ActiveChain, MessageAction, QueryOutcome, TestValidator},
+};
+use predictive_manager::{
+    AirdropCredit, GameConfig, MarketType, Operation, OperationError, OperationResponse, OrderSide,
+    PredictionPeriod, PredictiveManagerAbi, PriceCondition, PriceOutcome, StakeLimits,
 };
-use predictive_manager::{GameConfig, Operation, PriceOutcome};
 
 // ============================================================================
 // Player Registration Tests
@@ -351,6 +354,11 @@ async fn test_market_creation_insufficient_level() {
                     title: "Test Market".to_string(),
                     amount: Amount::from_tokens(1000),
                     fee_percent: 5,
+                    outcomes: Vec::new(),
+                    market_type: MarketType::FixedRate,
+                    close_time: None,
+                    resolution_time: None,
+                    oracle: None,
                 },
             );
         })
@@ -403,6 +411,11 @@ async fn test_market_creation_insufficient_balance() {
                     title: "Expensive Market".to_string(),
                     amount: Amount::from_tokens(1000),
                     fee_percent: 5,
+                    outcomes: Vec::new(),
+                    market_type: MarketType::FixedRate,
+                    close_time: None,
+                    resolution_time: None,
+                    oracle: None,
                 },
             );
         })
@@ -440,6 +453,11 @@ async fn test_market_creation_invalid_fee() {
                     title: "Invalid Fee Market".to_string(),
                     amount: Amount::from_tokens(1000),
                     fee_percent: 150, // Invalid: > 100
+                    outcomes: Vec::new(),
+                    market_type: MarketType::FixedRate,
+                    close_time: None,
+                    resolution_time: None,
+                    oracle: None,
                 },
             );
         })
@@ -480,7 +498,12 @@ async fn test_buy_shares_success() {
                 application_id,
                 Operation::BuyShares {
                     market_id: 999, // Non-existent market
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(50),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -515,8 +538,13 @@ async fn test_buy_shares_insufficient_balance() {
             block.with_operation(
                 application_id,
                 Operation::BuyShares {
-                    market_id: 0,                        // Market may not exist, but test error handling
+                    market_id: 0, // Market may not exist, but test error handling
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(100000), // Way more than player has
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -552,7 +580,12 @@ async fn test_buy_shares_nonexistent_market() {
                 application_id,
                 Operation::BuyShares {
                     market_id: 99999,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(50),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -592,7 +625,12 @@ async fn test_sell_shares_insufficient_level() {
                 application_id,
                 Operation::SellShares {
                     market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(10),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -628,7 +666,12 @@ async fn test_sell_shares_insufficient_balance() {
                 application_id,
                 Operation::SellShares {
                     market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(100000), // More than player has
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -664,7 +707,12 @@ async fn test_sell_shares_nonexistent_market() {
                 application_id,
                 Operation::SellShares {
                     market_id: 99999,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::from_tokens(10),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -972,6 +1020,7 @@ async fn test_mint_points_unauthorized() {
             block.with_operation(
                 application_id,
                 Operation::MintPoints {
+                    recipient: AccountOwner::CHAIN,
                     amount: Amount::from_tokens(1000),
                 },
             );
@@ -1043,6 +1092,7 @@ async fn test_update_market_price_unauthorized() {
             block.with_operation(
                 application_id,
                 Operation::UpdateMarketPrice {
+                    asset: "BTC".to_string(),
                     price: Amount::from_tokens(50000),
                 },
             );
@@ -1082,7 +1132,9 @@ async fn test_predict_daily_outcome_success() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1117,7 +1169,9 @@ async fn test_predict_daily_outcome_duplicate() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1129,7 +1183,9 @@ async fn test_predict_daily_outcome_duplicate() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1163,7 +1219,9 @@ async fn test_predict_weekly_outcome() {
             block.with_operation(
                 application_id,
                 Operation::PredictWeeklyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(20),
                 },
             );
         })
@@ -1197,7 +1255,9 @@ async fn test_predict_monthly_outcome() {
             block.with_operation(
                 application_id,
                 Operation::PredictMonthlyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Neutral,
+                    stake: Amount::from_tokens(30),
                 },
             );
         })
@@ -1232,7 +1292,9 @@ async fn test_all_prediction_outcomes() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1247,7 +1309,9 @@ async fn test_all_prediction_outcomes() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1307,7 +1371,9 @@ async fn test_complete_player_journey() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1412,7 +1478,9 @@ async fn test_multiple_players_interaction() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1423,7 +1491,9 @@ async fn test_multiple_players_interaction() {
             block.with_operation(
                 application_id,
                 Operation::PredictWeeklyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(20),
                 },
             );
         })
@@ -1434,7 +1504,9 @@ async fn test_multiple_players_interaction() {
             block.with_operation(
                 application_id,
                 Operation::PredictMonthlyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Neutral,
+                    stake: Amount::from_tokens(30),
                 },
             );
         })
@@ -1474,7 +1546,12 @@ async fn test_zero_amounts() {
                 application_id,
                 Operation::BuyShares {
                     market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::ZERO,
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -1486,7 +1563,12 @@ async fn test_zero_amounts() {
                 application_id,
                 Operation::SellShares {
                     market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: Amount::ZERO,
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -1535,7 +1617,12 @@ async fn test_maximum_values() {
                 application_id,
                 Operation::BuyShares {
                     market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
                     amount: max_amount,
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
                 },
             );
         })
@@ -1654,6 +1741,7 @@ async fn test_rapid_operations() {
                 block.with_operation(
                     application_id,
                     Operation::PredictDailyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: if i % 3 == 0 {
                             PriceOutcome::Rise
                         } else if i % 3 == 1 {
@@ -1661,6 +1749,7 @@ async fn test_rapid_operations() {
                         } else {
                             PriceOutcome::Neutral
                         },
+                        stake: Amount::from_tokens(10),
                     },
                 );
             })
@@ -1699,6 +1788,11 @@ async fn test_fee_percent_boundaries() {
                     title: "Zero Fee Market".to_string(),
                     amount: Amount::from_tokens(1000),
                     fee_percent: 0,
+                    outcomes: Vec::new(),
+                    market_type: MarketType::FixedRate,
+                    close_time: None,
+                    resolution_time: None,
+                    oracle: None,
                 },
             );
         })
@@ -1713,6 +1807,11 @@ async fn test_fee_percent_boundaries() {
                     title: "Max Fee Market".to_string(),
                     amount: Amount::from_tokens(1000),
                     fee_percent: 100,
+                    outcomes: Vec::new(),
+                    market_type: MarketType::FixedRate,
+                    close_time: None,
+                    resolution_time: None,
+                    oracle: None,
                 },
             );
         })
@@ -1747,7 +1846,9 @@ async fn test_multiple_period_predictions() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -1758,7 +1859,9 @@ async fn test_multiple_period_predictions() {
             block.with_operation(
                 application_id,
                 Operation::PredictWeeklyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(20),
                 },
             );
         })
@@ -1769,7 +1872,9 @@ async fn test_multiple_period_predictions() {
             block.with_operation(
                 application_id,
                 Operation::PredictMonthlyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Neutral,
+                    stake: Amount::from_tokens(30),
                 },
             );
         })
@@ -1893,7 +1998,10 @@ async fn test_graphql_queries() {
     // 7. Test outcome getter functions - getDailyOutcome, getWeeklyOutcome, getMonthlyOutcome
     if let Some(ref pid) = player_id {
         // Test getters when no predictions exist - should return false
-        let query = format!("query {{ getDailyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getDailyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getDailyOutcome").is_some());
         let daily_outcome = response["getDailyOutcome"].as_bool();
@@ -1905,7 +2013,9 @@ async fn test_graphql_queries() {
                 block.with_operation(
                     application_id,
                     Operation::PredictDailyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: PriceOutcome::Rise,
+                        stake: Amount::from_tokens(10),
                     },
                 );
             })
@@ -1916,7 +2026,9 @@ async fn test_graphql_queries() {
                 block.with_operation(
                     application_id,
                     Operation::PredictWeeklyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: PriceOutcome::Fall,
+                        stake: Amount::from_tokens(20),
                     },
                 );
             })
@@ -1927,28 +2039,39 @@ async fn test_graphql_queries() {
                 block.with_operation(
                     application_id,
                     Operation::PredictMonthlyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: PriceOutcome::Neutral,
+                        stake: Amount::from_tokens(30),
                     },
                 );
             })
             .await;
 
         // Test getDailyOutcome - should return false (prediction not resolved yet)
-        let query = format!("query {{ getDailyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getDailyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getDailyOutcome").is_some());
         let daily_outcome = response["getDailyOutcome"].as_bool();
         assert_eq!(daily_outcome, Some(false)); // Should be false since prediction is not resolved yet
 
         // Test getWeeklyOutcome - should return false (prediction not resolved yet)
-        let query = format!("query {{ getWeeklyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getWeeklyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getWeeklyOutcome").is_some());
         let weekly_outcome = response["getWeeklyOutcome"].as_bool();
         assert_eq!(weekly_outcome, Some(false)); // Should be false since prediction is not resolved yet
 
         // Test getMonthlyOutcome - should return false (prediction not resolved yet)
-        let query = format!("query {{ getMonthlyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getMonthlyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getMonthlyOutcome").is_some());
         let monthly_outcome = response["getMonthlyOutcome"].as_bool();
@@ -2004,12 +2127,16 @@ async fn test_prediction_resolution_flow() {
         })
         .await;
 
+    // The player chain registered with the admin chain, so price updates reach it
+    admin_chain.handle_received_messages().await;
+
     // Set initial market price (as admin)
     admin_chain
         .add_block(|block| {
             block.with_operation(
                 application_id,
                 Operation::UpdateMarketPrice {
+                    asset: "BTC".to_string(),
                     price: Amount::from_tokens(50000), // Initial price: $50,000
                 },
             );
@@ -2022,7 +2149,9 @@ async fn test_prediction_resolution_flow() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise, // Predict price will rise
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -2033,7 +2162,9 @@ async fn test_prediction_resolution_flow() {
             block.with_operation(
                 application_id,
                 Operation::PredictWeeklyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall, // Predict price will fall
+                    stake: Amount::from_tokens(20),
                 },
             );
         })
@@ -2044,7 +2175,9 @@ async fn test_prediction_resolution_flow() {
             block.with_operation(
                 application_id,
                 Operation::PredictMonthlyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Neutral, // Predict price will stay neutral
+                    stake: Amount::from_tokens(30),
                 },
             );
         })
@@ -2070,49 +2203,49 @@ async fn test_prediction_resolution_flow() {
 
     if let Some(ref pid) = player_id {
         // Check that predictions exist but are not resolved yet
-        let query = format!("query {{ getDailyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getDailyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getDailyOutcome").is_some());
         let daily_outcome = response["getDailyOutcome"].as_bool();
         assert_eq!(daily_outcome, Some(false)); // Not resolved yet
 
         // Progress time to make at least the daily period expire
-        // Each block advances time, so we add empty blocks to progress time
         // Daily period = 24 hours = 86,400,000,000 microseconds
-        // Note: In Linera tests, each block advances time incrementally
-        // We add many blocks to simulate time passing (24+ hours)
+        // Blocks default to timestamp 0, so the next block is stamped explicitly
         // This ensures the daily period expires so predictions can be resolved
-
-        // Add many empty blocks to progress time past the daily period (24 hours)
-        // Each block advances time, so we need enough blocks to pass 24 hours
-        for _ in 0..100 {
-            admin_chain
-                .add_block(|_block| {
-                    // Empty block to progress time
-                })
-                .await;
-        }
+        let after_daily_period = Timestamp::from(25 * 60 * 60 * 1_000_000);
+        validator.clock().set(after_daily_period);
 
         // Update market price to trigger resolution (price increased to $55,000)
         // This should trigger resolve_expired_predictions for all expired periods
         // Since daily period has expired (24 hours passed), it should resolve
         admin_chain
             .add_block(|block| {
-                block.with_operation(
+                block.with_timestamp(after_daily_period).with_operation(
                     application_id,
                     Operation::UpdateMarketPrice {
+                        asset: "BTC".to_string(),
                         price: Amount::from_tokens(55000), // New price: $55,000 (increased)
                     },
                 );
             })
             .await;
 
+        // The player chain resolves its predictions when the price update arrives
+        chain.handle_received_messages().await;
+
         // Check outcomes - predictions should now be resolved since periods have expired
         // Since price increased from $50,000 to $55,000 and we predicted Rise for daily, it should be correct
 
         // Verify daily prediction was resolved correctly
         // We predicted Rise, price increased from $50,000 to $55,000 → prediction is CORRECT
-        let query = format!("query {{ getDailyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getDailyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getDailyOutcome").is_some());
         let daily_outcome = response["getDailyOutcome"].as_bool();
@@ -2125,7 +2258,10 @@ async fn test_prediction_resolution_flow() {
 
         // Verify weekly prediction
         // We predicted Fall, but price increased → prediction is INCORRECT
-        let query = format!("query {{ getWeeklyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getWeeklyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getWeeklyOutcome").is_some());
         let weekly_outcome = response["getWeeklyOutcome"].as_bool();
@@ -2142,7 +2278,10 @@ async fn test_prediction_resolution_flow() {
 
         // Verify monthly prediction
         // We predicted Neutral, but price increased → prediction is INCORRECT
-        let query = format!("query {{ getMonthlyOutcome(playerId: \"{}\") }}", pid);
+        let query = format!(
+            "query {{ getMonthlyOutcome(playerId: \"{}\", asset: \"BTC\") }}",
+            pid
+        );
         let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
         assert!(response.get("getMonthlyOutcome").is_some());
         let monthly_outcome = response["getMonthlyOutcome"].as_bool();
@@ -2515,7 +2654,9 @@ async fn test_cross_chain_price_updates() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -2526,7 +2667,9 @@ async fn test_cross_chain_price_updates() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -2538,6 +2681,7 @@ async fn test_cross_chain_price_updates() {
             block.with_operation(
                 application_id,
                 Operation::UpdateMarketPrice {
+                    asset: "BTC".to_string(),
                     price: Amount::from_tokens(55000),
                 },
             );
@@ -2639,7 +2783,9 @@ async fn test_periodic_leaderboard_broadcasting() {
                 block.with_operation(
                     application_id,
                     Operation::PredictDailyOutcome {
+                        asset: "BTC".to_string(),
                         outcome: PriceOutcome::Rise,
+                        stake: Amount::from_tokens(10),
                     },
                 );
             })
@@ -2794,7 +2940,9 @@ async fn test_leaderboard_broadcast_time_threshold() {
             block.with_operation(
                 application_id,
                 Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
                 },
             );
         })
@@ -2811,7 +2959,9 @@ async fn test_leaderboard_broadcast_time_threshold() {
             block.with_operation(
                 application_id,
                 Operation::PredictWeeklyOutcome {
+                    asset: "BTC".to_string(),
                     outcome: PriceOutcome::Fall,
+                    stake: Amount::from_tokens(20),
                 },
             );
         })
@@ -2846,3 +2996,3532 @@ async fn test_leaderboard_broadcast_time_threshold() {
         );
     }
 }
+
+// ============================================================================
+// Operation Response Tests
+// ============================================================================
+
+/// Decode the first operation response of a block
+macro_rules! operation_response {
+    ($certificate:expr) => {
+        linera_sdk::bcs::from_bytes::<OperationResponse>(
+            &$certificate.inner().block().body.operation_results[0].0,
+        )
+        .expect("Failed to decode operation response")
+    };
+}
+
+/// Submit an operation in a block of its own and decode its response
+async fn execute(
+    chain: &ActiveChain,
+    application_id: ApplicationId<PredictiveManagerAbi>,
+    operation: Operation,
+) -> OperationResponse {
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(application_id, operation);
+        })
+        .await;
+    operation_response!(certificate)
+}
+
+/// Sign the chain's next blocks as another player
+/// Returns that player's id
+fn sign_as(chain: &mut ActiveChain, key_pair: &AccountSecretKey) -> AccountOwner {
+    chain.set_key_pair(key_pair.copy());
+    AccountOwner::from(key_pair.public())
+}
+
+/// Read a player's current point balance
+async fn token_balance(
+    chain: &ActiveChain,
+    application_id: ApplicationId<PredictiveManagerAbi>,
+    player_id: AccountOwner,
+) -> Amount {
+    let query = format!(
+        "query {{ player(playerId: \"{}\") {{ tokenBalance }} }}",
+        player_id
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    serde_json::from_value(response["player"]["tokenBalance"].clone())
+        .expect("Failed to decode balance")
+}
+
+/// Parse a decimal point amount, such as "0.45"
+fn points(amount: &str) -> Amount {
+    amount.parse().expect("Invalid amount")
+}
+
+/// Test that successful operations return a receipt and failures return the error
+#[tokio::test(flavor = "multi_thread")]
+async fn test_operation_responses() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    // Registration succeeds and reports the initial balance
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("TestPlayer".to_string()),
+                },
+            );
+        })
+        .await;
+    let receipt = operation_response!(certificate).expect("Registration should succeed");
+    assert_eq!(receipt.token_balance, Some(Amount::from_tokens(100)));
+
+    // Registering twice surfaces the contract error
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("TestPlayer".to_string()),
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::PlayerAlreadyExists)
+    );
+
+    // Buying from a missing market fails with MarketNotFound
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::BuyShares {
+                    market_id: 42,
+                    chain_id: None,
+                    outcome_id: None,
+                    amount: Amount::from_tokens(10),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::MarketNotFound)
+    );
+
+    // Predictions report the period they were recorded for
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
+                    outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
+                },
+            );
+        })
+        .await;
+    let receipt = operation_response!(certificate).expect("Prediction should succeed");
+    assert_eq!(receipt.period_start, Some(Timestamp::from(0)));
+}
+
+/// Test that strict mode rejects a block containing a failing operation
+#[tokio::test(flavor = "multi_thread")]
+async fn test_strict_operations_reject_block() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        strict_operations: true,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    // A valid operation is accepted as usual
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("TestPlayer".to_string()),
+                },
+            );
+        })
+        .await;
+
+    // The daily reward cooldown has not passed, so the block is rejected
+    let result = chain
+        .try_add_block(|block| {
+            block.with_operation(application_id, Operation::ClaimDailyReward);
+        })
+        .await;
+    assert!(result.is_err());
+
+    // Supply is unchanged by the rejected block
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { totalSupply }")
+        .await;
+    assert_eq!(response["totalSupply"].as_str(), Some("100."));
+}
+
+// ============================================================================
+// Market Lifecycle Tests
+// ============================================================================
+
+/// Test closing, cancelling and resolving markets that don't exist
+#[tokio::test(flavor = "multi_thread")]
+async fn test_market_lifecycle_nonexistent_market() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    for operation in [
+        Operation::CloseMarket { market_id: 42 },
+        Operation::CancelMarket { market_id: 42 },
+        Operation::ResolveMarket {
+            market_id: 42,
+            winning_outcome: None,
+        },
+    ] {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::MarketNotFound)
+        );
+    }
+}
+
+/// Test resolving a market without admin privileges
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resolve_market_unauthorized() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut admin_chain = validator.new_chain().await;
+
+    let application_id = admin_chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    // Non-admin player on another chain
+    let chain = validator.new_chain().await;
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Player".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ResolveMarket {
+                    market_id: 0,
+                    winning_outcome: None,
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::NotAdmin)
+    );
+}
+
+/// Test that outcome share trades on a missing market fail with MarketNotFound
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outcome_shares_nonexistent_market() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Trader".to_string()),
+                },
+            );
+        })
+        .await;
+
+    for operation in [
+        Operation::BuyShares {
+            market_id: 42,
+            chain_id: None,
+            outcome_id: Some(1),
+            amount: Amount::from_tokens(10),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+        Operation::SellShares {
+            market_id: 42,
+            chain_id: None,
+            outcome_id: Some(1),
+            amount: Amount::from_tokens(10),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+    ] {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::MarketNotFound)
+        );
+    }
+}
+
+/// Test an LMSR market from first trade to payout, with fees charged on fractions of a point
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outcome_market_lifecycle() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let yes_key = AccountSecretKey::generate();
+    let no_key = AccountSecretKey::generate();
+    for key_pair in [&admin_key, &yes_key, &no_key] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::RegisterPlayer { display_name: None },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 10,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    let trade = |side: OrderSide, outcome_id, shares: &str| match side {
+        OrderSide::Buy => Operation::BuyShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: Some(outcome_id),
+            amount: points(shares),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+        OrderSide::Sell => Operation::SellShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: Some(outcome_id),
+            amount: points(shares),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+    };
+    // Outcome prices and the total and fee of the latest trade
+    let market_query = "query { outcomePrices(marketId: 0) { price } \
+        trades(marketId: 0, limit: 200) { trades { total fee } } \
+        treasury { balance } }";
+    let decode = |value: &serde_json::Value| -> u128 {
+        serde_json::from_value::<Amount>(value.clone())
+            .expect("Failed to decode amount")
+            .into()
+    };
+    let prices = |response: &serde_json::Value| -> Vec<u128> {
+        response["outcomePrices"]
+            .as_array()
+            .expect("Missing prices")
+            .iter()
+            .map(|outcome| decode(&outcome["price"]))
+            .collect()
+    };
+    let assert_prices_sum_to_one = |prices: &[u128]| {
+        let sum: u128 = prices.iter().sum();
+        assert!(sum.abs_diff(10u128.pow(18)) <= prices.len() as u128);
+    };
+
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, market_query).await;
+    let initial_prices = prices(&response);
+    assert_eq!(initial_prices[0], initial_prices[1]);
+    assert_prices_sum_to_one(&initial_prices);
+
+    // Half a share costs less than a point, and still pays the fee and the platform's cut
+    let yes_trader = sign_as(&mut chain, &yes_key);
+    let receipt = execute(&chain, application_id, trade(OrderSide::Buy, 0, "0.5"))
+        .await
+        .expect("Buy failed");
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, market_query).await;
+    let last_trade = &response["trades"]["trades"][0];
+    let (cost, fee) = (decode(&last_trade["total"]), decode(&last_trade["fee"]));
+    assert!(cost < 10u128.pow(18));
+    assert!(fee > 0);
+    assert_eq!(fee, cost / 10);
+    assert_eq!(receipt.fees_charged, Amount::from_attos(fee));
+    assert_eq!(decode(&response["treasury"]["balance"]), fee * 2 / 100);
+
+    let buy_prices = prices(&response);
+    assert!(buy_prices[0] > buy_prices[1]);
+    assert_prices_sum_to_one(&buy_prices);
+
+    // Buying the other outcome moves the prices back, and they still sum to one
+    let no_trader = sign_as(&mut chain, &no_key);
+    let response = execute(&chain, application_id, trade(OrderSide::Buy, 1, "2")).await;
+    assert!(response.is_ok());
+    sign_as(&mut chain, &yes_key);
+    let response = execute(&chain, application_id, trade(OrderSide::Buy, 0, "2")).await;
+    assert!(response.is_ok());
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, market_query).await;
+    assert_prices_sum_to_one(&prices(&response));
+
+    // Selling a share pays its proceeds less the fee
+    let yes_balance = token_balance(&chain, application_id, yes_trader).await;
+    let response = execute(&chain, application_id, trade(OrderSide::Sell, 0, "1")).await;
+    assert!(response.is_ok());
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, market_query).await;
+    let last_trade = &response["trades"]["trades"][3];
+    let (proceeds, fee) = (decode(&last_trade["total"]), decode(&last_trade["fee"]));
+    assert_eq!(fee, proceeds / 10);
+    assert_eq!(
+        token_balance(&chain, application_id, yes_trader).await,
+        yes_balance.saturating_add(Amount::from_attos(proceeds - fee))
+    );
+    let sell_prices = prices(&response);
+    assert!(sell_prices[0] < buy_prices[0]);
+    assert_prices_sum_to_one(&sell_prices);
+
+    // Every winning share pays one point, losing shares pay nothing
+    let yes_balance = token_balance(&chain, application_id, yes_trader).await;
+    let no_balance = token_balance(&chain, application_id, no_trader).await;
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::ResolveMarket {
+            market_id: 0,
+            winning_outcome: Some(0),
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    assert_eq!(
+        token_balance(&chain, application_id, yes_trader).await,
+        yes_balance.saturating_add(points("1.5"))
+    );
+    assert_eq!(
+        token_balance(&chain, application_id, no_trader).await,
+        no_balance
+    );
+}
+
+/// Test that trades are rejected once their deadline has passed
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trade_deadline_expired() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Trader".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let deadline = Timestamp::from(60 * 1_000_000);
+    validator.clock().set(Timestamp::from(2 * 60 * 1_000_000));
+
+    let operations = [
+        Operation::BuyShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: None,
+            amount: Amount::from_tokens(10),
+            max_payment: None,
+            min_received: None,
+            deadline: Some(deadline),
+        },
+        Operation::SellShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: None,
+            amount: Amount::from_tokens(10),
+            max_payment: None,
+            min_received: None,
+            deadline: Some(deadline),
+        },
+    ];
+    for operation in operations {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::DeadlineExpired)
+        );
+    }
+}
+
+/// Test that trades crossing their slippage limits are rejected and leave the balance untouched
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trade_slippage_limits() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 10,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: None,
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    let trader_key = AccountSecretKey::generate();
+    let trader = sign_as(&mut chain, &trader_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    let buy = |max_payment: &str| Operation::BuyShares {
+        market_id: 0,
+        chain_id: None,
+        outcome_id: Some(0),
+        amount: Amount::from_tokens(2),
+        max_payment: Some(points(max_payment)),
+        min_received: None,
+        deadline: None,
+    };
+    let sell = |min_received: &str| Operation::SellShares {
+        market_id: 0,
+        chain_id: None,
+        outcome_id: Some(0),
+        amount: Amount::ONE,
+        max_payment: None,
+        min_received: Some(points(min_received)),
+        deadline: None,
+    };
+    let position_query = "query { market(marketId: 0) { positions } }";
+
+    // Two shares at about half a point each, plus the 10% fee, cost more than one point
+    let balance = token_balance(&chain, application_id, trader).await;
+    let response = execute(&chain, application_id, buy("1")).await;
+    assert_eq!(response, Err(OperationError::SlippageExceeded));
+    assert_eq!(token_balance(&chain, application_id, trader).await, balance);
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, position_query).await;
+    assert!(response["market"]["positions"][trader.to_string()].is_null());
+
+    let receipt = execute(&chain, application_id, buy("10"))
+        .await
+        .expect("Buy within its limit failed");
+    assert!(
+        receipt.token_balance.expect("Missing balance") >= balance.saturating_sub(points("10"))
+    );
+
+    // One share sells for less than a point, so asking for a whole point is refused
+    let balance = token_balance(&chain, application_id, trader).await;
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, position_query).await;
+    let position = response["market"]["positions"][trader.to_string()].clone();
+    let response = execute(&chain, application_id, sell("1")).await;
+    assert_eq!(response, Err(OperationError::SlippageExceeded));
+    assert_eq!(token_balance(&chain, application_id, trader).await, balance);
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, position_query).await;
+    assert_eq!(
+        response["market"]["positions"][trader.to_string()],
+        position
+    );
+
+    let response = execute(&chain, application_id, sell("0.1")).await;
+    assert!(response.is_ok());
+    assert!(
+        token_balance(&chain, application_id, trader).await
+            >= balance.saturating_add(points("0.1"))
+    );
+}
+
+/// Test that a market closes at its close time even when the operation that finds it overdue fails
+#[tokio::test(flavor = "multi_thread")]
+async fn test_market_closes_at_close_time() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let hour = 60 * 60 * 1_000_000;
+    let admin_key = chain.key_pair().copy();
+    sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 0,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: Some(Timestamp::from(hour)),
+            resolution_time: None,
+            oracle: None,
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // The trader leaves two bids on the book before the market closes
+    let trader_key = AccountSecretKey::generate();
+    let trader = sign_as(&mut chain, &trader_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::PlaceLimitOrder {
+            market_id: 0,
+            outcome_id: 0,
+            side: OrderSide::Buy,
+            price: points("0.2"),
+            quantity: Amount::ONE,
+        },
+        Operation::PlaceLimitOrder {
+            market_id: 0,
+            outcome_id: 0,
+            side: OrderSide::Buy,
+            price: points("0.1"),
+            quantity: Amount::from_tokens(2),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+    let trader_balance = token_balance(&chain, application_id, trader).await;
+
+    // A buy after the close time is rejected, but the market still closes and releases both bids once
+    validator.clock().set(Timestamp::from(2 * hour));
+    let buy = || Operation::BuyShares {
+        market_id: 0,
+        chain_id: None,
+        outcome_id: Some(0),
+        amount: Amount::ONE,
+        max_payment: None,
+        min_received: None,
+        deadline: None,
+    };
+    let response = execute(&chain, application_id, buy()).await;
+    assert_eq!(response, Err(OperationError::MarketNotActive));
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance.saturating_add(points("0.4"))
+    );
+
+    let query = format!(
+        "query {{ market(marketId: 0) {{ status }} \
+            orderBook(marketId: 0, outcomeId: 0) {{ bids {{ id }} }} \
+            player(playerId: \"{}\") {{ activeMarkets }} }}",
+        AccountOwner::from(admin_key.public())
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["market"]["status"], "CLOSED");
+    assert_eq!(
+        response["orderBook"]["bids"].as_array().map(Vec::len),
+        Some(0)
+    );
+    assert_eq!(
+        response["player"]["activeMarkets"].as_array().map(Vec::len),
+        Some(0)
+    );
+
+    // Nothing trades on the closed market any more, and its released orders are gone
+    let operations = [
+        (
+            Operation::CancelOrder {
+                market_id: 0,
+                order_id: 0,
+            },
+            OperationError::OrderNotFound,
+        ),
+        (buy(), OperationError::MarketNotActive),
+        (
+            Operation::PlaceLimitOrder {
+                market_id: 0,
+                outcome_id: 0,
+                side: OrderSide::Buy,
+                price: points("0.2"),
+                quantity: Amount::ONE,
+            },
+            OperationError::MarketNotActive,
+        ),
+    ];
+    for (operation, error) in operations {
+        let response = execute(&chain, application_id, operation).await;
+        assert_eq!(response, Err(error));
+    }
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance.saturating_add(points("0.4"))
+    );
+}
+
+/// Test paging through a market's trade log and filtering it by player and time
+#[tokio::test(flavor = "multi_thread")]
+async fn test_trade_log_pages() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let first_key = AccountSecretKey::generate();
+    let second_key = AccountSecretKey::generate();
+    for key_pair in [&admin_key, &first_key, &second_key] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::RegisterPlayer { display_name: None },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 0,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    // Trades 0 to 4 go to the market an hour apart; trade 5 fills the second player's ask
+    let first = AccountOwner::from(first_key.public());
+    let second = AccountOwner::from(second_key.public());
+    let buy = |outcome_id| Operation::BuyShares {
+        market_id: 0,
+        chain_id: None,
+        outcome_id: Some(outcome_id),
+        amount: Amount::ONE,
+        max_payment: None,
+        min_received: None,
+        deadline: None,
+    };
+    let trades = [
+        (&first_key, buy(0)),
+        (&second_key, buy(1)),
+        (&first_key, buy(0)),
+        (&second_key, buy(0)),
+        (
+            &first_key,
+            Operation::SellShares {
+                market_id: 0,
+                chain_id: None,
+                outcome_id: Some(0),
+                amount: Amount::ONE,
+                max_payment: None,
+                min_received: None,
+                deadline: None,
+            },
+        ),
+        (
+            &second_key,
+            Operation::PlaceLimitOrder {
+                market_id: 0,
+                outcome_id: 0,
+                side: OrderSide::Sell,
+                price: points("0.5"),
+                quantity: Amount::ONE,
+            },
+        ),
+        (
+            &first_key,
+            Operation::PlaceMarketOrder {
+                market_id: 0,
+                outcome_id: 0,
+                side: OrderSide::Buy,
+                quantity: Amount::ONE,
+            },
+        ),
+    ];
+    let hour = 60 * 60 * 1_000_000;
+    for (hours, (key_pair, operation)) in (1..).zip(trades) {
+        validator.clock().set(Timestamp::from(hours.min(6) * hour));
+        sign_as(&mut chain, key_pair);
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    let page = |arguments: &str| {
+        format!(
+            "query {{ trades(marketId: 0, {}) {{ trades {{ id playerId counterparty }} nextCursor }} }}",
+            arguments
+        )
+    };
+    let ids = |response: &serde_json::Value| -> Vec<u64> {
+        response["trades"]["trades"]
+            .as_array()
+            .expect("Missing trades")
+            .iter()
+            .map(|trade| trade["id"].as_u64().expect("Missing trade id"))
+            .collect()
+    };
+
+    // Following the cursor walks the whole log, two trades at a time
+    let mut cursor = None;
+    let mut pages = Vec::new();
+    loop {
+        let arguments = match cursor {
+            Some(after) => format!("limit: 2, after: {}", after),
+            None => "limit: 2".to_string(),
+        };
+        let QueryOutcome { response, .. } =
+            chain.graphql_query(application_id, &page(&arguments)).await;
+        pages.push(ids(&response));
+        cursor = response["trades"]["nextCursor"].as_u64();
+        if cursor.is_none() {
+            break;
+        }
+    }
+    assert_eq!(pages, vec![vec![0, 1], vec![2, 3], vec![4, 5]]);
+
+    // A player's trades include the order book fill where they were the maker
+    let arguments = format!("playerId: \"{}\", limit: 2", second);
+    let QueryOutcome { response, .. } =
+        chain.graphql_query(application_id, &page(&arguments)).await;
+    assert_eq!(ids(&response), vec![1, 3]);
+    assert_eq!(response["trades"]["nextCursor"], 3);
+    let arguments = format!("playerId: \"{}\", after: 3", second);
+    let QueryOutcome { response, .. } =
+        chain.graphql_query(application_id, &page(&arguments)).await;
+    assert_eq!(ids(&response), vec![5]);
+    assert_eq!(
+        response["trades"]["trades"][0]["playerId"],
+        first.to_string()
+    );
+    assert_eq!(
+        response["trades"]["trades"][0]["counterparty"],
+        second.to_string()
+    );
+    assert!(response["trades"]["nextCursor"].is_null());
+
+    // The time range is inclusive at both ends, and ends the page once it is passed
+    let arguments = format!("from: {}, to: {}", 2 * hour, 4 * hour);
+    let QueryOutcome { response, .. } =
+        chain.graphql_query(application_id, &page(&arguments)).await;
+    assert_eq!(ids(&response), vec![1, 2, 3]);
+    assert!(response["trades"]["nextCursor"].is_null());
+}
+
+/// Test that admin price updates are aggregated into candles of the price feed
+#[tokio::test(flavor = "multi_thread")]
+async fn test_price_feed_candles() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    for price in [50_000, 52_000, 49_000] {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::UpdateMarketPrice {
+                        asset: "BTC".to_string(),
+                        price: Amount::from_tokens(price),
+                    },
+                );
+            })
+            .await;
+        assert!(operation_response!(certificate).is_ok());
+    }
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { candles(asset: \"BTC\", resolution: MINUTE, from: 0, to: 0) { open high low close tradeCount } }",
+        )
+        .await;
+    let candles = response["candles"]
+        .as_array()
+        .expect("candles should be a list");
+    assert_eq!(candles.len(), 1);
+    assert_eq!(candles[0]["tradeCount"], 3);
+    assert_eq!(
+        candles[0]["open"],
+        serde_json::json!(Amount::from_tokens(50_000))
+    );
+    assert_eq!(
+        candles[0]["high"],
+        serde_json::json!(Amount::from_tokens(52_000))
+    );
+    assert_eq!(
+        candles[0]["low"],
+        serde_json::json!(Amount::from_tokens(49_000))
+    );
+    assert_eq!(
+        candles[0]["close"],
+        serde_json::json!(Amount::from_tokens(49_000))
+    );
+}
+
+// ============================================================================
+// Order Book Tests
+// ============================================================================
+
+/// Test order book operations that must fail before touching any market
+#[tokio::test(flavor = "multi_thread")]
+async fn test_order_book_errors() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Trader".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let cases = [
+        // A share never pays more than one point
+        (
+            Operation::PlaceLimitOrder {
+                market_id: 0,
+                outcome_id: 0,
+                side: OrderSide::Buy,
+                price: Amount::from_tokens(2),
+                quantity: Amount::from_tokens(10),
+            },
+            OperationError::InvalidPrice,
+        ),
+        (
+            Operation::PlaceMarketOrder {
+                market_id: 42,
+                outcome_id: 0,
+                side: OrderSide::Sell,
+                quantity: Amount::from_tokens(10),
+            },
+            OperationError::MarketNotFound,
+        ),
+        (
+            Operation::CancelOrder {
+                market_id: 42,
+                order_id: 7,
+            },
+            OperationError::OrderNotFound,
+        ),
+    ];
+    for (operation, error) in cases {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(operation_response!(certificate), Err(error));
+    }
+
+    // The book of a market without orders is empty
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { orderBook(marketId: 42, outcomeId: 0) { bids { id } askLevels { price } } }",
+        )
+        .await;
+    assert_eq!(
+        response["orderBook"]["bids"].as_array().map(Vec::len),
+        Some(0)
+    );
+    assert_eq!(
+        response["orderBook"]["askLevels"].as_array().map(Vec::len),
+        Some(0)
+    );
+}
+
+/// Test order matching: priority, partial fills, self-trades, escrow dust and released orders
+#[tokio::test(flavor = "multi_thread")]
+async fn test_order_book_matching() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let first_seller_key = AccountSecretKey::generate();
+    let second_seller_key = AccountSecretKey::generate();
+    let buyer_key = AccountSecretKey::generate();
+    for key_pair in [
+        &admin_key,
+        &first_seller_key,
+        &second_seller_key,
+        &buyer_key,
+    ] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::RegisterPlayer { display_name: None },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    // The admin opens two Yes/No markets, the sellers buy "Yes" shares in the first
+    sign_as(&mut chain, &admin_key);
+    for _ in 0..2 {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::CreateMarket {
+                title: "Will it rain?".to_string(),
+                amount: Amount::from_tokens(20),
+                fee_percent: 0,
+                outcomes: vec!["Yes".to_string(), "No".to_string()],
+                market_type: MarketType::Prediction,
+                close_time: None,
+                resolution_time: None,
+                oracle: None,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    for key_pair in [&first_seller_key, &second_seller_key] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::BuyShares {
+                market_id: 0,
+                chain_id: None,
+                outcome_id: Some(0),
+                amount: Amount::from_tokens(5),
+                max_payment: None,
+                min_received: None,
+                deadline: None,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    let first_seller = AccountOwner::from(first_seller_key.public());
+    let position_query = "query { market(marketId: 0) { positions } }";
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, position_query).await;
+    let first_seller_position = response["market"]["positions"][first_seller.to_string()].clone();
+
+    let limit_order = |side, price: &str, quantity: &str| Operation::PlaceLimitOrder {
+        market_id: 0,
+        outcome_id: 0,
+        side,
+        price: points(price),
+        quantity: points(quantity),
+    };
+
+    // Orders 0 to 3 rest as asks, sorted by price and then by time
+    sign_as(&mut chain, &first_seller_key);
+    let response = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Sell, "0.6", "2"),
+    )
+    .await;
+    assert!(response.is_ok());
+    let second_seller = sign_as(&mut chain, &second_seller_key);
+    for (price, quantity) in [("0.5", "1"), ("0.5", "2"), ("0.45", "1")] {
+        let response = execute(
+            &chain,
+            application_id,
+            limit_order(OrderSide::Sell, price, quantity),
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    // A bid that would cross the seller's own asks is rejected
+    let response = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Buy, "0.5", "1"),
+    )
+    .await;
+    assert_eq!(response, Err(OperationError::SelfCrossingOrder));
+
+    // The buyer takes the cheapest ask, then the older of the two at 0.5, and part of the newer
+    let buyer = sign_as(&mut chain, &buyer_key);
+    let buyer_balance = token_balance(&chain, application_id, buyer).await;
+    let seller_balance = token_balance(&chain, application_id, second_seller).await;
+    let receipt = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Buy, "0.55", "3"),
+    )
+    .await
+    .expect("Limit order failed");
+    assert_eq!(receipt.filled_quantity, Some(Amount::from_tokens(3)));
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance.saturating_sub(points("1.45"))
+    );
+    assert_eq!(
+        token_balance(&chain, application_id, second_seller).await,
+        seller_balance.saturating_add(points("1.45"))
+    );
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { trades(marketId: 0, after: 1) { trades { counterparty amount price } } }",
+        )
+        .await;
+    let fills: Vec<_> = response["trades"]["trades"]
+        .as_array()
+        .expect("Missing trades")
+        .iter()
+        .map(|trade| (trade["amount"].clone(), trade["price"].clone()))
+        .collect();
+    assert_eq!(
+        fills,
+        vec![
+            ("1.".into(), "0.45".into()),
+            ("1.".into(), "0.5".into()),
+            ("1.".into(), "0.5".into()),
+        ]
+    );
+    assert_eq!(
+        response["trades"]["trades"][0]["counterparty"],
+        second_seller.to_string()
+    );
+
+    // The next bid fills what is left of the partly filled ask and rests with the rest
+    let buyer_balance = token_balance(&chain, application_id, buyer).await;
+    let receipt = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Buy, "0.55", "2"),
+    )
+    .await
+    .expect("Limit order failed");
+    assert_eq!(receipt.filled_quantity, Some(Amount::ONE));
+    let resting_bid = receipt.order_id.expect("Missing order id");
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance.saturating_sub(points("1.05"))
+    );
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { orderBook(marketId: 0, outcomeId: 0) { bids { id remaining } asks { id remaining } } }",
+        )
+        .await;
+    let book = &response["orderBook"];
+    assert_eq!(book["bids"][0]["id"], resting_bid);
+    assert_eq!(book["bids"][0]["remaining"], "1.");
+    assert_eq!(book["asks"].as_array().map(Vec::len), Some(1));
+    assert_eq!(book["asks"][0]["id"], 0);
+
+    // A market order skips the taker's own orders, and fails if nothing else is left
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::PlaceMarketOrder {
+            market_id: 0,
+            outcome_id: 0,
+            side: OrderSide::Sell,
+            quantity: Amount::ONE,
+        },
+    )
+    .await;
+    assert_eq!(response, Err(OperationError::NoMatchingOrders));
+
+    sign_as(&mut chain, &second_seller_key);
+    let response = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Buy, "0.3", "1"),
+    )
+    .await;
+    assert!(response.is_ok());
+    sign_as(&mut chain, &buyer_key);
+    let buyer_balance = token_balance(&chain, application_id, buyer).await;
+    let receipt = execute(
+        &chain,
+        application_id,
+        Operation::PlaceMarketOrder {
+            market_id: 0,
+            outcome_id: 0,
+            side: OrderSide::Sell,
+            quantity: Amount::ONE,
+        },
+    )
+    .await
+    .expect("Market order failed");
+    assert_eq!(receipt.filled_quantity, Some(Amount::ONE));
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance.saturating_add(points("0.3"))
+    );
+
+    // Cancelling the resting bid returns its escrow
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::CancelOrder {
+            market_id: 0,
+            order_id: resting_bid,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance.saturating_add(points("0.85"))
+    );
+
+    // Fills of a third of a point round down, and the dust left in escrow goes back to the buyer
+    let buyer_balance = token_balance(&chain, application_id, buyer).await;
+    let response = execute(
+        &chain,
+        application_id,
+        limit_order(OrderSide::Buy, "0.333333333333333333", "1.5"),
+    )
+    .await;
+    assert!(response.is_ok());
+    sign_as(&mut chain, &second_seller_key);
+    for _ in 0..3 {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::PlaceMarketOrder {
+                market_id: 0,
+                outcome_id: 0,
+                side: OrderSide::Sell,
+                quantity: points("0.5"),
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance.saturating_sub(points("0.499999999999999998"))
+    );
+
+    // Closing or cancelling a market releases its resting orders
+    sign_as(&mut chain, &buyer_key);
+    let buyer_balance = token_balance(&chain, application_id, buyer).await;
+    for market_id in [0, 1] {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::PlaceLimitOrder {
+                market_id,
+                outcome_id: 0,
+                side: OrderSide::Buy,
+                price: points("0.2"),
+                quantity: Amount::ONE,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::CloseMarket { market_id: 0 },
+        Operation::CancelMarket { market_id: 1 },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+    assert_eq!(
+        token_balance(&chain, application_id, buyer).await,
+        buyer_balance
+    );
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { orderBook(marketId: 0, outcomeId: 0) { bids { id } asks { id } } }",
+        )
+        .await;
+    assert_eq!(
+        response["orderBook"]["bids"].as_array().map(Vec::len),
+        Some(0)
+    );
+    assert_eq!(
+        response["orderBook"]["asks"].as_array().map(Vec::len),
+        Some(0)
+    );
+
+    // The first seller's ask never filled, so their position is back where it started
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, position_query).await;
+    assert_eq!(
+        response["market"]["positions"][first_seller.to_string()],
+        first_seller_position
+    );
+}
+
+/// Test that config updates are validated and visible through the config query
+#[tokio::test(flavor = "multi_thread")]
+async fn test_update_game_config_validation() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    // The deployer became the admin
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { config { admin minCreatorLevel platformFeePercent fixedRateExchange } }",
+        )
+        .await;
+    assert_eq!(response["config"]["minCreatorLevel"].as_u64(), Some(5));
+    assert_eq!(response["config"]["platformFeePercent"].as_u64(), Some(2));
+    assert_eq!(response["config"]["fixedRateExchange"].as_u64(), Some(10));
+    let admin = serde_json::from_value(response["config"]["admin"].clone())
+        .expect("Failed to decode admin");
+
+    let invalid_configs = [
+        GameConfig {
+            admin,
+            fixed_rate_exchange: 0,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            platform_fee_percent: 101,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            fee_divisor: Amount::ZERO,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            prediction_rake_percent: 101,
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            assets: vec!["BTC".to_string(), "BTC".to_string()],
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            assets: Vec::new(),
+            ..GameConfig::default()
+        },
+        GameConfig {
+            admin,
+            weekly_stake: StakeLimits {
+                min_stake: Amount::from_tokens(600),
+                max_stake: Amount::from_tokens(500),
+                reward_percent: 100,
+            },
+            ..GameConfig::default()
+        },
+        // Dropping the admin would lock the config for good
+        GameConfig::default(),
+    ];
+    for config in invalid_configs {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, Operation::UpdateGameConfig { config });
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::InvalidConfig)
+        );
+    }
+
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::UpdateGameConfig {
+                    config: GameConfig {
+                        admin,
+                        min_creator_level: 1,
+                        min_creator_balance: Amount::ZERO,
+                        ..GameConfig::default()
+                    },
+                },
+            );
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { config { minCreatorLevel minCreatorBalance } }",
+        )
+        .await;
+    assert_eq!(response["config"]["minCreatorLevel"].as_u64(), Some(1));
+    assert_eq!(
+        response["config"]["minCreatorBalance"],
+        serde_json::json!(Amount::ZERO)
+    );
+}
+
+/// Test that an invalid config is refused at instantiation, not only on update
+#[tokio::test(flavor = "multi_thread")]
+#[should_panic]
+async fn test_instantiate_rejects_invalid_config() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    // A zero exchange rate would divide by zero on the first fixed-rate trade
+    let config = GameConfig {
+        fixed_rate_exchange: 0,
+        ..GameConfig::default()
+    };
+    chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+}
+
+/// Test that the shipped config.json still deserializes, with the keys it lacks defaulted
+#[test]
+fn test_shipped_config_deserializes() {
+    let config: GameConfig =
+        serde_json::from_str(include_str!("../config.json")).expect("Failed to parse config.json");
+    let default = GameConfig::default();
+    assert_eq!(config.initial_player_tokens, default.initial_player_tokens);
+    assert_eq!(config.min_creator_level, default.min_creator_level);
+    assert_eq!(config.dispute_period, default.dispute_period);
+    assert_eq!(
+        config.max_airdrop_recipients,
+        default.max_airdrop_recipients
+    );
+    assert_eq!(config.daily_stake.max_stake, default.daily_stake.max_stake);
+    assert_eq!(
+        config.prediction_rake_percent,
+        default.prediction_rake_percent
+    );
+    assert_eq!(config.assets, default.assets);
+}
+
+/// Test that invalid point transfers are rejected without moving points
+#[tokio::test(flavor = "multi_thread")]
+async fn test_transfer_points_errors() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Sender".to_string()),
+                },
+            );
+        })
+        .await;
+
+    // The deployer is both the admin and the registered player
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { config { admin } }")
+        .await;
+    let sender: AccountOwner = serde_json::from_value(response["config"]["admin"].clone())
+        .expect("Failed to decode sender");
+    let balance_query = format!(
+        "query {{ player(playerId: \"{}\") {{ tokenBalance }} }}",
+        sender
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &balance_query).await;
+    let balance = response["player"]["tokenBalance"].clone();
+
+    let cases = [
+        (
+            sender,
+            Amount::from_tokens(1),
+            None,
+            OperationError::InvalidTransfer,
+        ),
+        (
+            AccountOwner::CHAIN,
+            Amount::ZERO,
+            None,
+            OperationError::InvalidTransfer,
+        ),
+        (
+            AccountOwner::CHAIN,
+            Amount::from_tokens(1),
+            Some("x".repeat(281)),
+            OperationError::InvalidTransfer,
+        ),
+        (
+            AccountOwner::CHAIN,
+            Amount::from_tokens(1_000_000),
+            None,
+            OperationError::InsufficientBalance,
+        ),
+        // Nobody is registered under this owner on any chain
+        (
+            AccountOwner::CHAIN,
+            Amount::from_tokens(1),
+            Some("tip".to_string()),
+            OperationError::PlayerNotFound,
+        ),
+    ];
+    for (to, amount, memo, error) in cases {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::TransferPoints { to, amount, memo },
+                );
+            })
+            .await;
+        assert_eq!(operation_response!(certificate), Err(error));
+    }
+
+    // Failed transfers leave the balance untouched
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &balance_query).await;
+    assert_eq!(response["player"]["tokenBalance"], balance);
+}
+
+/// Test that minted and airdropped points reach their recipients
+#[tokio::test(flavor = "multi_thread")]
+async fn test_mint_points_and_airdrop() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Admin".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { config { admin } }")
+        .await;
+    let admin: AccountOwner = serde_json::from_value(response["config"]["admin"].clone())
+        .expect("Failed to decode admin");
+
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::MintPoints {
+                    recipient: admin,
+                    amount: Amount::from_tokens(500),
+                },
+            );
+        })
+        .await;
+    let balance = operation_response!(certificate)
+        .expect("Mint should succeed")
+        .token_balance
+        .expect("Receipt should carry the balance");
+
+    let cases = [
+        (Vec::new(), OperationError::InvalidAirdrop),
+        (
+            vec![AirdropCredit {
+                player_id: admin,
+                amount: Amount::ZERO,
+            }],
+            OperationError::InvalidAirdrop,
+        ),
+        (
+            vec![
+                AirdropCredit {
+                    player_id: admin,
+                    amount: Amount::from_tokens(10),
+                },
+                AirdropCredit {
+                    player_id: AccountOwner::CHAIN,
+                    amount: Amount::from_tokens(10),
+                },
+            ],
+            OperationError::PlayerNotFound,
+        ),
+    ];
+    for (credits, error) in cases {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, Operation::Airdrop { credits });
+            })
+            .await;
+        assert_eq!(operation_response!(certificate), Err(error));
+    }
+
+    // A player listed twice is credited twice
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::Airdrop {
+                    credits: vec![
+                        AirdropCredit {
+                            player_id: admin,
+                            amount: Amount::from_tokens(10),
+                        },
+                        AirdropCredit {
+                            player_id: admin,
+                            amount: Amount::from_tokens(15),
+                        },
+                    ],
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate)
+            .expect("Airdrop should succeed")
+            .token_balance,
+        Some(balance.saturating_add(Amount::from_tokens(25)))
+    );
+}
+
+/// Test that the treasury starts empty and can't be overspent
+#[tokio::test(flavor = "multi_thread")]
+async fn test_treasury_spend_errors() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut admin_chain = validator.new_chain().await;
+
+    let application_id = admin_chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    admin_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Admin".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = admin_chain
+        .graphql_query(
+            application_id,
+            "query { config { admin } treasury { balance totalInflow flows { source } } }",
+        )
+        .await;
+    assert_eq!(
+        response["treasury"]["balance"],
+        serde_json::json!(Amount::ZERO)
+    );
+    assert_eq!(
+        response["treasury"]["flows"].as_array().map(Vec::len),
+        Some(0)
+    );
+    let admin: AccountOwner = serde_json::from_value(response["config"]["admin"].clone())
+        .expect("Failed to decode admin");
+
+    let cases = [
+        (
+            admin,
+            Amount::from_tokens(1),
+            OperationError::InsufficientBalance,
+        ),
+        (
+            AccountOwner::CHAIN,
+            Amount::from_tokens(1),
+            OperationError::PlayerNotFound,
+        ),
+    ];
+    for (recipient, amount, error) in cases {
+        let certificate = admin_chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SpendTreasury {
+                        recipient,
+                        amount,
+                        memo: None,
+                    },
+                );
+            })
+            .await;
+        assert_eq!(operation_response!(certificate), Err(error));
+    }
+
+    // Only the admin spends from the treasury
+    let chain = validator.new_chain().await;
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::SpendTreasury {
+                    recipient: admin,
+                    amount: Amount::from_tokens(1),
+                    memo: Some("grant".to_string()),
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::NotAdmin)
+    );
+}
+
+/// Test that balance changes are journaled per player and the ledger balances
+#[tokio::test(flavor = "multi_thread")]
+async fn test_ledger_journal() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Admin".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { config { admin } }")
+        .await;
+    let admin: AccountOwner = serde_json::from_value(response["config"]["admin"].clone())
+        .expect("Failed to decode admin");
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::MintPoints {
+                    recipient: admin,
+                    amount: Amount::from_tokens(500),
+                },
+            );
+        })
+        .await;
+
+    // The first page holds the registration, its cursor leads to the mint
+    let query = format!(
+        "query {{ ledger(playerId: \"{}\", limit: 1) {{ \
+            entries {{ reason debit {{ kind }} credit {{ kind playerId }} }} nextCursor }} }}",
+        admin
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    let page = &response["ledger"];
+    assert_eq!(page["entries"][0]["reason"], "REGISTRATION");
+    assert_eq!(page["entries"][0]["debit"]["kind"], "ISSUANCE");
+    assert_eq!(page["entries"][0]["credit"]["kind"], "PLAYER");
+    assert_eq!(page["nextCursor"], 0);
+
+    let query = format!(
+        "query {{ ledger(playerId: \"{}\", after: 0) {{ entries {{ reason }} nextCursor }} }}",
+        admin
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    let page = &response["ledger"];
+    assert_eq!(page["entries"].as_array().map(Vec::len), Some(1));
+    assert_eq!(page["entries"][0]["reason"], "MINT");
+    assert!(page["nextCursor"].is_null());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { ledgerCheck { balanced totalDebits totalCredits mismatchedPlayers } }",
+        )
+        .await;
+    let check = &response["ledgerCheck"];
+    assert_eq!(check["balanced"], true);
+    assert_eq!(check["totalDebits"], check["totalCredits"]);
+    assert_eq!(check["mismatchedPlayers"].as_array().map(Vec::len), Some(0));
+}
+
+/// Test that the tracked supply matches a recount and only the admin reconciles it
+#[tokio::test(flavor = "multi_thread")]
+async fn test_supply_reconciliation() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut admin_chain = validator.new_chain().await;
+
+    let application_id = admin_chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    admin_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Admin".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = admin_chain
+        .graphql_query(
+            application_id,
+            "query { supplyReport { totalSupply actualSupply \
+                tracked { playerBalances treasury } drifted } }",
+        )
+        .await;
+    let report = &response["supplyReport"];
+    assert_eq!(report["totalSupply"], report["actualSupply"]);
+    assert_eq!(report["tracked"]["playerBalances"], "100.");
+    assert_eq!(report["tracked"]["treasury"], "0.");
+    assert_eq!(report["drifted"].as_array().map(Vec::len), Some(0));
+
+    let chain = validator.new_chain().await;
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(application_id, Operation::ReconcileSupply);
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::NotAdmin)
+    );
+
+    let certificate = admin_chain
+        .add_block(|block| {
+            block.with_operation(application_id, Operation::ReconcileSupply);
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+}
+
+/// Test that new players start without debt and debtors are listed by the service
+#[tokio::test(flavor = "multi_thread")]
+async fn test_player_debt_queries() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Player".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let player_id = AccountOwner::from(chain.public_key());
+    let query = format!(
+        "query {{ player(playerId: \"{}\") {{ debt }} debtors {{ id debt }} }}",
+        player_id
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["debt"], "0.");
+    assert_eq!(response["debtors"].as_array().map(Vec::len), Some(0));
+
+    // A player without debt can still predict
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
+                    outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(10),
+                },
+            );
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+}
+
+/// Test that a guild penalty the balance can't cover becomes debt, which blocks trading until a reward repays it
+#[tokio::test(flavor = "multi_thread")]
+async fn test_player_debt_repayment() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    // The admin founds a guild, and a member joins it and then gives away every point
+    let admin_key = chain.key_pair().copy();
+    let member_key = AccountSecretKey::generate();
+    let admin = sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    let guild_id = execute(
+        &chain,
+        application_id,
+        Operation::CreateGuild {
+            name: "Debtors".to_string(),
+        },
+    )
+    .await
+    .expect("Guild creation should succeed")
+    .guild_id
+    .expect("Receipt should carry the guild");
+
+    let member = sign_as(&mut chain, &member_key);
+    // Joining earns the 150 point Guild Member achievement on top of the 100 starting points
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::JoinGuild { guild_id },
+        Operation::TransferPoints {
+            to: admin,
+            amount: Amount::from_tokens(250),
+            memo: None,
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // The admin's wrong prediction costs the broke member a penalty they can't pay
+    sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::UpdateMarketPrice {
+            asset: "BTC".to_string(),
+            price: Amount::from_tokens(50000),
+        },
+        Operation::PredictDailyOutcome {
+            asset: "BTC".to_string(),
+            outcome: PriceOutcome::Fall,
+            stake: Amount::from_tokens(10),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+    let next_day = Timestamp::from(25 * 60 * 60 * 1_000_000);
+    validator.clock().set(next_day);
+    chain
+        .add_block(|block| {
+            block.with_timestamp(next_day).with_operation(
+                application_id,
+                Operation::UpdateMarketPrice {
+                    asset: "BTC".to_string(),
+                    price: Amount::from_tokens(55000),
+                },
+            );
+        })
+        .await;
+
+    let query = format!(
+        "query {{ player(playerId: \"{}\") {{ debt tokenBalance }} debtors {{ id debt }} }}",
+        member
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["debt"], "10.");
+    assert_eq!(response["player"]["tokenBalance"], "0.");
+    assert_eq!(response["debtors"][0]["id"], member.to_string());
+
+    // Taking on new risk is refused while the debt is owed
+    sign_as(&mut chain, &member_key);
+    let certificate = chain
+        .add_block(|block| {
+            block.with_timestamp(next_day).with_operation(
+                application_id,
+                Operation::BuyShares {
+                    market_id: 0,
+                    chain_id: None,
+                    outcome_id: None,
+                    amount: Amount::from_tokens(10),
+                    max_payment: None,
+                    min_received: None,
+                    deadline: None,
+                },
+            );
+        })
+        .await;
+    assert_eq!(
+        operation_response!(certificate),
+        Err(OperationError::InDebt)
+    );
+
+    // The next daily reward goes to the treasury until the debt is repaid
+    let certificate = chain
+        .add_block(|block| {
+            block
+                .with_timestamp(next_day)
+                .with_operation(application_id, Operation::ClaimDailyReward);
+        })
+        .await;
+    let receipt = operation_response!(certificate).expect("Daily reward should succeed");
+    assert_eq!(receipt.token_balance, Some(Amount::ZERO));
+
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["debt"], "0.");
+    assert_eq!(response["debtors"].as_array().map(Vec::len), Some(0));
+}
+
+/// Test that a wrong guild prediction costs the other members at most the period's minimum stake
+#[tokio::test(flavor = "multi_thread")]
+async fn test_guild_penalty_capped() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let member_key = AccountSecretKey::generate();
+    let admin = sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    let guild_id = execute(
+        &chain,
+        application_id,
+        Operation::CreateGuild {
+            name: "Stakers".to_string(),
+        },
+    )
+    .await
+    .expect("Guild creation should succeed")
+    .guild_id
+    .expect("Receipt should carry the guild");
+
+    let member = sign_as(&mut chain, &member_key);
+    for operation in [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::JoinGuild { guild_id },
+    ] {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // The admin stakes the daily maximum on the wrong outcome
+    sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::UpdateMarketPrice {
+            asset: "BTC".to_string(),
+            price: Amount::from_tokens(50000),
+        },
+        Operation::PredictDailyOutcome {
+            asset: "BTC".to_string(),
+            outcome: PriceOutcome::Fall,
+            stake: Amount::from_tokens(100),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+    let next_day = Timestamp::from(25 * 60 * 60 * 1_000_000);
+    validator.clock().set(next_day);
+    chain
+        .add_block(|block| {
+            block.with_timestamp(next_day).with_operation(
+                application_id,
+                Operation::UpdateMarketPrice {
+                    asset: "BTC".to_string(),
+                    price: Amount::from_tokens(55000),
+                },
+            );
+        })
+        .await;
+
+    // The admin only forfeits the stake, the member loses the 10 point daily minimum
+    // out of their starting points and Guild Member achievement
+    assert_eq!(
+        token_balance(&chain, application_id, admin).await,
+        Amount::ZERO
+    );
+    assert_eq!(
+        token_balance(&chain, application_id, member).await,
+        Amount::from_tokens(240)
+    );
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { debtors { id } }")
+        .await;
+    assert_eq!(response["debtors"].as_array().map(Vec::len), Some(0));
+}
+
+/// Test that prediction stakes are checked against the period's limits and escrowed
+#[tokio::test(flavor = "multi_thread")]
+async fn test_prediction_stake_limits() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Staker".to_string()),
+                },
+            );
+        })
+        .await;
+
+    // Daily stakes must fall between 10 and 100 points
+    for stake in [Amount::from_tokens(5), Amount::from_tokens(101)] {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::PredictDailyOutcome {
+                        asset: "BTC".to_string(),
+                        outcome: PriceOutcome::Rise,
+                        stake,
+                    },
+                );
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::InvalidStake)
+        );
+    }
+
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
+                    outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(40),
+                },
+            );
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+
+    // The stake is held out of the balance until the period resolves
+    let player_id = AccountOwner::from(chain.public_key());
+    let query = format!(
+        "query {{ player(playerId: \"{}\") {{ tokenBalance }} \
+            supplyReport {{ tracked {{ predictionStakes }} drifted }} }}",
+        player_id
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    assert_eq!(response["player"]["tokenBalance"], "60.");
+    assert_eq!(
+        response["supplyReport"]["tracked"]["predictionStakes"],
+        "40."
+    );
+    assert_eq!(
+        response["supplyReport"]["drifted"].as_array().map(Vec::len),
+        Some(0)
+    );
+}
+
+/// Test that parimutuel stakes are pooled by outcome and the service reports live odds
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parimutuel_prediction_pool() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        parimutuel_predictions: true,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Bettor".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let certificate = chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::PredictDailyOutcome {
+                    asset: "BTC".to_string(),
+                    outcome: PriceOutcome::Rise,
+                    stake: Amount::from_tokens(40),
+                },
+            );
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { predictionPool(asset: \"BTC\", period: DAILY) { parimutuel rakePercent riseStake predictions } \
+                poolOdds(asset: \"BTC\", period: DAILY) { outcome staked share payout } }",
+        )
+        .await;
+    let pool = &response["predictionPool"];
+    assert_eq!(pool["parimutuel"], true);
+    assert_eq!(pool["rakePercent"], 5);
+    assert_eq!(pool["riseStake"], "40.");
+    assert_eq!(pool["predictions"], 1);
+
+    // A lone stake holds the whole pool and gets it back less the 5% rake
+    let odds = &response["poolOdds"];
+    assert_eq!(odds[0]["outcome"], "RISE");
+    assert_eq!(odds[0]["share"], "1.");
+    assert_eq!(odds[0]["payout"], "0.95");
+    assert_eq!(odds[1]["staked"], "0.");
+    assert_eq!(odds[1]["payout"], "0.");
+}
+
+/// Test that a resolved parimutuel pool is split among the winners and refunded when nobody won
+#[tokio::test(flavor = "multi_thread")]
+async fn test_parimutuel_pool_resolution() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        parimutuel_predictions: true,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let admin = sign_as(&mut chain, &admin_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::UpdateMarketPrice {
+            asset: "BTC".to_string(),
+            price: Amount::from_tokens(50000),
+        },
+        Operation::UpdateMarketPrice {
+            asset: "ETH".to_string(),
+            price: Amount::from_tokens(3000),
+        },
+        Operation::PredictDailyOutcome {
+            asset: "BTC".to_string(),
+            outcome: PriceOutcome::Rise,
+            stake: Amount::from_tokens(60),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // BTC: 60 and 20 on Rise, 20 on Fall; ETH: nobody picks Rise
+    let rise_key = AccountSecretKey::generate();
+    let rise_bettor = sign_as(&mut chain, &rise_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::PredictDailyOutcome {
+            asset: "BTC".to_string(),
+            outcome: PriceOutcome::Rise,
+            stake: Amount::from_tokens(20),
+        },
+        Operation::PredictDailyOutcome {
+            asset: "ETH".to_string(),
+            outcome: PriceOutcome::Fall,
+            stake: Amount::from_tokens(10),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+    let fall_key = AccountSecretKey::generate();
+    let fall_bettor = sign_as(&mut chain, &fall_key);
+    let operations = [
+        Operation::RegisterPlayer { display_name: None },
+        Operation::PredictDailyOutcome {
+            asset: "BTC".to_string(),
+            outcome: PriceOutcome::Fall,
+            stake: Amount::from_tokens(20),
+        },
+        Operation::PredictDailyOutcome {
+            asset: "ETH".to_string(),
+            outcome: PriceOutcome::Neutral,
+            stake: Amount::from_tokens(10),
+        },
+    ];
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // Both prices rise by the next day
+    sign_as(&mut chain, &admin_key);
+    let next_day = Timestamp::from(25 * 60 * 60 * 1_000_000);
+    validator.clock().set(next_day);
+    for (asset, price) in [("BTC", 55000), ("ETH", 3300)] {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_timestamp(next_day).with_operation(
+                    application_id,
+                    Operation::UpdateMarketPrice {
+                        asset: asset.to_string(),
+                        price: Amount::from_tokens(price),
+                    },
+                );
+            })
+            .await;
+        assert!(operation_response!(certificate).is_ok());
+    }
+
+    // The Rise stakes split the 100 point BTC pool less its 5 point rake, 60:20;
+    // the ETH stakes are refunded
+    let expected = [
+        (admin, "111.25"),       // 40 left + 71.25
+        (rise_bettor, "103.75"), // 70 left + 23.75 + 10 refunded
+        (fall_bettor, "80."),    // 70 left + 10 refunded
+    ];
+    for (player_id, balance) in expected {
+        assert_eq!(
+            token_balance(&chain, application_id, player_id).await,
+            balance.parse::<Amount>().expect("Invalid amount")
+        );
+    }
+
+    // Only the rake reaches the treasury, and no points were minted
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { treasury { balance } totalSupply \
+                predictionPool(asset: \"BTC\", period: DAILY, periodStart: 0) { settled paidOut } }",
+        )
+        .await;
+    assert_eq!(response["treasury"]["balance"], "5.");
+    assert_eq!(response["totalSupply"], "300.");
+    assert_eq!(response["predictionPool"]["settled"], 3);
+    assert_eq!(response["predictionPool"]["paidOut"], "100.");
+}
+
+/// Test that oracle markets resolve themselves from their period's prices
+#[tokio::test(flavor = "multi_thread")]
+async fn test_oracle_market_resolution() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    // One market bets on BTC rising today, the other on it falling
+    let admin_key = chain.key_pair().copy();
+    sign_as(&mut chain, &admin_key);
+    let mut operations = vec![
+        Operation::RegisterPlayer { display_name: None },
+        Operation::UpdateMarketPrice {
+            asset: "BTC".to_string(),
+            price: Amount::from_tokens(50000),
+        },
+    ];
+    for outcome in [PriceOutcome::Rise, PriceOutcome::Fall] {
+        operations.push(Operation::CreateMarket {
+            title: "BTC today".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 0,
+            outcomes: Vec::new(),
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: Some(PriceCondition {
+                asset: "BTC".to_string(),
+                period: PredictionPeriod::Daily,
+                outcome,
+            }),
+        });
+    }
+    for operation in operations {
+        assert!(execute(&chain, application_id, operation).await.is_ok());
+    }
+
+    // The trader backs "Yes" in both
+    let trader_key = AccountSecretKey::generate();
+    let trader = sign_as(&mut chain, &trader_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    for market_id in [0, 1] {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::BuyShares {
+                market_id,
+                chain_id: None,
+                outcome_id: Some(0),
+                amount: Amount::from_tokens(2),
+                max_payment: None,
+                min_received: None,
+                deadline: None,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    let trader_balance = token_balance(&chain, application_id, trader).await;
+
+    // Not even the admin resolves an oracle market by hand
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::ResolveMarket {
+            market_id: 0,
+            winning_outcome: Some(0),
+        },
+    )
+    .await;
+    assert_eq!(response, Err(OperationError::OracleMarket));
+
+    // The first price after the day ends resolves both markets
+    validator
+        .clock()
+        .set(Timestamp::from(25 * 60 * 60 * 1_000_000));
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::UpdateMarketPrice {
+            asset: "BTC".to_string(),
+            price: Amount::from_tokens(55000),
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { rise: market(marketId: 0) { status winningOutcome } \
+                fall: market(marketId: 1) { status winningOutcome } }",
+        )
+        .await;
+    assert_eq!(response["rise"]["status"], "RESOLVED");
+    assert_eq!(response["rise"]["winningOutcome"], 0);
+    assert_eq!(response["fall"]["status"], "RESOLVED");
+    assert_eq!(response["fall"]["winningOutcome"], 1);
+
+    // Only the "Yes" shares of the market that came true pay out
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance.saturating_add(Amount::from_tokens(2))
+    );
+}
+
+/// Test that each listed asset takes its own prediction within the same period
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_asset_predictions() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Trader".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let predict = |asset: &str| Operation::PredictDailyOutcome {
+        asset: asset.to_string(),
+        outcome: PriceOutcome::Rise,
+        stake: Amount::from_tokens(10),
+    };
+    let cases = [
+        ("BTC", Ok(())),
+        ("ETH", Ok(())),
+        ("BTC", Err(OperationError::InvalidOutcome)), // Already predicted for this period
+        ("DOGE", Err(OperationError::UnknownAsset)),
+    ];
+    for (asset, expected) in cases {
+        let operation = predict(asset);
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(operation_response!(certificate).map(|_| ()), expected);
+    }
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { btc: predictionPool(asset: \"BTC\", period: DAILY) { asset riseStake } \
+                eth: predictionPool(asset: \"ETH\", period: DAILY) { asset riseStake } \
+                sol: predictionPool(asset: \"SOL\", period: DAILY) { asset } }",
+        )
+        .await;
+    assert_eq!(response["btc"]["asset"], "BTC");
+    assert_eq!(response["btc"]["riseStake"], "10.");
+    assert_eq!(response["eth"]["riseStake"], "10.");
+    assert!(response["sol"].is_null());
+}
+
+// ============================================================================
+// Dispute Tests
+// ============================================================================
+
+/// Test that dispute operations are rejected without a pending resolution
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dispute_errors() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        dispute_period: 60 * 60 * 1_000_000,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Juror".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let cases = [
+        (
+            Operation::ChallengeResolution {
+                market_id: 42,
+                outcome_id: 1,
+            },
+            OperationError::MarketNotFound,
+        ),
+        (
+            Operation::VoteOnDispute {
+                market_id: 42,
+                uphold: true,
+            },
+            OperationError::MarketNotFound,
+        ),
+        (
+            Operation::SettleDispute {
+                market_id: 42,
+                uphold: false,
+            },
+            OperationError::MarketNotFound,
+        ),
+    ];
+    for (operation, error) in cases {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(operation_response!(certificate), Err(error));
+    }
+}
+
+/// Test a challenged resolution settled by vote after the dispute period, and one settled by the admin
+#[tokio::test(flavor = "multi_thread")]
+async fn test_dispute_resolution() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let dispute_period = 60 * 60 * 1_000_000;
+    let config = GameConfig {
+        dispute_period,
+        challenge_bond: Amount::from_tokens(10),
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let creator_key = AccountSecretKey::generate();
+    let trader_key = AccountSecretKey::generate();
+    let voter_key = AccountSecretKey::generate();
+    for key_pair in [&admin_key, &creator_key, &trader_key, &voter_key] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::RegisterPlayer { display_name: None },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    // The creator opens two Yes/No markets
+    let creator = sign_as(&mut chain, &creator_key);
+    for _ in 0..2 {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::CreateMarket {
+                title: "Will it rain?".to_string(),
+                amount: Amount::from_tokens(20),
+                fee_percent: 0,
+                outcomes: vec!["Yes".to_string(), "No".to_string()],
+                market_type: MarketType::Prediction,
+                close_time: None,
+                resolution_time: None,
+                oracle: None,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    // The trader backs "No" in both markets, the voter backs "Yes" in the first
+    let trader = sign_as(&mut chain, &trader_key);
+    for market_id in [0, 1] {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::BuyShares {
+                market_id,
+                chain_id: None,
+                outcome_id: Some(1),
+                amount: Amount::from_tokens(5),
+                max_payment: None,
+                min_received: None,
+                deadline: None,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    let voter = sign_as(&mut chain, &voter_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::BuyShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: Some(0),
+            amount: Amount::from_tokens(5),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    // The creator proposes "Yes" in both markets, posting a bond each time
+    sign_as(&mut chain, &creator_key);
+    let creator_balance = token_balance(&chain, application_id, creator).await;
+    for market_id in [0, 1] {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::ResolveMarket {
+                market_id,
+                winning_outcome: Some(0),
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    assert_eq!(
+        token_balance(&chain, application_id, creator).await,
+        creator_balance.saturating_sub(Amount::from_tokens(20))
+    );
+
+    // The trader challenges both proposals, posting a bond each time
+    sign_as(&mut chain, &trader_key);
+    let trader_balance = token_balance(&chain, application_id, trader).await;
+    for market_id in [0, 1] {
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::ChallengeResolution {
+                market_id,
+                outcome_id: 1,
+            },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+    let trader_balance = trader_balance.saturating_sub(Amount::from_tokens(20));
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance
+    );
+
+    // The voter backs the proposal in the first market
+    sign_as(&mut chain, &voter_key);
+    let voter_balance = token_balance(&chain, application_id, voter).await;
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::VoteOnDispute {
+            market_id: 0,
+            uphold: true,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::VoteOnDispute {
+            market_id: 0,
+            uphold: true,
+        },
+    )
+    .await;
+    assert_eq!(response, Err(OperationError::AlreadyVoted));
+
+    // The admin overturns the second proposal: the trader's "No" shares pay out
+    // and the trader takes back their bond along with the creator's
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::SettleDispute {
+            market_id: 1,
+            uphold: false,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    let trader_balance = trader_balance.saturating_add(Amount::from_tokens(25));
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance
+    );
+
+    // The first market stays disputed until voting ends
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { market(marketId: 0) { status } }")
+        .await;
+    assert_eq!(response["market"]["status"], "DISPUTED");
+
+    // Once it has ended, the next operation upholds the proposal by vote:
+    // the voter's "Yes" shares pay out and the creator takes both bonds
+    let after_voting = Timestamp::from(2 * dispute_period);
+    validator.clock().set(after_voting);
+    chain
+        .add_block(|block| {
+            block.with_timestamp(after_voting).with_operation(
+                application_id,
+                Operation::UpdateProfile {
+                    display_name: Some("Admin".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { market(marketId: 0) { status } }")
+        .await;
+    assert_eq!(response["market"]["status"], "RESOLVED");
+    assert_eq!(
+        token_balance(&chain, application_id, voter).await,
+        voter_balance.saturating_add(Amount::from_tokens(5))
+    );
+    assert_eq!(
+        token_balance(&chain, application_id, trader).await,
+        trader_balance
+    );
+
+    let query = format!(
+        "query {{ ledger(playerId: \"{}\", limit: 100) {{ \
+            entries {{ reason amount credit {{ kind }} }} }} }}",
+        creator
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &query).await;
+    let bonds_won = response["ledger"]["entries"]
+        .as_array()
+        .expect("Ledger entries should be listed")
+        .iter()
+        .filter(|entry| entry["reason"] == "DISPUTE_BOND" && entry["credit"]["kind"] == "PLAYER")
+        .map(|entry| entry["amount"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(bonds_won, vec!["20."]);
+}
+
+// ============================================================================
+// Liquidity Tests
+// ============================================================================
+
+/// Test that liquidity changes are rejected on a missing market
+#[tokio::test(flavor = "multi_thread")]
+async fn test_liquidity_nonexistent_market() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let application_id = chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::RegisterPlayer {
+                    display_name: Some("Creator".to_string()),
+                },
+            );
+        })
+        .await;
+
+    let operations = [
+        Operation::AddLiquidity {
+            market_id: 42,
+            amount: Amount::from_tokens(100),
+        },
+        Operation::WithdrawLiquidity {
+            market_id: 42,
+            shares: Amount::from_tokens(100),
+        },
+    ];
+    for operation in operations {
+        let certificate = chain
+            .add_block(|block| {
+                block.with_operation(application_id, operation);
+            })
+            .await;
+        assert_eq!(
+            operation_response!(certificate),
+            Err(OperationError::MarketNotFound)
+        );
+    }
+}
+
+/// Test that a provider's LP shares earn their part of trading fees and redeem for their part of the pool
+#[tokio::test(flavor = "multi_thread")]
+async fn test_liquidity_provision_lifecycle() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = chain.key_pair().copy();
+    let provider_key = AccountSecretKey::generate();
+    let trader_key = AccountSecretKey::generate();
+    for key_pair in [&admin_key, &provider_key, &trader_key] {
+        sign_as(&mut chain, key_pair);
+        let response = execute(
+            &chain,
+            application_id,
+            Operation::RegisterPlayer { display_name: None },
+        )
+        .await;
+        assert!(response.is_ok());
+    }
+
+    // The creator's 20 points make the first 20 LP shares
+    sign_as(&mut chain, &admin_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 10,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    // Depositing as much again mints as many shares
+    let provider = sign_as(&mut chain, &provider_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::AddLiquidity {
+            market_id: 0,
+            amount: Amount::from_tokens(20),
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    let positions_query = format!(
+        "query {{ liquidityPositions(playerId: \"{}\") {{ marketId shares deposited earned }} }}",
+        provider
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &positions_query).await;
+    let position = &response["liquidityPositions"][0];
+    assert_eq!(position["marketId"], 0);
+    assert_eq!(position["shares"], "20.");
+    assert_eq!(position["deposited"], "20.");
+    let provider_balance = token_balance(&chain, application_id, provider).await;
+    assert_eq!(provider_balance, Amount::from_tokens(80));
+
+    // The provider holds half the shares, so earns half of the fee left after the platform's cut
+    sign_as(&mut chain, &trader_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::BuyShares {
+            market_id: 0,
+            chain_id: None,
+            outcome_id: Some(0),
+            amount: Amount::from_tokens(2),
+            max_payment: None,
+            min_received: None,
+            deadline: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { trades(marketId: 0) { trades { total fee } } }",
+        )
+        .await;
+    let decode = |value: &serde_json::Value| -> u128 {
+        serde_json::from_value::<Amount>(value.clone())
+            .expect("Failed to decode amount")
+            .into()
+    };
+    let cost = decode(&response["trades"]["trades"][0]["total"]);
+    let fee = decode(&response["trades"]["trades"][0]["fee"]);
+    let providers_share = fee - fee * 2 / 100;
+    let earned = Amount::from_attos(providers_share / 2);
+    assert_eq!(
+        token_balance(&chain, application_id, provider).await,
+        provider_balance.saturating_add(earned)
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &positions_query).await;
+    assert_eq!(
+        serde_json::from_value::<Amount>(response["liquidityPositions"][0]["earned"].clone())
+            .expect("Failed to decode amount"),
+        earned
+    );
+
+    // Half of the provider's shares redeem a quarter of the pool, which holds the trade's cost
+    let provider_balance = token_balance(&chain, application_id, provider).await;
+    sign_as(&mut chain, &provider_key);
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::WithdrawLiquidity {
+            market_id: 0,
+            shares: Amount::from_tokens(10),
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    let redeemed = Amount::from_attos((40 * 10u128.pow(18) + cost) / 4);
+    assert_eq!(
+        token_balance(&chain, application_id, provider).await,
+        provider_balance.saturating_add(redeemed)
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &positions_query).await;
+    assert_eq!(response["liquidityPositions"][0]["shares"], "10.");
+
+    // The rest would dip into the liquidity that backs the trader's shares
+    let response = execute(
+        &chain,
+        application_id,
+        Operation::WithdrawLiquidity {
+            market_id: 0,
+            shares: Amount::from_tokens(10),
+        },
+    )
+    .await;
+    assert_eq!(response, Err(OperationError::InsufficientBalance));
+}
+
+// ============================================================================
+// Cross-Chain Tests
+// ============================================================================
+
+/// Test that a player trades a market hosted on another chain, with balances kept on both chains
+#[tokio::test(flavor = "multi_thread")]
+async fn test_remote_trade_across_chains() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut host_chain = validator.new_chain().await;
+    let mut home_chain = validator.new_chain().await;
+
+    let config = GameConfig {
+        min_creator_level: 1,
+        min_creator_balance: Amount::ZERO,
+        market_creation_cost: Amount::ZERO,
+        ..GameConfig::default()
+    };
+    let application_id = host_chain
+        .create_application(module_id, (), config, vec![])
+        .await;
+
+    let admin_key = host_chain.key_pair().copy();
+    sign_as(&mut host_chain, &admin_key);
+    let response = execute(
+        &host_chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+
+    // The trader only registers on the home chain, which subscribes it to the host chain
+    let trader_key = AccountSecretKey::generate();
+    let trader = sign_as(&mut home_chain, &trader_key);
+    let response = execute(
+        &home_chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    host_chain.handle_received_messages().await;
+
+    let response = execute(
+        &host_chain,
+        application_id,
+        Operation::CreateMarket {
+            title: "Will it rain?".to_string(),
+            amount: Amount::from_tokens(20),
+            fee_percent: 10,
+            outcomes: vec!["Yes".to_string(), "No".to_string()],
+            market_type: MarketType::Prediction,
+            close_time: None,
+            resolution_time: None,
+            oracle: None,
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    home_chain.handle_received_messages().await;
+
+    let buy = |market_id, chain_id| Operation::BuyShares {
+        market_id,
+        chain_id,
+        outcome_id: Some(0),
+        amount: Amount::from_tokens(2),
+        max_payment: Some(Amount::from_tokens(10)),
+        min_received: None,
+        deadline: None,
+    };
+
+    // Market IDs are per chain: the home chain has no market 0 of its own, nor a market 7 on the host
+    let host = host_chain.id();
+    for (market_id, chain_id) in [(0, None), (0, Some(home_chain.id())), (7, Some(host))] {
+        let response = execute(&home_chain, application_id, buy(market_id, chain_id)).await;
+        assert_eq!(response, Err(OperationError::MarketNotFound));
+    }
+
+    // The escrow leaves the home chain at once, and what the trade left of it comes back at settlement
+    let response = execute(&home_chain, application_id, buy(0, Some(host))).await;
+    assert!(response.is_ok());
+    assert_eq!(
+        token_balance(&home_chain, application_id, trader).await,
+        Amount::from_tokens(90)
+    );
+    host_chain.handle_received_messages().await;
+    home_chain.handle_received_messages().await;
+
+    let trades_query = format!(
+        "query {{ remoteTrades(playerId: \"{}\") {{ status refund }} }}",
+        trader
+    );
+    let QueryOutcome { response, .. } = home_chain
+        .graphql_query(application_id, &trades_query)
+        .await;
+    let trade = &response["remoteTrades"][0];
+    assert_eq!(trade["status"], "SETTLED");
+    let refund: Amount =
+        serde_json::from_value(trade["refund"].clone()).expect("Failed to decode refund");
+    assert!(refund > Amount::ZERO);
+    let settled_balance = token_balance(&home_chain, application_id, trader).await;
+    assert_eq!(
+        settled_balance,
+        Amount::from_tokens(90).saturating_add(refund)
+    );
+
+    // The host chain keeps the shares in a hosted account, not among its registered players
+    let host_query = format!(
+        "query {{ hostedAccount(playerId: \"{}\") {{ tokenBalance }} \
+         market(marketId: 0) {{ positions }} \
+         ledgerCheck {{ balanced }} }}",
+        trader
+    );
+    let QueryOutcome { response, .. } = host_chain.graphql_query(application_id, &host_query).await;
+    assert_eq!(response["hostedAccount"]["tokenBalance"], "0.");
+    assert_eq!(
+        response["market"]["positions"][trader.to_string()]["shares_by_outcome"]["0"],
+        "2."
+    );
+    assert_eq!(response["ledgerCheck"]["balanced"], true);
+    sign_as(&mut host_chain, &trader_key);
+    let response = execute(&host_chain, application_id, Operation::ClaimDailyReward).await;
+    assert_eq!(response, Err(OperationError::PlayerNotFound));
+
+    // A sell the hosting chain refuses leaves the hosted account as it was, still not a player
+    let sell = Operation::SellShares {
+        market_id: 0,
+        chain_id: Some(host),
+        outcome_id: Some(0),
+        amount: Amount::from_tokens(5),
+        max_payment: None,
+        min_received: None,
+        deadline: None,
+    };
+    let response = execute(&home_chain, application_id, sell).await;
+    assert!(response.is_ok());
+    host_chain.handle_received_messages().await;
+    home_chain.handle_received_messages().await;
+    let QueryOutcome { response, .. } = home_chain
+        .graphql_query(application_id, &trades_query)
+        .await;
+    assert_eq!(response["remoteTrades"][1]["status"], "REJECTED");
+    assert_eq!(
+        token_balance(&home_chain, application_id, trader).await,
+        settled_balance
+    );
+    let QueryOutcome { response, .. } = host_chain.graphql_query(application_id, &host_query).await;
+    assert_eq!(response["hostedAccount"]["tokenBalance"], "0.");
+    assert_eq!(
+        response["market"]["positions"][trader.to_string()]["shares_by_outcome"]["0"],
+        "2."
+    );
+    assert_eq!(response["ledgerCheck"]["balanced"], true);
+    let response = execute(&host_chain, application_id, Operation::ClaimDailyReward).await;
+    assert_eq!(response, Err(OperationError::PlayerNotFound));
+
+    // Resolution pays the winning shares into the hosted account, which sends them home
+    sign_as(&mut host_chain, &admin_key);
+    let response = execute(
+        &host_chain,
+        application_id,
+        Operation::ResolveMarket {
+            market_id: 0,
+            winning_outcome: Some(0),
+        },
+    )
+    .await;
+    assert!(response.is_ok());
+    home_chain.handle_received_messages().await;
+    assert_eq!(
+        token_balance(&home_chain, application_id, trader).await,
+        settled_balance.saturating_add(Amount::from_tokens(2))
+    );
+
+    for chain in [&host_chain, &home_chain] {
+        let QueryOutcome { response, .. } = chain
+            .graphql_query(application_id, "query { ledgerCheck { balanced } }")
+            .await;
+        assert_eq!(response["ledgerCheck"]["balanced"], true);
+    }
+}
+
+/// Test that a transfer refused by the recipient's chain bounces back to the sender
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bounced_transfer_refunded() {
+    let (validator, module_id) = TestValidator::with_current_module::<
+        predictive_manager::PredictiveManagerAbi,
+        (),
+        GameConfig,
+    >()
+    .await;
+    let mut sender_chain = validator.new_chain().await;
+    let mut recipient_chain = validator.new_chain().await;
+
+    let application_id = sender_chain
+        .create_application(module_id, (), GameConfig::default(), vec![])
+        .await;
+
+    let sender_key = sender_chain.key_pair().copy();
+    let sender = sign_as(&mut sender_chain, &sender_key);
+    let response = execute(
+        &sender_chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    let recipient_key = AccountSecretKey::generate();
+    let recipient = sign_as(&mut recipient_chain, &recipient_key);
+    let response = execute(
+        &recipient_chain,
+        application_id,
+        Operation::RegisterPlayer { display_name: None },
+    )
+    .await;
+    assert!(response.is_ok());
+    sender_chain.handle_received_messages().await;
+
+    let sender_balance = token_balance(&sender_chain, application_id, sender).await;
+    let certificate = sender_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::TransferPoints {
+                    to: recipient,
+                    amount: Amount::from_tokens(25),
+                    memo: None,
+                },
+            );
+        })
+        .await;
+    assert!(operation_response!(certificate).is_ok());
+    assert_eq!(
+        token_balance(&sender_chain, application_id, sender).await,
+        sender_balance.saturating_sub(Amount::from_tokens(25))
+    );
+
+    // The recipient's chain rejects the credit, and the bounced message refunds the sender
+    recipient_chain
+        .add_block(|block| {
+            block.with_messages_from_by_action(&certificate, MessageAction::Reject);
+        })
+        .await;
+    sender_chain.handle_received_messages().await;
+    assert_eq!(
+        token_balance(&sender_chain, application_id, sender).await,
+        sender_balance
+    );
+    assert_eq!(
+        token_balance(&recipient_chain, application_id, recipient).await,
+        Amount::from_tokens(100)
+    );
+
+    let QueryOutcome { response, .. } = sender_chain
+        .graphql_query(application_id, "query { ledgerCheck { balanced } }")
+        .await;
+    assert_eq!(response["ledgerCheck"]["balanced"], true);
+}